@@ -1,10 +1,15 @@
 #![deny(missing_docs)]
 
-use std::path::PathBuf;
+use std::{num::NonZeroUsize, path::PathBuf};
 
 use clap::{Parser, ValueHint};
 use clap_complete::Shell;
-use ferrallay::{DEFAULT_MINECRAFT_DIR, config::ModLoader};
+use ferrallay::{
+    DEFAULT_MINECRAFT_DIR,
+    config::{GameVersionPolicy, ModLoader, VersionConstraint},
+};
+use size::Size;
+use url::Url;
 
 #[derive(Parser)]
 #[command(author, version, about)]
@@ -17,7 +22,8 @@ pub struct Allaerris {
     #[arg(long, short)]
     pub threads: Option<usize>,
     /// Set a GitHub personal access token for increasing the GitHub API rate
-    /// limit. You can also use the environment variable `GITHUB_TOKEN`.
+    /// limit and enabling batched lookups. You can also use the environment
+    /// variable `GITHUB_TOKEN`.
     #[arg(long, visible_alias = "gh")]
     pub github_token: Option<String>,
     /// Set a custom CurseForge API key.
@@ -30,6 +36,32 @@ pub struct Allaerris {
     #[arg(long, short, visible_aliases = ["config", "conf"])]
     #[arg(value_hint(ValueHint::FilePath))]
     pub config_file: Option<PathBuf>,
+    /// Never access the network. Only previously cached metadata and files
+    /// will be used, and anything that isn't cached will fail.
+    #[arg(long, global = true)]
+    pub offline: bool,
+    /// Set the maximum number of files downloaded at once.
+    /// Overrides `downloads.max_concurrent` in the config file.
+    #[arg(long, global = true, value_name = "COUNT")]
+    pub max_downloads: Option<NonZeroUsize>,
+    /// Set the maximum number of files hashed at once.
+    /// Overrides `downloads.max_hashing` in the config file.
+    #[arg(long, global = true, value_name = "COUNT")]
+    pub max_hashing: Option<NonZeroUsize>,
+    /// Limit the combined speed of all downloads per second, e.g. `2MiB`.
+    /// Overrides `downloads.max_speed` in the config file.
+    #[arg(long, global = true, value_name = "SIZE")]
+    pub limit_rate: Option<Size>,
+    /// Send every download through this proxy, e.g. `http://proxy.local:8080`.
+    /// Overrides `downloads.proxy` in the config file, which otherwise defaults
+    /// to the `HTTP_PROXY` and `HTTPS_PROXY` environment variables.
+    #[arg(long, global = true, value_name = "URL")]
+    pub proxy: Option<Url>,
+    /// Trust the root certificates in this PEM file for downloads, in addition
+    /// to `downloads.ca_certs` in the config file. Can be used multiple times.
+    #[arg(long, global = true, value_name = "FILE")]
+    #[arg(value_hint(ValueHint::FilePath))]
+    pub ca_cert: Vec<PathBuf>,
 }
 
 #[derive(clap::Subcommand)]
@@ -78,6 +110,12 @@ pub enum ModsSubcommand {
         /// the top of the right sidebar under 'About Project'.
         /// The GitHub identifier is the repository's full name, e.g.
         /// `mgziminsky/allaerris`.
+        /// Any `http(s)://` url is added as a file downloaded directly from
+        /// that url.
+        /// Maven artifacts are identified by their coordinates and repository,
+        /// e.g. `com.example:cool-mod[:classifier]@https://maven.example.com`.
+        /// Files under the configured `local_root` directory are added with
+        /// `local:<path>`, where the path may contain `*` and `?` wildcards.
         ids: Vec<String>,
 
         /// Prevent the mod(s) from being installed
@@ -86,6 +124,80 @@ pub enum ModsSubcommand {
         /// a modpack
         #[arg(short = 'x', long)]
         exclude: bool,
+
+        /// The expected sha1 of the file when adding a single url
+        #[arg(long)]
+        sha1: Option<String>,
+
+        /// The expected size in bytes of the file when adding a single url
+        #[arg(long)]
+        size: Option<u64>,
+
+        /// The least stable release channel to install the mod(s) from,
+        /// overriding the profile's channel
+        #[arg(value_enum, long)]
+        channel: Option<Channel>,
+
+        /// Which Minecraft versions the mod(s) can be installed for when there
+        /// is no version for the profile's, overriding the profile's policy.
+        /// Either `exact`, `minor` for any in the same minor line, or a comma
+        /// separated list of versions
+        #[arg(long)]
+        compat: Option<GameVersionPolicy>,
+
+        /// Limit which versions of the mod(s) can be installed. A comma
+        /// separated list of rules that must all match: a version pattern like
+        /// `0.5.*`, `^<version>` for compatible versions by Cargo's caret
+        /// rules, `>=<version>`, or `until:<YYYY-MM-DD>`
+        #[arg(long)]
+        constraint: Option<VersionConstraint>,
+    },
+
+    /// Search for projects and pick which to add to the active profile
+    ///
+    /// By default, results are filtered to the profile's Minecraft version
+    /// and mod loader
+    #[command(visible_aliases = ["find"])]
+    Search {
+        /// The text to search for
+        #[arg(required = true)]
+        query: Vec<String>,
+
+        /// Only show projects of this type
+        #[arg(value_enum, long = "type", short = 't')]
+        project_type: Option<SearchType>,
+
+        /// The Minecraft version to filter by [default: active profile]
+        #[arg(long, short = 'v')]
+        game_version: Option<String>,
+
+        /// The mod loader to filter by [default: active profile]
+        #[arg(value_enum, long, short)]
+        loader: Option<ModLoader>,
+
+        /// Don't filter by the profile's Minecraft version and mod loader
+        #[arg(long, short, conflicts_with_all = ["game_version", "loader"])]
+        any: bool,
+
+        /// Only show projects in this category
+        #[arg(long, short)]
+        category: Option<String>,
+
+        /// How to sort the results
+        #[arg(value_enum, long, short, default_value_t)]
+        sort: SearchSort,
+
+        /// The max number of results to fetch from each source
+        #[arg(long, short = 'n', default_value_t = 10)]
+        limit: u8,
+
+        /// The page of results to show, starting from 1
+        #[arg(long, short, default_value_t = 1)]
+        page: u32,
+
+        /// Prevent the picked mod(s) from being installed
+        #[arg(short = 'x', long)]
+        exclude: bool,
     },
 
     /// Remove mods and/or repositories from the profile.
@@ -111,6 +223,22 @@ pub enum ModsSubcommand {
         markdown: bool,
     },
 
+    /// List mods with available updates without marking them to be installed
+    ///
+    /// Exits with code 10 if any updates are available
+    Outdated {
+        /// Only check mods with the specified id(s)
+        ids: Vec<String>,
+
+        /// Output the updates as JSON
+        #[arg(long, short)]
+        json: bool,
+
+        /// Check every profile instead of only the active one
+        #[arg(long, short)]
+        all: bool,
+    },
+
     /// Lock the specified mods to their installed version
     Lock {
         /// IDs of mods to lock
@@ -144,6 +272,25 @@ pub enum ModsSubcommand {
         all: bool,
     },
 
+    /// Limit which versions the specified mods can be installed or updated to
+    Constrain {
+        /// A comma separated list of rules that must all match: a version
+        /// pattern like `0.5.*`, `^<version>` for compatible versions by
+        /// Cargo's caret rules, `>=<version>`, or `until:<YYYY-MM-DD>`
+        constraint: VersionConstraint,
+
+        /// IDs of mods to constrain
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+
+    /// Remove the version constraint from the specified mods
+    Unconstrain {
+        /// IDs of mods to unconstrain
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+
     #[command(flatten)]
     Mgmt(MgmtCommand),
 }
@@ -163,6 +310,21 @@ pub enum MgmtCommand {
         /// Don't use cache and install files directly to profile
         #[arg(long)]
         no_cache: bool,
+
+        /// Only show what would be downloaded, installed, and deleted without
+        /// changing the profile
+        #[arg(long, short = 'n')]
+        dry_run: bool,
+
+        /// Install whatever succeeds instead of leaving the profile unchanged
+        /// when any file fails to install or any mod can't be resolved
+        #[arg(long, conflicts_with = "dry_run")]
+        allow_partial: bool,
+
+        /// Install exactly the versions and files in the lockfile, failing if
+        /// the profile doesn't match it
+        #[arg(long)]
+        frozen: bool,
     },
 
     /// Mark outdated mods in the active profile to be updated by the next call
@@ -185,6 +347,15 @@ pub enum MgmtCommand {
         /// Immediatly apply any updates. Revert will not be possible
         #[arg(long, short, conflicts_with = "revert")]
         apply: bool,
+
+        /// Show the changelogs of all versions between the installed and
+        /// updated versions
+        #[arg(long, short = 'l', conflicts_with = "revert")]
+        changelog: bool,
+
+        /// Page through the changelogs with `$PAGER`, or `less` if not set
+        #[arg(long, conflicts_with = "revert")]
+        page: bool,
     },
 
     /// Attempt to lookup all unknown files non-recursively in the profile
@@ -249,7 +420,8 @@ pub enum ProfileSubcommand {
         #[arg(long, short)]
         switch_to: Option<String>,
     },
-    /// Configure the current profile's name, Minecraft version, and mod loader
+    /// Configure the current profile's name, Minecraft version, mod loader,
+    /// release channel, Minecraft version compatibility, and install strategy
     #[command(visible_aliases = ["configure", "config", "conf"])]
     Edit {
         /// The Minecraft version to use
@@ -258,6 +430,17 @@ pub enum ProfileSubcommand {
         /// The mod loader to use
         #[arg(value_enum, long, short)]
         loader: Option<ModLoader>,
+        /// The least stable release channel to install versions from
+        #[arg(value_enum, long)]
+        channel: Option<Channel>,
+        /// Which Minecraft versions mods can be installed for when there is no
+        /// version for the profile's. Either `exact`, `minor` for any in the
+        /// same minor line, or a comma separated list of versions
+        #[arg(long)]
+        compat: Option<GameVersionPolicy>,
+        /// How mods downloaded to the cache are installed into the profile
+        #[arg(value_enum, long)]
+        install: Option<Strategy>,
         /// The name of the profile
         #[arg(long, short)]
         name: Option<String>,
@@ -306,7 +489,56 @@ pub enum ModpackSubcommand {
     },
 }
 
-#[derive(clap::Subcommand, Default, Clone, Copy)]
+/// The type of project to search for
+#[derive(clap::ValueEnum, Clone, Copy)]
+pub enum SearchType {
+    Mod,
+    #[value(alias = "pack")]
+    ModPack,
+    ResourcePack,
+    DataPack,
+    Shader,
+}
+
+/// How stable a version must be to be installed
+#[derive(clap::ValueEnum, Clone, Copy)]
+pub enum Channel {
+    /// Only full releases
+    Release,
+    /// Releases and betas
+    Beta,
+    /// Any version
+    Alpha,
+}
+
+/// How mods downloaded to the cache are installed into a profile. Falls back to
+/// copying when the filesystem doesn't support it
+#[derive(clap::ValueEnum, Clone, Copy)]
+pub enum Strategy {
+    /// Copy the files
+    Copy,
+    /// Hard link the files, sharing their contents with the cache
+    Hardlink,
+    /// Symbolic link the files to the cache
+    Symlink,
+    /// Copy-on-write clone the files
+    Reflink,
+    /// Use the `install_strategy` from the config file
+    Default,
+}
+
+/// The search result order
+#[derive(clap::ValueEnum, Default, Clone, Copy)]
+pub enum SearchSort {
+    #[default]
+    Relevance,
+    Downloads,
+    Follows,
+    Newest,
+    Updated,
+}
+
+#[derive(clap::Subcommand, Default, Clone)]
 pub enum CacheSubcommand {
     /// Show count and total size of cached mod files
     #[default]
@@ -314,6 +546,63 @@ pub enum CacheSubcommand {
 
     /// Delete all files in the cache
     Clear,
+
+    /// Delete cached versions that aren't locked by any profile
+    #[command(visible_alias = "clean")]
+    Gc {
+        /// The number of unlocked versions of each project to keep, most
+        /// recently used first, so they can still be rolled back to
+        #[arg(long, short, default_value_t = 0)]
+        keep: usize,
+        /// Only delete versions that haven't been used in this many days
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u64>,
+        /// Delete the least recently used unlocked versions until the cache is
+        /// no larger than this size, e.g. `2GiB`. This includes versions kept
+        /// by the other options
+        #[arg(long, value_name = "SIZE")]
+        max_size: Option<Size>,
+        /// Show what would be deleted without deleting anything
+        #[arg(long, short = 'n')]
+        dry_run: bool,
+    },
+
+    /// Check cached files against the sha1 locked by profiles or provided by
+    /// their source, and report any that are corrupted
+    Verify {
+        /// Move corrupted files to a `quarantine` directory in the cache
+        #[arg(long, short, conflicts_with = "delete")]
+        quarantine: bool,
+        /// Delete corrupted files
+        #[arg(long, short)]
+        delete: bool,
+        /// Download corrupted files again when their version is known
+        #[arg(long, short)]
+        redownload: bool,
+    },
+
+    /// Package the cached files needed to apply the active profile offline
+    /// into a zip archive, to import into the cache on another system
+    Export {
+        /// The archive to write
+        file: PathBuf,
+        /// Export the files needed by every profile
+        #[arg(long, short)]
+        all: bool,
+    },
+
+    /// Extract the files of an archive made by `cache export` that the active
+    /// profile needs into the cache, skipping any that don't match its lockfile
+    Import {
+        /// The archive to read
+        file: PathBuf,
+        /// Import the files needed by every profile
+        #[arg(long, short)]
+        all: bool,
+        /// Replace files that are already cached
+        #[arg(long, short)]
+        force: bool,
+    },
 }
 
 #[derive(clap::Subcommand, Clone)]
@@ -1,7 +1,10 @@
 use std::{borrow::Cow, ffi::OsStr, path::Path};
 
 use anyhow::{Context, Result, anyhow, bail};
-use ferrallay::config::{Config, Profile, profile::ProfileData};
+use ferrallay::{
+    client::schema::ReleaseChannel,
+    config::{Config, InstallStrategy, Profile, profile::ProfileData},
+};
 use yansi::Paint;
 
 macro_rules! consts {
@@ -11,7 +14,10 @@ macro_rules! consts {
 }
 pub(crate) use consts;
 
-use crate::tui::const_style;
+use crate::{
+    cli::{Channel, Strategy},
+    tui::const_style,
+};
 
 pub const APP_NAME: &str = consts!(APP_NAME);
 
@@ -62,3 +68,25 @@ pub fn path_profile(path: Option<&Path>) -> Option<Profile> {
         .and_then(|mut anc| anc.find(|p| ProfileData::file_path(p).exists()))
         .map(|p| Profile::new("Local Directory".to_owned(), p.try_into().expect("Should be an absolute path")))
 }
+
+impl From<Strategy> for Option<InstallStrategy> {
+    fn from(value: Strategy) -> Self {
+        match value {
+            Strategy::Copy => Some(InstallStrategy::Copy),
+            Strategy::Hardlink => Some(InstallStrategy::Hardlink),
+            Strategy::Symlink => Some(InstallStrategy::Symlink),
+            Strategy::Reflink => Some(InstallStrategy::Reflink),
+            Strategy::Default => None,
+        }
+    }
+}
+
+impl From<Channel> for ReleaseChannel {
+    fn from(value: Channel) -> Self {
+        match value {
+            Channel::Release => Self::Release,
+            Channel::Beta => Self::Beta,
+            Channel::Alpha => Self::Alpha,
+        }
+    }
+}
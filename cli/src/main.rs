@@ -14,20 +14,24 @@ use std::{
 use anyhow::{Context, Result};
 use clap::{CommandFactory, Parser};
 use ferrallay::{
-    client::{Client, ForgeClient, GithubClient, ModrinthClient},
-    config::{Config, DEFAULT_CONFIG_PATH},
+    checked_types::PathAbsolute,
+    client::{CacheOptions, Client, DirectClient, ForgeClient, LocalClient, MavenClient, ModrinthClient, RateLimiter, Revalidator},
+    config::{Config, DEFAULT_CONFIG_PATH, RateLimits},
     curseforge::client::AuthData,
+    mgmt::CACHE_DIR,
 };
 use tokio::{runtime, sync::OnceCell};
 use yansi::Paint;
 
 use self::{
-    cli::{Allaerris, ModpackSubcommand, ProfileSubcommand, Subcommand},
+    cli::{Allaerris, CacheSubcommand, ModpackSubcommand, ModsSubcommand, ProfileSubcommand, Subcommand},
     helpers::{APP_NAME, consts, get_active_profile, path_profile},
     subcommands::{cache, modpack, mods, profile},
     tui::const_style,
 };
 
+/// Exit code of `mods outdated` when there are updates available
+const UPDATES_AVAILABLE: u8 = 10;
 const USER_AGENT: &str = concat!(consts!(APP_NAME), "/", env!("CARGO_PKG_VERSION"), " (Github: mgziminsky)");
 
 fn main() -> ExitCode {
@@ -41,19 +45,20 @@ fn main() -> ExitCode {
         }
         builder.build().expect("Could not initialise Tokio runtime")
     };
-    if let Err(err) = runtime.block_on(actual_main(cli)) {
-        eprintln!("{:?}", err.red().wrap());
-        if err.to_string().contains("error trying to connect") {
-            eprintln!("{}", "Verify that you are connnected to the internet".yellow().bold());
-        }
-        ExitCode::FAILURE
-    } else {
-        ExitCode::SUCCESS
+    match runtime.block_on(actual_main(cli)) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("{:?}", err.red().wrap());
+            if err.to_string().contains("error trying to connect") {
+                eprintln!("{}", "Verify that you are connnected to the internet".yellow().bold());
+            }
+            ExitCode::FAILURE
+        },
     }
 }
 
 #[allow(clippy::too_many_lines)]
-async fn actual_main(mut cli_app: Allaerris) -> Result<()> {
+async fn actual_main(mut cli_app: Allaerris) -> Result<ExitCode> {
     // The complete command should not require a config.
     // See [#139](https://github.com/gorilla-devs/ferium/issues/139) for why this might be a problem.
     if let Subcommand::Complete { shell } = cli_app.subcommand {
@@ -68,7 +73,7 @@ async fn actual_main(mut cli_app: Allaerris) -> Result<()> {
                 .unwrap_or(APP_NAME),
             &mut std::io::stdout(),
         );
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
     // Alias `profiles` to `profile list`
     if let Subcommand::Profiles = cli_app.subcommand {
@@ -77,27 +82,6 @@ async fn actual_main(mut cli_app: Allaerris) -> Result<()> {
         };
     }
 
-    let mut client: Vec<Client> = vec![
-        { const { ModrinthClient::builder(USER_AGENT) } }.build()?.into(),
-        GithubClient::builder()
-            .personal_token(cli_app.github_token.or_else(|| var("GITHUB_TOKEN").ok()).unwrap_or_default())
-            .build()?
-            .into(),
-    ];
-    if let Some(api_key) = cli_app.curseforge_api_key.or_else(|| var("CURSEFORGE_API_KEY").ok()) {
-        client.push(
-            { const { ForgeClient::builder(USER_AGENT) } }
-                .auth(AuthData {
-                    api_key_auth: Some(api_key),
-                })
-                .build()?
-                .into(),
-        );
-    } else {
-        eprintln!("{}", "No Curseforge API key provided, client disabled".yellow());
-    }
-    let client = client.try_into()?;
-
     let config_path = &cli_app
         .config_file
         .or_else(|| var_os("ALLAERRIS_CONFIG_FILE").map(Into::into))
@@ -135,12 +119,101 @@ async fn actual_main(mut cli_app: Allaerris) -> Result<()> {
         };
     }
 
+    // Only load the config for client settings if it exists to avoid warning about
+    // a missing file
+    let (rate_limits, mut settings, mut local_root) = if config_path.exists() {
+        let config = config!();
+        let settings = mods::Settings {
+            endpoints: config.endpoints().clone(),
+            install_strategy: config.install_strategy(),
+            mirrors: config.mirrors().to_vec(),
+            downloads: config.downloads().clone(),
+            http: reqwest::Client::default(),
+        };
+        (*config.rate_limits(), settings, config.local_root().cloned())
+    } else {
+        (RateLimits::default(), mods::Settings::default(), None)
+    };
+    let downloads = &mut settings.downloads;
+    if let Some(max) = cli_app.max_downloads {
+        downloads.max_concurrent = max.get();
+    }
+    if let Some(max) = cli_app.max_hashing {
+        downloads.max_hashing = max.get();
+    }
+    if let Some(rate) = cli_app.limit_rate {
+        downloads.max_speed = Some(u64::try_from(rate.bytes()).context("Download rate limit can't be negative")?);
+    }
+    if let Some(proxy) = &cli_app.proxy {
+        downloads.proxy = Some(proxy.clone());
+    }
+    for cert in &cli_app.ca_cert {
+        downloads.ca_certs.push(PathAbsolute::new(cert)?);
+    }
+    settings.http = settings.downloads.http_client(USER_AGENT)?;
+    let endpoints = &mut settings.endpoints;
+    endpoints.override_with("ALLAERRIS_", |name| var(name).ok())?;
+    if let Ok(root) = var("ALLAERRIS_LOCAL_ROOT") {
+        local_root = Some(PathAbsolute::new(root).context("ALLAERRIS_LOCAL_ROOT must be an absolute path")?);
+    }
+    let etags = CACHE_DIR.join("http");
+    let mut client: Vec<Client> = vec![
+        {
+            let mut builder = { const { ModrinthClient::builder(USER_AGENT) } }
+                .middleware(Revalidator::new(&etags, RateLimiter::new(rate_limits.modrinth)));
+            if let Some(server) = endpoints.modrinth.clone() {
+                builder = builder.server(server);
+            }
+            builder.build()?.into()
+        },
+        RateLimiter::new(rate_limits.github)
+            .github_client(
+                USER_AGENT,
+                endpoints.github.as_ref(),
+                cli_app.github_token.or_else(|| var("GITHUB_TOKEN").ok()).as_deref(),
+            )?
+            .into(),
+    ];
+    if let Some(api_key) = cli_app.curseforge_api_key.or_else(|| var("CURSEFORGE_API_KEY").ok()) {
+        let mut builder = { const { ForgeClient::builder(USER_AGENT) } }
+            .auth(AuthData {
+                api_key_auth: Some(api_key),
+            })
+            .middleware(Revalidator::new(&etags, RateLimiter::new(rate_limits.curseforge)));
+        if let Some(server) = endpoints.curseforge.clone() {
+            builder = builder.server(server);
+        }
+        client.push(builder.build()?.into());
+    } else {
+        eprintln!("{}", "No Curseforge API key provided, client disabled".yellow());
+    }
+    client.push(DirectClient.into());
+    client.push(MavenClient::with_http(settings.http.clone()).into());
+    if let Some(root) = local_root {
+        client.push(LocalClient::new(root).into());
+    }
+    let client = Client::try_from(client)?.cached(CacheOptions {
+        offline: cli_app.offline,
+        ..Default::default()
+    });
+
     // Run function(s) based on the sub(sub)command to be executed
+    let mut code = ExitCode::SUCCESS;
     match cli_app.subcommand {
         Subcommand::Complete { .. } | Subcommand::Profiles => {
             unreachable!();
         },
-        Subcommand::Mods(subcommand) => mods::process(subcommand, profile!(), &client).await?,
+        Subcommand::Mods(ModsSubcommand::Outdated { ids, json, all }) => {
+            let found = if all {
+                mods::outdated(&client, &config!().get_profiles(), &ids, json).await?
+            } else {
+                mods::outdated(&client, &[profile!()], &ids, json).await?
+            };
+            if found {
+                code = ExitCode::from(UPDATES_AVAILABLE);
+            }
+        },
+        Subcommand::Mods(subcommand) => mods::process(subcommand, profile!(), &client, &settings).await?,
         Subcommand::Modpack { subcommand } => {
             let mut default_flag = false;
             let subcommand = subcommand.unwrap_or_else(|| {
@@ -172,9 +245,12 @@ async fn actual_main(mut cli_app: Allaerris) -> Result<()> {
                 ProfileSubcommand::Edit {
                     game_version,
                     loader,
+                    channel,
+                    compat,
+                    install,
                     name,
                 } => {
-                    profile::configure(profile!(), game_version, loader, name).await?;
+                    profile::configure(profile!(), game_version, loader, channel, compat, install, name).await?;
                 },
                 _ => profile::process(subcommand, config!()).await?,
             }
@@ -190,12 +266,49 @@ async fn actual_main(mut cli_app: Allaerris) -> Result<()> {
                 );
             }
         },
-        Subcommand::Cache { subcommand } => cache::process(subcommand.unwrap_or_default()),
+        Subcommand::Cache {
+            subcommand:
+                Some(CacheSubcommand::Gc {
+                    keep,
+                    older_than,
+                    max_size,
+                    dry_run,
+                }),
+        } => cache::gc(&config!().get_profiles(), keep, older_than, max_size, dry_run).await?,
+        Subcommand::Cache {
+            subcommand:
+                Some(CacheSubcommand::Verify {
+                    quarantine,
+                    delete,
+                    redownload,
+                }),
+        } => {
+            cache::verify(&client, &config!().get_profiles(), &settings, quarantine, delete, redownload).await?;
+        },
+        Subcommand::Cache {
+            subcommand: Some(CacheSubcommand::Export { file, all }),
+        } => {
+            if all {
+                cache::export(&config!().get_profiles(), &file).await?;
+            } else {
+                cache::export(&[profile!()], &file).await?;
+            }
+        },
+        Subcommand::Cache {
+            subcommand: Some(CacheSubcommand::Import { file, all, force }),
+        } => {
+            if all {
+                cache::import(&config!().get_profiles(), &file, force).await?;
+            } else {
+                cache::import(&[profile!()], &file, force).await?;
+            }
+        },
+        Subcommand::Cache { subcommand } => cache::process(&subcommand.unwrap_or_default()),
     }
 
     if let Some(config) = config_.get_mut() {
         config.save_to(config_path).await?;
     }
 
-    Ok(())
+    Ok(code)
 }
@@ -1,17 +1,27 @@
-use std::{collections::BTreeMap, fs};
+use std::{collections::BTreeMap, fs, path::Path, sync::mpsc, time::Duration};
 
 use anyhow::{Context, Result, anyhow};
-use ferrallay::mgmt::CACHE_DIR;
+use ferrallay::{
+    Client, ProfileManager,
+    config::Profile,
+    mgmt::{
+        CACHE_DIR, CacheStatus, CorruptAction, GcOptions, VerifyOptions,
+        events::{DownloadProgress, ProgressEvent},
+    },
+};
 use walkdir::WalkDir;
 use yansi::Paint;
 
+use super::mods::Settings;
 use crate::cli::CacheSubcommand::{self, *};
 
 
-pub fn process(subcommand: CacheSubcommand) {
+pub fn process(subcommand: &CacheSubcommand) {
     match subcommand {
         Info => info(),
         Clear => clear(),
+        // Handled in main since they need profiles or are async
+        Gc { .. } | Verify { .. } | Export { .. } | Import { .. } => unreachable!(),
     }
 }
 
@@ -88,3 +98,149 @@ fn clear() {
         println!("Cache cleared");
     }
 }
+
+pub async fn gc(profiles: &[&Profile], keep: usize, older_than: Option<u64>, max_size: Option<::size::Size>, dry_run: bool) -> Result<()> {
+    let older_than = older_than
+        .map(|days| {
+            days.checked_mul(24 * 60 * 60)
+                .map(Duration::from_secs)
+                .context("Number of days is too large")
+        })
+        .transpose()?;
+    let max_size = max_size
+        .map(|size| u64::try_from(size.bytes()).context("Cache size can't be negative"))
+        .transpose()?;
+    let options = GcOptions {
+        keep,
+        older_than,
+        max_size,
+        dry_run,
+    };
+    let (sender, receiver) = mpsc::channel();
+    let removed = ProfileManager::with_channel(sender).collect_garbage(profiles, &options).await?;
+    for event in receiver.try_iter() {
+        if let ProgressEvent::Error(e) = event {
+            eprintln!("{:?}", anyhow!(e).red().wrap());
+        }
+    }
+
+    if removed.is_empty() {
+        println!("Nothing to delete");
+        return Ok(());
+    }
+    for file in &removed {
+        let path = file.path.strip_prefix(&*CACHE_DIR).unwrap_or(&file.path);
+        println!(
+            "{}  {}{}",
+            format_args!("{:>10}", ::size::Size::from_bytes(file.size).to_string()).cyan(),
+            path.display(),
+            if file.evicted { " (over size limit)" } else { "" }.dim()
+        );
+    }
+    let freed = ::size::Size::from_bytes(removed.iter().map(|f| f.size).sum::<u64>());
+    println!(
+        "\n{} {} from {} files",
+        if dry_run { "Would free" } else { "Freed" },
+        freed.cyan().bold(),
+        removed.len().bright_green().bold(),
+    );
+    Ok(())
+}
+
+pub async fn verify(
+    client: &Client,
+    profiles: &[&Profile],
+    settings: &Settings,
+    quarantine: bool,
+    delete: bool,
+    redownload: bool,
+) -> Result<()> {
+    let options = VerifyOptions {
+        action: match (quarantine, delete) {
+            (true, _) => CorruptAction::Quarantine,
+            (_, true) => CorruptAction::Delete,
+            _ => CorruptAction::Report,
+        },
+        redownload,
+    };
+    let (sender, receiver) = mpsc::channel();
+    let verified = settings.manager(sender).verify_cache(client, profiles, &options).await?;
+    print_errors(&receiver);
+
+    let (mut valid, mut unknown, mut corrupt, mut repaired) = (0, 0, 0, 0);
+    for file in &verified {
+        let path = file.path.strip_prefix(&*CACHE_DIR).unwrap_or(&file.path);
+        match &file.status {
+            CacheStatus::Valid => valid += 1,
+            CacheStatus::Unknown => unknown += 1,
+            CacheStatus::Corrupt { expected, actual } => {
+                corrupt += 1;
+                println!(
+                    "{} {}\n\tExpected: {}\n\t  Actual: {}",
+                    "Corrupted:".red().bold(),
+                    path.display(),
+                    expected,
+                    actual.as_deref().unwrap_or("unreadable"),
+                );
+            },
+            CacheStatus::Repaired => {
+                repaired += 1;
+                println!("{} {}", "Repaired:".green().bold(), path.display());
+            },
+        }
+    }
+    if corrupt + repaired > 0 {
+        match options.action {
+            CorruptAction::Report => {},
+            CorruptAction::Quarantine => println!(
+                "\nCorrupted files were moved to `{}`",
+                CACHE_DIR.join("quarantine").display().bold()
+            ),
+            CorruptAction::Delete => println!("\nCorrupted files were deleted"),
+        }
+    }
+    println!(
+        "\n{} valid, {} corrupted, {} repaired, {} unknown",
+        valid.bright_green().bold(),
+        corrupt.red().bold(),
+        repaired.green().bold(),
+        unknown.dim(),
+    );
+    Ok(())
+}
+
+pub async fn export(profiles: &[&Profile], file: &Path) -> Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let exported = ProfileManager::with_channel(sender).export_cache(profiles, file).await?;
+    print_errors(&receiver);
+    println!(
+        "Exported {} cached files to {}",
+        exported.len().bright_green().bold(),
+        file.display().bold()
+    );
+    Ok(())
+}
+
+pub async fn import(profiles: &[&Profile], file: &Path, force: bool) -> Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut manager = ProfileManager::with_channel(sender);
+    manager.force = force;
+    let imported = manager.import_cache(profiles, file).await?;
+    print_errors(&receiver);
+    for path in &imported {
+        println!("{} {}", "Imported:".green().bold(), path.display());
+    }
+    println!("\nImported {} files into the cache", imported.len().bright_green().bold());
+    Ok(())
+}
+
+fn print_errors(receiver: &mpsc::Receiver<ProgressEvent>) {
+    for event in receiver.try_iter() {
+        match event {
+            ProgressEvent::Error(e) | ProgressEvent::Download(DownloadProgress::Fail(_, e)) => {
+                eprintln!("{:?}", anyhow!(e).red().wrap());
+            },
+            _ => {},
+        }
+    }
+}
@@ -1,5 +1,9 @@
 use anyhow::{Result, bail};
-use ferrallay::{Client, config::Profile};
+use ferrallay::{
+    Client,
+    client::schema::SearchQuery,
+    config::{ModLoader, Profile},
+};
 use yansi::Paint;
 
 use crate::{cli::ModsSubcommand, helpers, tui::print_mods};
@@ -8,16 +12,68 @@ mod add;
 mod list;
 mod locking;
 mod mgmt;
+mod outdated;
 mod remove;
+mod search;
 
-pub async fn process(subcommand: ModsSubcommand, profile: &mut Profile, client: &Client) -> Result<()> {
+pub use self::{mgmt::Settings, outdated::outdated};
+
+#[allow(clippy::too_many_lines)]
+pub async fn process(subcommand: ModsSubcommand, profile: &mut Profile, client: &Client, settings: &Settings) -> Result<()> {
     use ModsSubcommand::*;
     match subcommand {
-        Add { ids, exclude } => {
+        Add {
+            ids,
+            exclude,
+            sha1,
+            size,
+            channel,
+            compat,
+            constraint,
+        } => {
             if ids.is_empty() {
                 bail!("Must provide at least one project ID");
             }
-            let new = add::add(client, profile.data_mut().await?, ids, exclude).await?;
+            let expected = add::expected_version(ids.len(), sha1, size)?;
+            let new = add::add(
+                client,
+                profile.data_mut().await?,
+                ids,
+                exclude,
+                expected,
+                channel.map(Into::into),
+                compat,
+                constraint,
+            )
+            .await?;
+            if new > 0 {
+                profile.save().await?;
+            }
+        },
+        Search {
+            query,
+            project_type,
+            game_version,
+            loader,
+            any,
+            category,
+            sort,
+            limit,
+            page,
+            exclude,
+        } => {
+            let data = profile.data_mut().await?;
+            let query = SearchQuery {
+                query: query.join(" "),
+                project_type: project_type.map(Into::into),
+                game_version: game_version.or_else(|| (!any).then(|| data.game_version.clone())),
+                loader: loader.or((!any).then_some(data.loader)).and_then(ModLoader::known),
+                category,
+                sort: sort.into(),
+                offset: page.saturating_sub(1).saturating_mul(u32::from(limit)),
+                limit,
+            };
+            let new = search::search(client, data, &query, exclude).await?;
             if new > 0 {
                 profile.save().await?;
             }
@@ -56,7 +112,17 @@ pub async fn process(subcommand: ModsSubcommand, profile: &mut Profile, client:
             }
             profile.save().await?;
         },
-        Mgmt(command) => mgmt::process(command, client, profile).await?,
+        Constrain { constraint, ids } => {
+            locking::constrain(profile, &ids, &constraint).await?;
+            profile.save().await?;
+        },
+        Unconstrain { ids } => {
+            locking::unconstrain(profile, &ids).await?;
+            profile.save().await?;
+        },
+        // Handled in main since it may need every profile
+        Outdated { .. } => unreachable!(),
+        Mgmt(command) => mgmt::process(command, client, profile, settings).await?,
     }
     Ok(())
 }
@@ -1,15 +1,46 @@
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+use dialoguer::MultiSelect;
 use ferrallay::{
-    Client,
-    config::{Mod, VersionedProject, profile::ProfileData},
+    Client, ProfileManager,
+    client::schema::{ReleaseChannel, UrlVersion, VersionId, VersionIdSvcType},
+    config::{GameVersionPolicy, Mod, VersionConstraint, VersionedProject, profile::ProfileData},
 };
 use yansi::Paint;
 
-use crate::tui::{CROSS_RED, TICK_GREEN, TICK_YELLOW, mod_single_line};
+use crate::tui::{CROSS_RED, TICK_GREEN, TICK_YELLOW, mod_single_line, print_mods};
+
+/// The expected [version](UrlVersion) of a url mod from the `sha1` and `size`
+/// args, which are only allowed when adding a single mod
+pub fn expected_version(count: usize, sha1: Option<String>, size: Option<u64>) -> Result<Option<UrlVersion>> {
+    if sha1.is_none() && size.is_none() {
+        return Ok(None);
+    }
+    if count != 1 {
+        bail!("Expected sha1/size can only be set when adding a single url");
+    }
+    let mut expected = match sha1 {
+        Some(sha1) => VersionIdSvcType::get_url(sha1.as_str()).context("Invalid sha1")?,
+        None => UrlVersion::default(),
+    };
+    expected.size = size;
+    Ok(Some(expected))
+}
 
 /// Add mods with `ids` to `profile` returning the number of added/updated mods,
-/// not counting existing and unchanged mods
-pub async fn add(client: &Client, profile: &mut ProfileData, ids: Vec<String>, exclude: bool) -> Result<usize> {
+/// not counting existing and unchanged mods. Url mods are set to the
+/// `expected` version if provided, and all are installed from `channel`, with
+/// the `compat` policy, and limited by `constraint` if set
+#[allow(clippy::too_many_arguments)]
+pub async fn add(
+    client: &Client,
+    profile: &mut ProfileData,
+    ids: Vec<String>,
+    exclude: bool,
+    expected: Option<UrlVersion>,
+    channel: Option<ReleaseChannel>,
+    compat: Option<GameVersionPolicy>,
+    constraint: Option<VersionConstraint>,
+) -> Result<usize> {
     eprintln!("Fetching mod information...");
     let mods = if ids.len() == 1 {
         let m = client.get_project(&ids[0]).await?;
@@ -25,19 +56,68 @@ pub async fn add(client: &Client, profile: &mut ProfileData, ids: Vec<String>, e
     .map(Mod::from) // From schema to config Mod
     .map(|mut m| {
         m.exclude = exclude;
-        m
+        m.channel = channel;
+        m.compat.clone_from(&compat);
+        m.constraint.clone_from(&constraint);
+        if let Some(expected) = expected.clone() {
+            m.id.set_version(VersionId::Url(expected)).context("Expected sha1/size is only valid for urls")?;
+        }
+        Ok(m)
     })
-    .collect::<Vec<_>>();
+    .collect::<Result<Vec<_>>>()?;
 
-    let new = add_mods(profile, &mods);
+    let mut new = add_mods(profile, &mods);
     // Show not found
     ids.into_iter()
         .filter(|id| !mods.iter().any(|m| &m.slug == id || m.project() == id))
         .for_each(|id| println!("{} {} — Not Found", CROSS_RED, id.italic().bold()));
 
+    if !exclude {
+        new += add_deps(client, profile, &mods).await?;
+    }
+
     Ok(new)
 }
 
+/// Show the required dependencies of `mods` and prompt for which optional
+/// dependencies to add to `profile`, returning the number of added mods
+pub(super) async fn add_deps(client: &Client, profile: &mut ProfileData, mods: &[Mod]) -> Result<usize> {
+    if mods.is_empty() {
+        return Ok(0);
+    }
+    eprintln!("Checking dependencies...");
+    let deps = match ProfileManager::new().dependencies(client, profile, mods).await {
+        Ok(deps) => deps,
+        Err(e) => {
+            eprintln!("{}", format_args!("Failed to check dependencies: {e}").yellow());
+            return Ok(0);
+        },
+    };
+    if !deps.required.is_empty() {
+        let required = deps.required.into_iter().map(Mod::from).collect::<Vec<_>>();
+        print_mods(
+            format_args!(
+                "{} required dependencies will be installed by apply",
+                required.len().yellow().bold()
+            ),
+            &required,
+        );
+    }
+    if deps.optional.is_empty() {
+        return Ok(0);
+    }
+
+    let optional = deps.optional.into_iter().map(Mod::from).collect::<Vec<_>>();
+    let selection = MultiSelect::new()
+        .with_prompt("Select optional dependencies to add to profile")
+        .report(false)
+        .items(&optional.iter().map(mod_single_line).collect::<Vec<_>>())
+        .interact_opt()?
+        .unwrap_or_default();
+    let picked = selection.into_iter().map(|i| &optional[i]);
+    Ok(add_mods(profile, picked))
+}
+
 pub(super) fn add_mods<'m>(data: &mut ProfileData, mods: impl IntoIterator<Item = &'m Mod>) -> usize {
     let mut new = 0;
     for res in data.add_mods(mods) {
@@ -9,7 +9,7 @@ use ferrallay::{
 };
 use yansi::Paint;
 
-use crate::tui::{print_mods, print_project_markdown, print_project_verbose};
+use crate::tui::{channel_tag, print_mods, print_project_markdown, print_project_verbose};
 
 static MR_BASE: LazyLock<::url::Url> = LazyLock::new(|| {
     "https://modrinth.com/user/"
@@ -21,11 +21,12 @@ pub async fn simple(profile: &Profile) -> Result<(), anyhow::Error> {
     let data = profile.data().await?;
     print_mods(
         format_args!(
-            "{} {} on {} {}",
+            "{} {} on {} {} [{}]",
             profile.name().bold(),
             format_args!("({} mods)", data.mods.len()).yellow(),
             format_args!("{:?}", data.loader).magenta(),
             data.game_version.green(),
+            data.channel.map_or_else(|| "any".dim(), channel_tag),
         ),
         &data.mods,
     );
@@ -83,7 +84,6 @@ pub async fn verbose(client: &Client, profile: &Profile, markdown: bool) -> Resu
     }
 
     // Calculate Github downloads count from releases
-    // FIXME: Rate Limiting
     if let Some(gh_client) = client.as_github() {
         for proj in &mut projects {
             if let Ok((own, repo)) = proj.id.get_github() {
@@ -4,7 +4,7 @@ use anyhow::Result;
 use ferrallay::{
     Client,
     client::schema::{ProjectIdSvcType, VersionIdSvcType},
-    config::{Mod, Profile, VersionedProject},
+    config::{Mod, Profile, VersionConstraint, VersionedProject},
 };
 use yansi::{Paint, Style};
 
@@ -156,3 +156,46 @@ pub async fn unlock_all(profile: &mut Profile) -> Result<()> {
 
     Ok(())
 }
+
+/// Set the version constraint of the projects with `ids` to `constraint`
+pub async fn constrain(profile: &mut Profile, ids: &[impl ProjectIdSvcType + Display], constraint: &VersionConstraint) -> Result<()> {
+    let mut mods = mods!(profile).collect::<Vec<_>>();
+    let mut changed = false;
+    for id in ids {
+        let m = take_else_cont!(id, mods, eprintln!("{}", not_found_msg!(id)));
+        print!("{}: {}", tui::mod_single_line(m), constraint.bright_blue().italic());
+        match m.constraint.replace(constraint.clone()) {
+            Some(prev) if &prev == constraint => print!(" (No Change)"),
+            Some(prev) => {
+                print!(" (Previous: {})", prev.paint(PREV_STYLE));
+                changed = true;
+            },
+            None => changed = true,
+        }
+        println!();
+    }
+    if changed {
+        eprintln!(
+            "Run {} to move installed mods to a version satisfying their constraint",
+            const_style!("update"; yellow().bold())
+        );
+    }
+
+    Ok(())
+}
+
+/// Remove the version constraint from the projects with `ids`
+pub async fn unconstrain(profile: &mut Profile, ids: &[impl ProjectIdSvcType + Display]) -> Result<()> {
+    let mut mods = mods!(profile).collect::<Vec<_>>();
+    for id in ids {
+        let m = take_else_cont!(id, mods, eprintln!("{}", not_found_msg!(id)));
+        print!("{}: ", tui::mod_single_line(m));
+        if let Some(c) = m.constraint.take() {
+            println!("{}", c.paint(PREV_STYLE));
+        } else {
+            println!("No Change");
+        }
+    }
+
+    Ok(())
+}
@@ -1,12 +1,17 @@
-use std::{collections::HashMap, sync::mpsc};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::mpsc::{self, Sender},
+};
 
 use anyhow::{Result, bail};
 use dialoguer::MultiSelect;
 use ferrallay::{
     Client, ProfileManager,
+    checked_types::PathScoped,
     client::schema::ProjectType,
-    config::{Mod, ModLoader, Profile, ProjectWithVersion},
+    config::{DownloadSettings, Endpoints, InstallStrategy, Mirror, Mod, ModLoader, Profile, ProjectWithVersion},
     mgmt::{
+        InstallPlan, PlannedFile, UpdateInfo,
         events::{DownloadId, DownloadProgress, ProgressEvent},
         server::Version,
     },
@@ -18,24 +23,70 @@ use crate::{
     cli::{MgmtCommand, ServerSubcommand},
     consts,
     helpers::path_profile,
-    tui::{CROSS_RED, PROG_BYTES, PROG_DONE, THEME, TICK_GREEN, TICK_YELLOW, const_style, ellipsize, id_tag},
+    tui::{CROSS_RED, PROG_BYTES, PROG_DONE, THEME, TICK_GREEN, TICK_YELLOW, channel_tag, const_style, ellipsize, id_tag, print_paged},
 };
 
 
-pub async fn process(command: MgmtCommand, client: &Client, profile: &mut Profile) -> Result<()> {
+/// Global settings from the config used by every [`ProfileManager`]
+#[derive(Default)]
+pub struct Settings {
+    pub endpoints: Endpoints,
+    pub install_strategy: InstallStrategy,
+    pub mirrors: Vec<Mirror>,
+    pub downloads: DownloadSettings,
+    /// Made from [`downloads`](Self::downloads) and shared with the clients
+    pub http: reqwest::Client,
+}
+
+impl Settings {
+    /// Create a [`ProfileManager`] using these settings
+    pub fn manager(&self, sender: Sender<ProgressEvent>) -> ProfileManager {
+        let mut manager = ProfileManager::with_channel(sender);
+        manager.endpoints = self.endpoints.clone();
+        manager.install_strategy = self.install_strategy;
+        manager.mirrors.clone_from(&self.mirrors);
+        manager.configure_downloads(&self.downloads, self.http.clone());
+        manager
+    }
+}
+
+pub async fn process(command: MgmtCommand, client: &Client, profile: &mut Profile, settings: &Settings) -> Result<()> {
     use MgmtCommand::*;
     let (sender, handle) = progress_hander();
+    let mut plan = None;
     {
-        let mut manager = ProfileManager::with_channel(sender);
+        let mut manager = settings.manager(sender);
+        manager.offline = client.is_offline();
         match command {
-            Apply { force, no_cache } => {
+            Apply {
+                force,
+                no_cache,
+                dry_run,
+                allow_partial,
+                frozen,
+            } => {
                 manager.force = force;
                 manager.no_cache = no_cache;
-                manager.apply(client, profile).await?;
+                manager.allow_partial = allow_partial;
+                manager.frozen = frozen;
+                if dry_run {
+                    plan = Some(manager.plan(client, profile).await?);
+                } else {
+                    manager.apply(client, profile).await?;
+                }
             },
-            Update { ids, revert, apply } => {
+            Update {
+                ids,
+                revert,
+                apply,
+                changelog,
+                page,
+            } => {
                 assert!(!(revert && apply), "Revert and Apply should never both be set");
-                update(&manager, profile, client, ids, revert).await?;
+                let updates = update(&manager, profile, client, ids, revert).await?;
+                if changelog || page {
+                    changelogs(&manager, profile, client, &updates, page).await?;
+                }
                 if apply || (!revert && prompt_apply()) {
                     manager.apply(client, profile).await?;
                 } else if !revert {
@@ -84,10 +135,61 @@ pub async fn process(command: MgmtCommand, client: &Client, profile: &mut Profil
         }
     }
     let _ = handle.await;
+    if let Some(plan) = plan {
+        print_plan(&plan);
+    }
     Ok(())
 }
 
-async fn update(manager: &ProfileManager, profile: &Profile, client: &Client, ids: Vec<String>, revert: bool) -> Result<()> {
+fn print_plan(plan: &InstallPlan) {
+    if plan.is_empty() {
+        println!("\nProfile is up to date. Nothing to install");
+        return;
+    }
+    let files = |label: &str, files: &[PlannedFile]| {
+        if files.is_empty() {
+            return;
+        }
+        println!("\n{}", format_args!("{label} ({}):", files.len()).bold());
+        for f in files {
+            println!(
+                "  {} {} {}",
+                f.file.display().blue(),
+                f.title.dim(),
+                ::size::Size::from_bytes(f.length).cyan(),
+            );
+        }
+    };
+    let paths = |label: &str, paths: &BTreeSet<PathScoped>| {
+        if paths.is_empty() {
+            return;
+        }
+        println!("\n{}", format_args!("{label} ({}):", paths.len()).bold());
+        for p in paths {
+            println!("  {}", p.display().yellow());
+        }
+    };
+    files("Download", &plan.download);
+    files("Copy from cache", &plan.copy);
+    paths("Extract overrides", &plan.extract);
+    paths("Back up modified overrides", &plan.backup);
+    paths("Delete", &plan.delete);
+    if !plan.changes.is_empty() {
+        println!("\n{}", format_args!("Lockfile changes ({}):", plan.changes.len()).bold());
+        for change in &plan.changes {
+            let id = id_tag(&change.project);
+            match (&change.from, &change.to) {
+                (None, Some(to)) => println!("  {} {id} {}", "+".green(), to.blue()),
+                (Some(from), None) => println!("  {} {id} {}", "-".red(), from.yellow()),
+                (Some(from), Some(to)) => println!("  {} {id} {} -> {}", "~".yellow(), from.yellow(), to.blue()),
+                (None, None) => {},
+            }
+        }
+    }
+    println!("\n{} files already up to date", plan.keep.len().bold());
+}
+
+async fn update(manager: &ProfileManager, profile: &Profile, client: &Client, ids: Vec<String>, revert: bool) -> Result<Vec<UpdateInfo>> {
     let updates = if revert {
         manager.revert(profile).await?
     } else {
@@ -102,17 +204,54 @@ async fn update(manager: &ProfileManager, profile: &Profile, client: &Client, id
         } else {
             (TICK_GREEN, "Updated")
         };
-        for up in updates {
+        for up in &updates {
             println!(
-                "{tick} {label} {} from version {} -> {}\n\t{} -> {}",
+                "{tick} {label} {} from version {} -> {} [{}]\n\t{} -> {}",
                 id_tag(&up.project).bold().wrap(),
                 up.from.0.bold().yellow(),
                 up.to.0.bold().blue(),
+                channel_tag(up.channel),
                 up.from.1.display().bold().yellow(),
                 up.to.1.display().bold().blue(),
             );
         }
     }
+    Ok(updates)
+}
+
+async fn changelogs(manager: &ProfileManager, profile: &Profile, client: &Client, updates: &[UpdateInfo], page: bool) -> Result<()> {
+    use std::fmt::Write;
+
+    if updates.is_empty() {
+        return Ok(());
+    }
+    eprintln!("{}", "Fetching changelogs...".dim());
+    let mut out = String::new();
+    for up in updates {
+        let changelogs = manager.changelogs(client, profile, up).await?;
+        writeln!(out, "\n{}", id_tag(&up.project).bold().underline())?;
+        if changelogs.is_empty() {
+            writeln!(out, "  {}", "No changelog available".dim())?;
+        }
+        for log in changelogs {
+            writeln!(
+                out,
+                "\n{} {} [{}] {}",
+                "──".dim(),
+                log.version.title.bold().blue(),
+                channel_tag(log.version.channel),
+                log.version.date.get(..10).unwrap_or_default().dim(),
+            )?;
+            for line in log.text.trim().lines() {
+                writeln!(out, "  {line}")?;
+            }
+        }
+    }
+    if page {
+        print_paged(&out);
+    } else {
+        print!("{out}");
+    }
     Ok(())
 }
 
@@ -179,6 +318,9 @@ async fn scan(
                 slug: String::new(),
                 name: format!("[SCANNED] {}", v.title),
                 exclude: false,
+                channel: None,
+                compat: None,
+                constraint: None,
                 project_type: typ,
             })
             .collect::<Vec<_>>()
@@ -208,10 +350,11 @@ fn progress_hander() -> (mpsc::Sender<ProgressEvent>, tokio::task::JoinHandle<()
                 Installed { file, is_new, typ } => {
                     use ferrallay::mgmt::events::InstallType::*;
                     println!(
-                        "{} {:>9}: {}",
+                        "{} {:>10}: {}",
                         if is_new { TICK_GREEN } else { TICK_YELLOW },
                         match typ {
                             Mod => "Installed",
+                            Dependency => "Dependency",
                             Override => "Override",
                             Other => "Other",
                         },
@@ -219,7 +362,17 @@ fn progress_hander() -> (mpsc::Sender<ProgressEvent>, tokio::task::JoinHandle<()
                     );
                 },
                 Deleted(file) => {
-                    println!("{}   Deleted: {}", TICK_GREEN, file.display());
+                    println!("{}    Deleted: {}", TICK_GREEN, file.display());
+                },
+                RelaxedMatch { title, game_versions } => {
+                    println!(
+                        "{}",
+                        format_args!(
+                            "`{title}` has no version for the profile's Minecraft version, using the one for {}",
+                            game_versions.join(", ")
+                        )
+                        .yellow()
+                    );
                 },
                 Error(err) => {
                     eprintln!("{}", err.red());
@@ -242,6 +395,11 @@ fn handle_dl(evt: DownloadProgress, bars: &mut HashMap<DownloadId, ProgressBar>,
                 bar.inc(len);
             }
         },
+        Retry(id) => {
+            if let Some(bar) = bars.get(&id) {
+                bar.set_position(0);
+            }
+        },
         Success(id) => {
             if let Some(bar) = bars.remove(&id) {
                 bar.with_style(PROG_DONE.clone()).with_prefix(TICK_GREEN.to_string()).finish();
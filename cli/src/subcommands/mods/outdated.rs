@@ -0,0 +1,156 @@
+use anyhow::{Context, Result, bail};
+use ferrallay::{
+    Client, ProfileManager,
+    checked_types::PathScoped,
+    client::schema::{ProjectId, ReleaseChannel, VersionId},
+    config::Profile,
+    mgmt::UpdateInfo,
+};
+use serde::Serialize;
+use yansi::Paint;
+
+use crate::tui::{channel_tag, id_tag};
+
+#[derive(Serialize)]
+struct Outdated<'a> {
+    profile: &'a str,
+    path: &'a std::path::Path,
+    project: &'a ProjectId,
+    installed: VersionInfo<'a>,
+    latest: VersionInfo<'a>,
+    channel: ReleaseChannel,
+}
+
+#[derive(Serialize)]
+struct VersionInfo<'a> {
+    version: &'a VersionId,
+    file: &'a PathScoped,
+}
+
+impl<'a> Outdated<'a> {
+    fn new(profile: &'a Profile, up: &'a UpdateInfo) -> Self {
+        Self {
+            profile: profile.name(),
+            path: profile.path(),
+            project: &up.project,
+            installed: VersionInfo {
+                version: &up.from.0,
+                file: &up.from.1,
+            },
+            latest: VersionInfo {
+                version: &up.to.0,
+                file: &up.to.1,
+            },
+            channel: up.channel,
+        }
+    }
+}
+
+/// Print the available updates for each of `profiles` without marking them to
+/// be installed. Returns `true` if any updates were found
+///
+/// A profile that fails to be checked is reported without stopping the others,
+/// and an error is returned after the report
+pub async fn outdated(client: &Client, profiles: &[&Profile], ids: &[String], json: bool) -> Result<bool> {
+    let manager = ProfileManager::new();
+    let ids = ids.iter().map(|id| id as _).collect::<Vec<_>>();
+    let mut found = Vec::with_capacity(profiles.len());
+    let mut failed = 0;
+    for &profile in profiles {
+        let res = manager
+            .check_updates(client, profile, &ids)
+            .await
+            .with_context(|| format!("Failed to check updates for profile `{}`", profile.name()));
+        match res {
+            Ok(updates) => found.push((profile, updates)),
+            Err(e) if profiles.len() == 1 => return Err(e),
+            Err(e) => {
+                eprintln!("{:?}", e.red().wrap());
+                failed += 1;
+            },
+        }
+    }
+    let any = found.iter().any(|(_, updates)| !updates.is_empty());
+
+    if json {
+        let entries = found
+            .iter()
+            .flat_map(|(profile, updates)| updates.iter().map(|up| Outdated::new(profile, up)))
+            .collect::<Vec<_>>();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else if !any {
+        println!("Everything is up to date");
+    } else {
+        print_table(&found, profiles.len() > 1);
+    }
+    if failed > 0 {
+        bail!("Failed to check updates for {failed} of {} profiles", profiles.len());
+    }
+    Ok(any)
+}
+
+fn print_table(found: &[(&Profile, Vec<UpdateInfo>)], show_profile: bool) {
+    let rows = found
+        .iter()
+        .flat_map(|(profile, updates)| {
+            updates.iter().map(|up| {
+                let cols = [
+                    profile.name().to_owned(),
+                    id_tag(&up.project),
+                    up.from.0.to_string(),
+                    up.to.0.to_string(),
+                ];
+                (cols, up.channel)
+            })
+        })
+        .collect::<Vec<_>>();
+    let header = ["Profile", "Project", "Installed", "Latest"];
+    let mut widths = header.map(str::len);
+    for (row, _) in &rows {
+        for (w, col) in widths.iter_mut().zip(row) {
+            *w = (*w).max(visible_width(col));
+        }
+    }
+    let skip = usize::from(!show_profile);
+
+    let line = header
+        .iter()
+        .zip(widths)
+        .skip(skip)
+        .map(|(h, w)| format!("{h:w$}"))
+        .collect::<Vec<_>>()
+        .join("  ");
+    println!("{}", format_args!("{line}  Channel").bold());
+    for (row, channel) in &rows {
+        let [profile, project, from, to] = row.each_ref().map(String::as_str);
+        if show_profile {
+            print!("{:w$}  ", profile.dim(), w = widths[0]);
+        }
+        // The project tag is already styled, so pad by its visible width
+        println!(
+            "{}{:pad$}  {:w2$}  {:w3$}  {}",
+            project.bold(),
+            "",
+            from.yellow(),
+            to.blue(),
+            channel_tag(*channel),
+            pad = widths[1] - visible_width(project),
+            w2 = widths[2],
+            w3 = widths[3],
+        );
+    }
+}
+
+/// The number of characters in `text`, ignoring terminal style sequences
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
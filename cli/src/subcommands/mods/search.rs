@@ -0,0 +1,88 @@
+use anyhow::Result;
+use dialoguer::MultiSelect;
+use ferrallay::{
+    Client,
+    client::schema::{Project, ProjectType, SearchQuery, SearchSort as LibSearchSort},
+    config::{Mod, profile::ProfileData},
+};
+use yansi::Paint;
+
+use super::add::{add_deps, add_mods};
+use crate::{
+    cli::{SearchSort, SearchType},
+    tui::{CROSS_RED, id_tag},
+};
+
+const DESC_WIDTH: usize = 60;
+
+/// Search for projects matching `query` and add the ones picked by the user to
+/// `profile`, returning the number of added mods
+pub async fn search(client: &Client, profile: &mut ProfileData, query: &SearchQuery, exclude: bool) -> Result<usize> {
+    eprintln!("Searching...");
+    let results = client.search(query).await?;
+    if results.is_empty() {
+        println!("{CROSS_RED} No projects found");
+        return Ok(0);
+    }
+
+    let selection = MultiSelect::new()
+        .with_prompt("Select which projects to add to profile")
+        .report(false)
+        .items(&results.iter().map(result_line).collect::<Vec<_>>())
+        .interact_opt()?
+        .unwrap_or_default();
+    let mods = selection
+        .into_iter()
+        .map(|i| Mod::from(results[i].clone()))
+        .map(|mut m| {
+            m.exclude = exclude;
+            m
+        })
+        .collect::<Vec<_>>();
+
+    let mut new = add_mods(profile, &mods);
+    if !exclude {
+        new += add_deps(client, profile, &mods).await?;
+    }
+    Ok(new)
+}
+
+fn result_line(proj: &Project) -> String {
+    let desc = proj.description.trim();
+    let desc = if desc.chars().count() > DESC_WIDTH {
+        format!("{}…", desc.chars().take(DESC_WIDTH - 1).collect::<String>())
+    } else {
+        desc.to_owned()
+    };
+    format!(
+        "[{}] {} ({} downloads) ― {}",
+        id_tag(&proj.id),
+        proj.name.trim().bold(),
+        proj.downloads.yellow(),
+        desc.italic().dim()
+    )
+}
+
+impl From<SearchType> for ProjectType {
+    fn from(value: SearchType) -> Self {
+        match value {
+            SearchType::Mod => Self::Mod,
+            SearchType::ModPack => Self::ModPack,
+            SearchType::ResourcePack => Self::ResourcePack,
+            SearchType::DataPack => Self::DataPack,
+            SearchType::Shader => Self::Shader,
+        }
+    }
+}
+
+impl From<SearchSort> for LibSearchSort {
+    fn from(value: SearchSort) -> Self {
+        match value {
+            SearchSort::Relevance => Self::Relevance,
+            SearchSort::Downloads => Self::Downloads,
+            SearchSort::Follows => Self::Follows,
+            SearchSort::Newest => Self::Newest,
+            SearchSort::Updated => Self::Updated,
+        }
+    }
+}
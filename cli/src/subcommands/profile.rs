@@ -81,9 +81,12 @@ pub async fn process(subcommand: ProfileSubcommand, config: &mut Config) -> Resu
         ProfileSubcommand::Edit {
             game_version,
             loader,
+            channel,
+            compat,
+            install,
             name,
         } => {
-            configure(get_active_profile(config)?, game_version, loader, name).await?;
+            configure(get_active_profile(config)?, game_version, loader, channel, compat, install, name).await?;
         },
         ProfileSubcommand::Switch { profile_name } => {
             let profiles = config.get_profiles();
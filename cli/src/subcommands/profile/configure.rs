@@ -1,11 +1,22 @@
 use anyhow::Result;
 use dialoguer::{Input, Select};
-use ferrallay::config::{ModLoader, Profile};
+use ferrallay::config::{GameVersionPolicy, ModLoader, Profile};
 
-use super::helpers::{pick_minecraft_version, pick_mod_loader};
-use crate::tui::THEME;
+use super::helpers::{pick_minecraft_version, pick_mod_loader, pick_release_channel};
+use crate::{
+    cli::{Channel, Strategy},
+    tui::THEME,
+};
 
-pub async fn configure(profile: &mut Profile, game_version: Option<String>, loader: Option<ModLoader>, name: Option<String>) -> Result<()> {
+pub async fn configure(
+    profile: &mut Profile,
+    game_version: Option<String>,
+    loader: Option<ModLoader>,
+    channel: Option<Channel>,
+    compat: Option<GameVersionPolicy>,
+    install: Option<Strategy>,
+    name: Option<String>,
+) -> Result<()> {
     let mut interactive = true;
 
     {
@@ -18,6 +29,18 @@ pub async fn configure(profile: &mut Profile, game_version: Option<String>, load
             data.loader = loader;
             interactive = false;
         }
+        if let Some(channel) = channel {
+            data.channel = Some(channel.into());
+            interactive = false;
+        }
+        if let Some(compat) = compat {
+            data.compat = compat;
+            interactive = false;
+        }
+        if let Some(install) = install {
+            data.install = install.into();
+            interactive = false;
+        }
     }
     if let Some(name) = name {
         profile.set_name(&name);
@@ -29,6 +52,8 @@ pub async fn configure(profile: &mut Profile, game_version: Option<String>, load
             "Minecraft version",
             // Show a picker to change mod loader
             "Mod loader",
+            // Show a picker to change release channel
+            "Release channel",
             // Show a dialog to change name
             "Profile Name",
             // Quit the configuration
@@ -48,14 +73,15 @@ pub async fn configure(profile: &mut Profile, game_version: Option<String>, load
             match index {
                 0 => data.game_version = pick_minecraft_version(Some(&data.game_version)).await?,
                 1 => data.loader = pick_mod_loader(Some(data.loader))?,
-                2 => {
+                2 => data.channel = pick_release_channel(data.channel)?,
+                3 => {
                     let name = Input::with_theme(&*THEME)
                         .with_prompt("Change the profile's name")
                         .default(profile.name().to_owned())
                         .interact_text()?;
                     profile.set_name(&name);
                 },
-                3 => break,
+                4 => break,
                 _ => unreachable!(),
             }
             println!();
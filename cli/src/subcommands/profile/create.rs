@@ -78,6 +78,9 @@ pub async fn create(
         mods: vec![],
         modpack: None,
         is_server: server,
+        channel: None,
+        compat: Default::default(),
+        install: None,
     });
     config
         .add_profile(profile)
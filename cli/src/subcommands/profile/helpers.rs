@@ -4,6 +4,7 @@ use anyhow::{Context, Result};
 use dialoguer::{Input, Select};
 use ferrallay::{
     checked_types::PathAbsolute,
+    client::schema::ReleaseChannel,
     config::{ModLoader, Profile, profile::DEFAULT_GAME_VERSION},
 };
 use tokio::sync::OnceCell;
@@ -50,6 +51,25 @@ pub fn pick_mod_loader(default: Option<ModLoader>) -> Result<ModLoader> {
         .map_err(Into::into)
 }
 
+pub fn pick_release_channel(default: Option<ReleaseChannel>) -> Result<Option<ReleaseChannel>> {
+    Select::with_theme(&*THEME)
+        .with_prompt("Select the least stable versions to install:")
+        .items(&["Any", "Beta", "Release"])
+        .default(match default {
+            None | Some(ReleaseChannel::Alpha) => 0,
+            Some(ReleaseChannel::Beta) => 1,
+            Some(ReleaseChannel::Release) => 2,
+        })
+        .interact()
+        .map(|i| match i {
+            0 => None,
+            1 => Some(ReleaseChannel::Beta),
+            2 => Some(ReleaseChannel::Release),
+            _ => unreachable!(),
+        })
+        .map_err(Into::into)
+}
+
 pub async fn pick_minecraft_version(default: Option<&str>) -> Result<String> {
     let versions = MC_VERSIONS.get_or_try_init(fetch_versions).await;
     let choice = match versions {
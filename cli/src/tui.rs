@@ -1,9 +1,16 @@
-use std::{borrow::Cow, fmt::Display, ops::Range, sync::LazyLock};
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    io::Write,
+    ops::Range,
+    process::{Command, Stdio},
+    sync::LazyLock,
+};
 
 use anyhow::anyhow;
 use dialoguer::theme::ColorfulTheme;
 use ferrallay::{
-    client::schema::{Project, ProjectId, VersionId},
+    client::schema::{Project, ProjectId, ReleaseChannel, VersionId},
     config::{Mod, Profile, VersionedProject},
 };
 use indicatif::ProgressStyle;
@@ -27,6 +34,9 @@ pub(crate) use const_style;
 const CF: Painted<&str> = Painted::new("CF").red();
 const MR: Painted<&str> = Painted::new("MR").green();
 const GH: Painted<&str> = Painted::new("GH").magenta();
+const URL: Painted<&str> = Painted::new("URL").cyan();
+const MVN: Painted<&str> = Painted::new("MVN").blue();
+const LOCAL: Painted<&str> = Painted::new("LOCAL").yellow();
 
 pub const CROSS: &str = "✗";
 pub const CROSS_RED: Painted<&str> = Painted::new(CROSS).red();
@@ -112,6 +122,9 @@ pub fn id_tag(id: &ProjectId) -> String {
         ProjectId::Forge(id) => format!("{CF} {id}"),
         ProjectId::Modrinth(id) => format!("{MR} {id}"),
         ProjectId::Github((own, repo)) => format!("{GH} {own}/{repo}"),
+        ProjectId::Url(url) => format!("{URL} {url}"),
+        ProjectId::Maven(id) => format!("{MVN} {id}"),
+        ProjectId::Local(path) => format!("{LOCAL} {path}"),
     }
 }
 pub fn vid_tag(id: &VersionId) -> String {
@@ -119,16 +132,52 @@ pub fn vid_tag(id: &VersionId) -> String {
         VersionId::Forge(id) => format!("{CF} {id}"),
         VersionId::Modrinth(id) => format!("{MR} {id}"),
         VersionId::Github(id) => format!("{GH} {id}"),
+        VersionId::Url(_) => format!("{URL} {id}"),
+        VersionId::Maven(id) => format!("{MVN} {id}"),
+        VersionId::Local(id) => format!("{LOCAL} {id}"),
+    }
+}
+
+pub fn channel_tag(channel: ReleaseChannel) -> Painted<&'static str> {
+    let tag = Painted::new(channel.as_str());
+    match channel {
+        ReleaseChannel::Release => tag.green(),
+        ReleaseChannel::Beta => tag.yellow(),
+        ReleaseChannel::Alpha => tag.red(),
     }
 }
 
 pub fn mod_single_line(m: &Mod) -> String {
     let id = id_tag(m.project());
     let name = match m.project() {
-        ProjectId::Forge(_) | ProjectId::Modrinth(_) => m.name.bold().to_string(),
+        ProjectId::Forge(_) | ProjectId::Modrinth(_) | ProjectId::Url(_) | ProjectId::Maven(_) | ProjectId::Local(_) => {
+            m.name.bold().to_string()
+        },
         ProjectId::Github((owner, repo)) => format!("{}/{}", owner.dim(), repo.bold()),
     };
-    format!("{id} ― {name}")
+    match m.channel {
+        Some(channel) => format!("{id} ― {name} [{}]", channel_tag(channel)),
+        None => format!("{id} ― {name}"),
+    }
+}
+
+/// Show `text` in the user's `$PAGER`, or `less`. Printed directly if the
+/// pager can't be started
+pub fn print_paged(text: &str) {
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| "less -R".to_owned());
+    let mut args = pager.split_whitespace();
+    let child = args
+        .next()
+        .and_then(|cmd| Command::new(cmd).args(args).stdin(Stdio::piped()).spawn().ok());
+    let Some(mut child) = child else {
+        print!("{text}");
+        return;
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // Pager exiting early closes the pipe, which isn't an error
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    let _ = child.wait();
 }
 
 pub fn print_mods(label: impl Display, mods: &[Mod]) {
@@ -163,11 +212,17 @@ pub async fn print_profile(profile: &Profile, active: bool) {
             |d| format!(
                 "\
     MC Version:  {}
+    Compat:      {}
     Mod Loader:  {}
+    Channel:     {}
+    Install:     {}
     Mods:        {}
     Modpack:     {}",
                 d.game_version.green(),
+                d.compat.green(),
                 format_args!("{:?}", d.loader).magenta(),
+                d.channel.map_or_else(|| "any".dim(), channel_tag),
+                d.install.map_or_else(|| "default".dim(), |s| s.as_str().cyan()),
                 d.mods.len().yellow(),
                 d.modpack.as_deref().map_or_else(|| CROSS_RED.to_string(), mod_single_line)
             )
@@ -241,6 +296,9 @@ _{}_
                 ProjectId::Forge(_) => "Forge",
                 ProjectId::Modrinth(_) => "Modrinth",
                 ProjectId::Github(_) => "Github",
+                ProjectId::Url(_) => "Url",
+                ProjectId::Maven(_) => "Maven",
+                ProjectId::Local(_) => "Local",
             },
             proj.id
         ),
@@ -263,7 +321,7 @@ pub fn fmt_profile_simple(p: &Profile, max_width: usize) -> String {
     let name = p.name();
     let path = p.path().display().to_string();
     let (name_width, path_width) = prop_widths(name.len(), path.len(), max_width);
-    format!("{} • {}", ellipsize!(^name, name_width), ellipsize!(^path, path_width),)
+    format!("{} • {}", ellipsize!(^name, name_width), ellipsize!(^path, path_width))
 }
 
 const fn prop_widths(a: usize, b: usize, max: usize) -> (usize, usize) {
@@ -302,6 +360,7 @@ assert_widths! {
 
 #[cfg(test)]
 mod tests {
+    #[allow(unused_imports)]
     use super::*;
 
     const TEST_STR: &str = "12345678909876543210";
@@ -76,7 +76,7 @@ impl CategoriesApi<'_> {
             req_builder = req_builder.query(&[("classesOnly", &param_value)]);
         }
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
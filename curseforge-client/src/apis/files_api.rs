@@ -165,7 +165,7 @@ impl FilesApi<'_> {
 
         req_builder = req_builder.json(&params.get_mod_files_request_body);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -214,7 +214,7 @@ impl FilesApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -262,7 +262,7 @@ impl FilesApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -310,7 +310,7 @@ impl FilesApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -384,7 +384,7 @@ impl FilesApi<'_> {
             req_builder = req_builder.query(&[("platformType", &param_value)]);
         }
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -124,7 +124,7 @@ impl FingerprintsApi<'_> {
 
         req_builder = req_builder.json(&params.get_fuzzy_matches_request_body);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -172,7 +172,7 @@ impl FingerprintsApi<'_> {
 
         req_builder = req_builder.json(&params.get_fuzzy_matches_request_body);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -217,7 +217,7 @@ impl FingerprintsApi<'_> {
 
         req_builder = req_builder.json(&params.get_fingerprint_matches_request_body);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -265,7 +265,7 @@ impl FingerprintsApi<'_> {
 
         req_builder = req_builder.json(&params.get_fingerprint_matches_request_body);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
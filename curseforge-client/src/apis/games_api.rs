@@ -144,7 +144,7 @@ impl GamesApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -194,7 +194,7 @@ impl GamesApi<'_> {
             req_builder = req_builder.query(&[("pageSize", &param_value)]);
         }
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -240,7 +240,7 @@ impl GamesApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -287,7 +287,7 @@ impl GamesApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -334,7 +334,7 @@ impl GamesApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -125,7 +125,7 @@ impl MinecraftApi<'_> {
             req_builder = req_builder.query(&[("includeAll", &param_value)]);
         }
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -172,7 +172,7 @@ impl MinecraftApi<'_> {
             req_builder = req_builder.query(&[("sortDescending", &param_value)]);
         }
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -219,7 +219,7 @@ impl MinecraftApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -266,7 +266,7 @@ impl MinecraftApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -181,7 +181,7 @@ impl ModsApi<'_> {
 
         req_builder = req_builder.json(&params.get_featured_mods_request_body);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -229,7 +229,7 @@ impl ModsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -285,7 +285,7 @@ impl ModsApi<'_> {
             req_builder = req_builder.query(&[("markup", &param_value)]);
         }
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -330,7 +330,7 @@ impl ModsApi<'_> {
 
         req_builder = req_builder.json(&params.get_mods_by_ids_list_request_body);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -426,7 +426,7 @@ impl ModsApi<'_> {
             req_builder = req_builder.query(&[("PremiumType", &param_value)]);
         }
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -1,10 +1,16 @@
 //! A unified api and schema around one or more Minecraft mod provider APIs.
 
+mod cached;
 mod common;
 mod curseforge;
-mod github;
-mod modrinth;
+pub(crate) mod direct;
+pub(crate) mod github;
+mod local;
+mod maven;
+pub(crate) mod modrinth;
 mod multi;
+mod ratelimit;
+mod revalidate;
 mod service_id;
 
 pub mod schema;
@@ -14,16 +20,26 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use self::schema::{GameVersion, Project, ProjectIdSvcType, Version, VersionIdSvcType};
-pub use self::service_id::ServiceId;
+use tokio::sync::Semaphore;
+
+pub(crate) use self::cached::{project_file, version_file};
+use self::schema::{GameVersion, Project, ProjectIdSvcType, ReleaseChannel, SearchQuery, Version, VersionId, VersionIdSvcType};
+pub use self::{
+    cached::CacheOptions,
+    direct::DirectClient,
+    github::GithubClient,
+    local::LocalClient,
+    maven::MavenClient,
+    ratelimit::{RateLimitPolicy, RateLimitService, RateLimiter},
+    revalidate::Revalidator,
+    service_id::ServiceId,
+};
 use crate::{Result, config::ModLoader, mgmt::LockedMod};
 
 #[rustfmt::skip]
 mod exported {
     /// The Curseforge API client used by this project. Convert `into` [Client](struct.Client.html) to use, don't access directly
     pub use curseforge::ApiClient as ForgeClient;
-    #[doc = "The Github API client used by this project. Convert `into` [Client](struct.Client.html) to use, don't access directly\n\n"]
-    pub use github::Octocrab as GithubClient;
     /// The Modrinth API client used by this project. Convert `into` [Client](struct.Client.html) to use, don't access directly
     pub use modrinth::ApiClient as ModrinthClient;
 }
@@ -50,10 +66,14 @@ macro_rules! api {
             $(#[$attr])*
             $vis async fn $name$(< $( $lt $( : $clt $(+ $dlt )* )? ),* >)?(&self, $($arg: $ty),*) -> Result<$ret> {
                 match &self.0 {
-                    ClientInner::Modrinth(c) => c.$name($($arg),*).await,
-                    ClientInner::Forge(c) => c.$name($($arg),*).await,
-                    ClientInner::Github(c) => c.$name($($arg),*).await,
+                    ClientInner::Modrinth(c) => ApiOps::$name(c, $($arg),*).await,
+                    ClientInner::Forge(c) => ApiOps::$name(c, $($arg),*).await,
+                    ClientInner::Github(c) => ApiOps::$name(c, $($arg),*).await,
+                    ClientInner::Direct(c) => ApiOps::$name(c, $($arg),*).await,
+                    ClientInner::Maven(c) => ApiOps::$name(c, $($arg),*).await,
+                    ClientInner::Local(c) => ApiOps::$name(c, $($arg),*).await,
                     ClientInner::Multi(c) => multi::proxy!(c; $name($($arg),*) $(+$prox $ret)?),
+                    ClientInner::Cached(c) => ApiOps::$name(&**c, $($arg),*).await,
                 }
             }
         )*}
@@ -108,7 +128,8 @@ api! {
     /// Get single [versions](Version) details by `ids`
     pub get_version(id: &(impl VersionIdSvcType + ?Sized)) -> Version;
 
-    /// Get the latest [versions](Version) of the project with `id`
+    /// Get the latest [versions](Version) of the project with `id` that is at
+    /// least as stable as `channel`
     ///
     /// # Errors
     ///
@@ -117,13 +138,36 @@ api! {
     /// Any network or api errors from the backing client
     ///
     /// [`ErrorKind::WrongService`]: crate::ErrorKind::WrongService
-    pub get_latest(id: &(impl ProjectIdSvcType + ?Sized), game_version: Option<&str>, loader: Option<ModLoader>) -> Version;
+    pub get_latest(id: &(impl ProjectIdSvcType + ?Sized), game_version: Option<&str>, loader: Option<ModLoader>, channel: Option<ReleaseChannel>) -> Version;
+
+    /// Get the changelogs of `versions` as written by their authors, by
+    /// version id. The format depends on the service, but is typically
+    /// markdown or plain text. Versions from services without changelogs are
+    /// left out
+    ///
+    /// Changelogs are fetched in as few requests as the service allows
+    ///
+    /// # Errors
+    ///
+    /// Any network or api errors from the backing client
+    ++pub get_changelogs(versions: &[&Version]) -> HashMap<VersionId, String>;
 
-    ++pub(crate) get_updates(game_version: &str, loader: ModLoader, mods: &[&LockedMod]) -> Vec<LockedMod>;
+    ++pub(crate) get_updates(game_version: &str, loader: ModLoader, channel: Option<ReleaseChannel>, mods: &[&LockedMod]) -> Vec<LockedMod>;
 
     /// Attempt to find an associated project for all `files`.
-    /// Takes an output arg so impls don't need to search for previously matched files
-    ++pub lookup(files: &[impl AsRef<Path>], out_results: &mut HashMap<PathBuf, Version>) -> Vec<crate::Error>;
+    /// Takes an output arg so impls don't need to search for previously matched files.
+    /// At most as many files as `jobs` has permits are hashed at once
+    ++pub lookup(files: &[impl AsRef<Path>], jobs: &Semaphore, out_results: &mut HashMap<PathBuf, Version>) -> Vec<crate::Error>;
+
+    /// Search for [projects](Project) matching `query`
+    ///
+    /// If called on a multi-client, then the results from each client will be
+    /// interleaved so that the top results of every client come first
+    ///
+    /// # Errors
+    ///
+    /// Any network or api errors from the backing client
+    +~pub search(query: &SearchQuery) -> Vec<Project>;
 }
 
 /// The main [`Client`] for accessing the various modding APIs
@@ -134,7 +178,10 @@ api! {
 /// successful result will be returned. If **all** clients fail the operation,
 /// then only the first [error](crate::Error) encountered will be returned. For
 /// convenience, the supported clients are re-exported as: [`ForgeClient`],
-/// [`ModrinthClient`], [`GithubClient`]
+/// [`ModrinthClient`], [`GithubClient`]. Projects downloaded directly from a
+/// url are provided by a [`DirectClient`], artifacts from any maven
+/// repository by a [`MavenClient`], and files from a local directory by a
+/// [`LocalClient`]
 ///
 /// # Example
 /// ```no_run
@@ -149,7 +196,7 @@ api! {
 /// let client: Client = vec![
 ///     ModrinthClient::builder("<USER_AGENT>").build()?.into(),
 ///     ForgeClient::builder("<USER_AGENT>").build()?.into(),
-///     GithubClient::builder().build()?.into(),
+///     GithubClient::from(ferrallay::github::Octocrab::builder().build()?).into(),
 /// ]
 /// .try_into()?;
 /// let m = client.get_project("mod_id").await;
@@ -165,7 +212,11 @@ enum ClientInner {
     Modrinth(ModrinthClient),
     Forge(ForgeClient),
     Github(GithubClient),
+    Direct(DirectClient),
+    Maven(MavenClient),
+    Local(LocalClient),
     Multi(Vec<Client>),
+    Cached(Box<cached::CachedClient>),
 }
 
 impl From<ClientInner> for Client {
@@ -174,8 +225,26 @@ impl From<ClientInner> for Client {
     }
 }
 
+impl Client {
+    /// Wrap this client so that [project](Project) and [version](Version)
+    /// metadata is cached on disk using `options`. Any other operations are
+    /// passed through unless [offline](CacheOptions::offline)
+    #[must_use]
+    pub fn cached(self, options: CacheOptions) -> Self {
+        ClientInner::Cached(Box::new(cached::CachedClient { inner: self, options })).into()
+    }
+
+    /// Returns `true` if this is a [cached](Self::cached) client in offline
+    /// mode
+    pub fn is_offline(&self) -> bool {
+        matches!(&self.0, ClientInner::Cached(c) if c.options.offline)
+    }
+}
+
 macro_rules! as_inner {
-    ($($ty:ty),*$(,)?) => {
+    (@offline) => { false };
+    (@offline offline) => { true };
+    ($($ty:ident $(($offline:ident))?),*$(,)?) => {
         /// Methods for accessing the raw underlying service clients for
         /// performing direct queries if something isn't supported
         impl Client {
@@ -185,6 +254,9 @@ macro_rules! as_inner {
                     match &self.0 {
                         ClientInner::$ty(v) => Some(v),
                         ClientInner::Multi(clients) => clients.iter().find_map(|c| c.[<as_ $ty:lower>]()),
+                        // Raw clients would bypass offline mode
+                        ClientInner::Cached(c) if c.options.offline && !as_inner!(@offline $($offline)?) => None,
+                        ClientInner::Cached(c) => c.inner.[<as_ $ty:lower>](),
                         _ => None,
                     }
                 }
@@ -196,4 +268,7 @@ as_inner! {
     Modrinth,
     Forge,
     Github,
+    Maven,
+    // Only reads files on disk
+    Local(offline),
 }
@@ -0,0 +1,552 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    future::Future,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tokio::sync::Semaphore;
+
+use super::{
+    ApiOps, Client,
+    schema::{GameVersion, Project, ProjectId, ProjectIdSvcType, ReleaseChannel, SearchQuery, Version, VersionId, VersionIdSvcType},
+};
+use crate::{ErrorKind, PathAbsolute, Result, config::ModLoader, mgmt::LockedMod};
+
+/// Settings for a client with [cached](Client::cached) metadata
+#[derive(Debug, Clone)]
+pub struct CacheOptions {
+    /// Directory where the metadata is stored
+    pub dir: PathAbsolute,
+    /// How long a cached entry is used before it is fetched again
+    pub ttl: Duration,
+    /// Only use cached metadata, even if expired, and fail anything that
+    /// would need the network with [`ErrorKind::Offline`]. Batch lookups fail
+    /// with [`ErrorKind::DoesNotExist`] if any of their ids isn't cached
+    pub offline: bool,
+}
+
+impl Default for CacheOptions {
+    fn default() -> Self {
+        Self {
+            dir: crate::mgmt::CACHE_DIR.join("meta"),
+            ttl: Duration::from_secs(60 * 60),
+            offline: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct CachedClient {
+    pub(super) inner: Client,
+    pub(super) options: CacheOptions,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    /// Unix timestamp in seconds
+    expires: u64,
+    data: T,
+}
+
+/// A cached value and whether it has expired
+type Cached<T> = (T, bool);
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Every project id that `id` could represent. Only ids from a single service
+/// should be present for anything but ambiguous ids, like a plain string
+fn project_keys(id: &(impl ProjectIdSvcType + ?Sized)) -> Vec<ProjectId> {
+    [
+        id.get_forge().ok().map(ProjectId::Forge),
+        id.get_modrinth().ok().map(|id| ProjectId::Modrinth(id.to_owned())),
+        id.get_github().ok().map(|(o, r)| ProjectId::Github((o.to_owned(), r.to_owned()))),
+        // Url, maven, and local projects are built from their id alone, so aren't cached
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// See [`project_keys`]
+fn version_keys(id: &(impl VersionIdSvcType + ?Sized)) -> Vec<VersionId> {
+    [
+        id.get_forge().ok().map(VersionId::Forge),
+        id.get_modrinth().ok().map(|id| VersionId::Modrinth(id.to_owned())),
+        id.get_github().ok().map(VersionId::Github),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// The path of the cached metadata of project `id`, relative to the
+/// [cache dir](CacheOptions::dir)
+pub(crate) fn project_file(id: &ProjectId) -> Option<String> {
+    let (svc, id): (_, &dyn std::fmt::Display) = match id {
+        ProjectId::Forge(id) => ("forge", id),
+        ProjectId::Modrinth(id) => ("modrinth", id),
+        ProjectId::Github(_) => ("github", id),
+        ProjectId::Url(_) | ProjectId::Maven(_) | ProjectId::Local(_) => return None,
+    };
+    Some(format!("projects/{svc}/{id}.json"))
+}
+
+/// See [`project_file`]
+pub(crate) fn version_file(id: &VersionId) -> Option<String> {
+    let svc = match id {
+        VersionId::Forge(_) => "forge",
+        VersionId::Modrinth(_) => "modrinth",
+        VersionId::Github(_) => "github",
+        // Maven and local versions are only unique within their project
+        VersionId::Url(_) | VersionId::Maven(_) | VersionId::Local(_) => return None,
+    };
+    Some(format!("versions/{svc}/{id}.json"))
+}
+
+/// The parameters of a version query, which its cached result is stored by
+struct QueryKey<'q> {
+    kind: &'static str,
+    game_version: Option<&'q str>,
+    loader: Option<ModLoader>,
+    channel: Option<ReleaseChannel>,
+}
+
+impl QueryKey<'_> {
+    /// The path of the cached result of this query for project `id`, relative
+    /// to the [cache dir](CacheOptions::dir)
+    fn file(&self, id: &ProjectId) -> Option<String> {
+        let project = project_file(id)?;
+        let key = [
+            self.game_version.unwrap_or("any"),
+            self.loader.map_or("any", ModLoader::as_str),
+            self.channel.map_or("any", ReleaseChannel::as_str),
+        ]
+        .map(|part| part.replace(|c: char| !c.is_ascii_alphanumeric() && c != '.', "-"))
+        .join("_");
+        Some(format!("{}/{}/{key}.json", project.strip_suffix(".json")?, self.kind))
+    }
+}
+
+impl CachedClient {
+    fn check_online(&self) -> Result<()> {
+        if self.options.offline {
+            Err(ErrorKind::Offline)?;
+        }
+        Ok(())
+    }
+
+    fn project_path(&self, id: &ProjectId) -> Option<PathAbsolute> {
+        Some(self.options.dir.join(project_file(id)?))
+    }
+
+    fn version_path(&self, id: &VersionId) -> Option<PathAbsolute> {
+        Some(self.options.dir.join(version_file(id)?))
+    }
+
+    async fn load<T: DeserializeOwned>(path: &Path) -> Option<Cached<T>> {
+        let data = tokio::fs::read(path).await.ok()?;
+        let entry: Entry<T> = serde_json::from_slice(&data).ok()?;
+        Some((entry.data, entry.expires <= now()))
+    }
+
+    /// Failures are ignored since the cache is only an optimization
+    async fn store<T: Serialize>(&self, path: &Path, data: &T) {
+        let entry = Entry {
+            expires: now() + self.options.ttl.as_secs(),
+            data,
+        };
+        let Ok(json) = serde_json::to_vec(&entry) else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = tokio::fs::create_dir_all(parent).await;
+        }
+        let _ = tokio::fs::write(path, json).await;
+    }
+
+    async fn cached_project(&self, id: &(impl ProjectIdSvcType + ?Sized)) -> Option<Cached<Project>> {
+        for key in project_keys(id) {
+            if let Some(found) = Self::load(&self.project_path(&key)?).await {
+                return Some(found);
+            }
+        }
+        None
+    }
+
+    async fn cached_version(&self, id: &(impl VersionIdSvcType + ?Sized)) -> Option<Cached<Version>> {
+        for key in version_keys(id) {
+            if let Some(found) = Self::load(&self.version_path(&key)?).await {
+                return Some(found);
+            }
+        }
+        None
+    }
+
+    /// Shared implementation of version queries, cached by their `key`.
+    /// Expired results are still used if offline, or if fetching fresh ones
+    /// fails
+    async fn query<T: Serialize + DeserializeOwned>(
+        &self,
+        id: &(impl ProjectIdSvcType + ?Sized),
+        key: &QueryKey<'_>,
+        fetch: impl Future<Output = Result<T>>,
+        project: impl Fn(&T) -> Option<&ProjectId>,
+    ) -> Result<T> {
+        let keys = project_keys(id);
+        let mut stale = None;
+        for path in keys.iter().filter_map(|k| key.file(k)) {
+            match Self::load(&self.options.dir.join(path)).await {
+                Some((data, false)) => return Ok(data),
+                Some((data, true)) if self.options.offline => return Ok(data),
+                Some((data, true)) => {
+                    stale = Some(data);
+                    break;
+                },
+                None => {},
+            }
+        }
+        self.check_online()?;
+        match fetch.await {
+            Ok(data) => {
+                // Ambiguous ids are stored by the project they turned out to be
+                let id = project(&data).or(keys.first().filter(|_| keys.len() == 1));
+                if let Some(path) = id.and_then(|id| key.file(id)) {
+                    self.store(&self.options.dir.join(path), &data).await;
+                }
+                Ok(data)
+            },
+            Err(e) => stale.ok_or(e),
+        }
+    }
+
+    async fn store_projects(&self, projects: &[Project]) {
+        for p in projects {
+            if let Some(path) = self.project_path(&p.id) {
+                self.store(&path, p).await;
+            }
+        }
+    }
+
+    async fn store_versions(&self, versions: &[Version]) {
+        for v in versions {
+            if let Some(path) = self.version_path(&v.id) {
+                self.store(&path, v).await;
+            }
+        }
+    }
+}
+
+/// Shared implementation of single item lookups. Expired entries are still
+/// used if offline, or if fetching a fresh one fails
+macro_rules! get_single {
+    ($self:ident.$cached:ident($id:ident) ?? $fetch:ident => $store:ident) => {{
+        let stale = match $self.$cached($id).await {
+            Some((data, false)) => return Ok(data),
+            Some((data, true)) if $self.options.offline => return Ok(data),
+            Some((data, true)) => Some(data),
+            None => None,
+        };
+        $self.check_online()?;
+        match Box::pin($self.inner.$fetch($id)).await {
+            Ok(data) => {
+                $self.$store(std::slice::from_ref(&data)).await;
+                Ok(data)
+            },
+            Err(e) => stale.ok_or(e),
+        }
+    }};
+}
+
+/// Shared implementation of batch lookups. See [`get_single`]. When offline,
+/// any id that could be cached but isn't fails the batch with
+/// [`ErrorKind::DoesNotExist`]
+macro_rules! get_multi {
+    ($self:ident.$cached:ident($ids:ident) [$keys:ident] ?? $fetch:ident => $store:ident) => {{
+        let mut found = vec![];
+        let mut stale = vec![];
+        let mut missing = vec![];
+        for &id in $ids {
+            match $self.$cached(id).await {
+                Some((data, false)) => found.push(data),
+                Some((data, true)) if $self.options.offline => found.push(data),
+                Some((data, true)) => {
+                    stale.push(data);
+                    missing.push(id);
+                },
+                None => missing.push(id),
+            }
+        }
+        if $self.options.offline && missing.iter().any(|&id| !$keys(id).is_empty()) {
+            Err(ErrorKind::DoesNotExist)?;
+        }
+        if missing.is_empty() || $self.options.offline {
+            return Ok(found);
+        }
+
+        match Box::pin($self.inner.$fetch(&missing)).await {
+            Ok(fetched) => {
+                $self.$store(&fetched).await;
+                found.extend(fetched);
+            },
+            Err(e) if found.is_empty() && stale.is_empty() => return Err(e),
+            Err(_) => found.extend(stale),
+        }
+        Ok(found)
+    }};
+}
+
+impl ApiOps for CachedClient {
+    async fn get_project(&self, id: &(impl ProjectIdSvcType + ?Sized)) -> Result<Project> {
+        get_single!(self.cached_project(id) ?? get_project => store_projects)
+    }
+
+    async fn get_projects(&self, ids: &[&dyn ProjectIdSvcType]) -> Result<Vec<Project>> {
+        get_multi!(self.cached_project(ids) [project_keys] ?? get_projects => store_projects)
+    }
+
+    async fn get_project_versions(
+        &self,
+        id: &(impl ProjectIdSvcType + ?Sized),
+        game_version: Option<&str>,
+        loader: Option<ModLoader>,
+    ) -> Result<Vec<Version>> {
+        let key = QueryKey {
+            kind: "versions",
+            game_version,
+            loader,
+            channel: None,
+        };
+        let fetch = async {
+            let versions = Box::pin(self.inner.get_project_versions(id, game_version, loader)).await?;
+            self.store_versions(&versions).await;
+            Ok(versions)
+        };
+        self.query(id, &key, fetch, |versions| versions.first().map(|v| &v.project_id))
+            .await
+    }
+
+    async fn get_game_versions(&self) -> Result<BTreeSet<GameVersion>> {
+        self.check_online()?;
+        Box::pin(self.inner.get_game_versions()).await
+    }
+
+    async fn get_versions(&self, ids: &[&dyn VersionIdSvcType]) -> Result<Vec<Version>> {
+        get_multi!(self.cached_version(ids) [version_keys] ?? get_versions => store_versions)
+    }
+
+    async fn get_version(&self, id: &(impl VersionIdSvcType + ?Sized)) -> Result<Version> {
+        get_single!(self.cached_version(id) ?? get_version => store_versions)
+    }
+
+    async fn get_latest(
+        &self,
+        id: &(impl ProjectIdSvcType + ?Sized),
+        game_version: Option<&str>,
+        loader: Option<ModLoader>,
+        channel: Option<ReleaseChannel>,
+    ) -> Result<Version> {
+        let key = QueryKey {
+            kind: "latest",
+            game_version,
+            loader,
+            channel,
+        };
+        let fetch = async {
+            let latest = Box::pin(self.inner.get_latest(id, game_version, loader, channel)).await?;
+            self.store_versions(std::slice::from_ref(&latest)).await;
+            Ok(latest)
+        };
+        self.query(id, &key, fetch, |latest| Some(&latest.project_id)).await
+    }
+
+    async fn get_changelogs(&self, versions: &[&Version]) -> Result<HashMap<VersionId, String>> {
+        self.check_online()?;
+        Box::pin(self.inner.get_changelogs(versions)).await
+    }
+
+    async fn get_updates(
+        &self,
+        game_version: &str,
+        loader: ModLoader,
+        channel: Option<ReleaseChannel>,
+        mods: &[&LockedMod],
+    ) -> Result<Vec<LockedMod>> {
+        self.check_online()?;
+        Box::pin(self.inner.get_updates(game_version, loader, channel, mods)).await
+    }
+
+    async fn lookup(
+        &self,
+        files: &[impl AsRef<Path>],
+        jobs: &Semaphore,
+        out_results: &mut HashMap<PathBuf, Version>,
+    ) -> Result<Vec<crate::Error>> {
+        self.check_online()?;
+        Box::pin(self.inner.lookup(files, jobs, out_results)).await
+    }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<Project>> {
+        self.check_online()?;
+        Box::pin(self.inner.search(query)).await
+    }
+}
+
+#[cfg(test)]
+impl Client {
+    /// An offline client with `versions` cached in `dir`. Each version is also
+    /// cached as the latest of its project for its first game version and
+    /// loader, so later ones replace earlier ones, and in the versions of its
+    /// project for its first loader
+    pub(crate) async fn offline_stub(dir: &Path, versions: &[Version]) -> Self {
+        use itertools::Itertools;
+
+        let client = CachedClient {
+            inner: Client(super::ClientInner::Multi(vec![])),
+            options: CacheOptions {
+                dir: PathAbsolute::new(dir).unwrap(),
+                ttl: Duration::ZERO,
+                offline: true,
+            },
+        };
+        client.store_versions(versions).await;
+        for v in versions {
+            let key = QueryKey {
+                kind: "latest",
+                game_version: v.game_versions.first().map(String::as_str),
+                loader: v.loaders.first().copied(),
+                channel: None,
+            };
+            client.store(&client.options.dir.join(key.file(&v.project_id).unwrap()), v).await;
+        }
+        for ((project, loader), versions) in versions.iter().into_group_map_by(|v| (&v.project_id, v.loaders.first().copied())) {
+            let game_versions = versions.iter().filter_map(|v| v.game_versions.first().map(String::as_str));
+            for game_version in game_versions.unique().map(Some).chain([None]) {
+                let key = QueryKey {
+                    kind: "versions",
+                    game_version,
+                    loader,
+                    channel: None,
+                };
+                let versions = { versions.iter() }
+                    .filter(|v| game_version.is_none_or(|gv| v.game_versions.iter().any(|v| v == gv)))
+                    .collect::<Vec<_>>();
+                client.store(&client.options.dir.join(key.file(project).unwrap()), &versions).await;
+            }
+        }
+        super::ClientInner::Cached(Box::new(client)).into()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ambiguous_keys() {
+        assert_eq!(project_keys("123"), [ProjectId::Forge(123), ProjectId::Modrinth("123".into())]);
+        assert_eq!(project_keys(&ProjectId::Modrinth("abc".into())), [ProjectId::Modrinth(
+            "abc".into()
+        )]);
+        assert_eq!(project_keys("owner/repo").len(), 2);
+    }
+
+    #[test]
+    fn entry_path_per_service() {
+        let client = CachedClient {
+            inner: Client(super::super::ClientInner::Multi(vec![])),
+            options: CacheOptions {
+                dir: PathAbsolute::new("/cache").unwrap(),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            &*client.project_path(&ProjectId::Github(("Owner".into(), "Repo".into()))).unwrap(),
+            Path::new("/cache/projects/github/owner/repo.json")
+        );
+        assert_eq!(
+            &*client.version_path(&VersionId::Forge(42)).unwrap(),
+            Path::new("/cache/versions/forge/42.json")
+        );
+        assert!(
+            client
+                .project_path(&ProjectId::Url("https://example.com/a.jar".parse().unwrap()))
+                .is_none()
+        );
+    }
+
+    fn offline_client(dir: &Path) -> CachedClient {
+        CachedClient {
+            inner: Client(super::super::ClientInner::Multi(vec![])),
+            options: CacheOptions {
+                dir: PathAbsolute::new(dir).unwrap(),
+                ttl: Duration::ZERO,
+                offline: true,
+            },
+        }
+    }
+
+    #[test]
+    fn query_path_per_key() {
+        let key = QueryKey {
+            kind: "latest",
+            game_version: Some("1.21 Pre-Release 1"),
+            loader: Some(ModLoader::Fabric),
+            channel: None,
+        };
+        assert_eq!(
+            key.file(&ProjectId::Modrinth("AANobbMI".into())).unwrap(),
+            "projects/modrinth/AANobbMI/latest/1.21-Pre-Release-1_fabric_any.json"
+        );
+        assert!(key.file(&ProjectId::Local("mods/*.jar".into())).is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn offline_queries() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = offline_client(dir.path());
+        let id = ProjectId::Modrinth("AANobbMI".into());
+        let key = QueryKey {
+            kind: "versions",
+            game_version: Some("1.21.1"),
+            loader: Some(ModLoader::Fabric),
+            channel: None,
+        };
+        client
+            .store(&client.options.dir.join(key.file(&id).unwrap()), &vec![Version::stub(
+                "AANobbMI", "abc",
+            )])
+            .await;
+
+        // Expired entries are still used offline
+        let versions = client
+            .get_project_versions(&id, Some("1.21.1"), Some(ModLoader::Fabric))
+            .await
+            .unwrap();
+        assert_eq!(versions[0].id, VersionId::Modrinth("abc".into()));
+        let err = client
+            .get_project_versions(&id, Some("1.20.1"), Some(ModLoader::Fabric))
+            .await
+            .unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::Offline));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn offline_missing_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = offline_client(dir.path());
+        client.store_versions(&[Version::stub("AANobbMI", "abc")]).await;
+
+        let found = VersionId::Modrinth("abc".into());
+        // Url versions are never cached
+        let url = VersionId::Url(super::super::schema::UrlVersion::default());
+        assert_eq!(client.get_versions(&[&found, &url]).await.unwrap().len(), 1);
+        let missing = VersionId::Modrinth("def".into());
+        let err = client.get_versions(&[&found, &missing]).await.unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::DoesNotExist));
+    }
+}
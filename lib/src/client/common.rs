@@ -23,10 +23,12 @@ macro_rules! get_latest {
             id: &(impl ProjectIdSvcType + ?Sized),
             game_version: Option<&str>,
             loader: Option<ModLoader>,
+            channel: Option<ReleaseChannel>,
         ) -> Result<Version> {
             self.get_project_versions(id, game_version, loader)
                 .await?
                 .into_iter()
+                .filter(|v| v.in_channel(channel))
                 .max_by(|a, b| a.date.cmp(&b.date))
                 .ok_or(crate::error::ErrorKind::DoesNotExist.into())
         }
@@ -51,6 +53,7 @@ pub(super) use get_version;
 
 pub fn compute_lookup_hashes<'p, R, F, C, K>(
     files: &'p [impl AsRef<Path>],
+    jobs: &Semaphore,
     done: &HashMap<K, impl std::any::Any>,
     calc_fn: C,
 ) -> (HashMap<R, &'p Path>, Vec<crate::Error>)
@@ -61,36 +64,36 @@ where
     C: Fn(&'p Path) -> F + Send + Sync,
 {
     let files = files.iter().map(AsRef::as_ref).filter(|p| !done.contains_key(*p));
-    compute(files, &calc_fn).into_iter().fold((HashMap::new(), vec![]), |mut acc, res| {
-        let (data, errs) = &mut acc;
-        match res {
-            Ok((hash, path)) => {
-                data.insert(hash, path);
-            },
-            Err(e) => {
-                errs.push(e);
-            },
-        }
-        acc
-    })
+    compute(files, jobs, &calc_fn)
+        .into_iter()
+        .fold((HashMap::new(), vec![]), |mut acc, res| {
+            let (data, errs) = &mut acc;
+            match res {
+                Ok((hash, path)) => {
+                    data.insert(hash, path);
+                },
+                Err(e) => {
+                    errs.push(e);
+                },
+            }
+            acc
+        })
 }
 
-fn compute<'p, R, F, I, C>(files: I, calculate: &C) -> Vec<Result<(R, &'p Path)>>
+fn compute<'p, R, F, I, C>(files: I, jobs: &Semaphore, calculate: &C) -> Vec<Result<(R, &'p Path)>>
 where
     I: IntoIterator<Item = &'p Path>,
     R: Send + Sync,
     F: Future<Output = Result<R>> + Send + Sync,
     C: Fn(&'p Path) -> F + Send + Sync,
 {
-    static PERMITS: Semaphore = Semaphore::const_new(50);
-
     let keys = Arc::new(Mutex::new(vec![]));
     TokioScope::scope_and_block(|scope| {
         for path in files {
             let keys = keys.clone();
             scope.spawn(async move {
                 let key = {
-                    let _permit = PERMITS.acquire().await.unwrap();
+                    let _permit = jobs.acquire().await;
                     calculate(path).await
                 };
 
@@ -3,27 +3,30 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use async_scoped::TokioScope;
 use curseforge::{
     apis::{
-        files_api::{GetFilesParams, GetModFilesParams},
+        categories_api::GetCategoriesParams,
+        files_api::{GetFilesParams, GetModFileChangelogParams, GetModFilesParams},
         fingerprints_api::GetFingerprintMatchesByGameParams,
         minecraft_api::GetMinecraftVersionsParams,
-        mods_api::{GetModParams, GetModsParams},
+        mods_api::{GetModParams, GetModsParams, SearchModsParams},
     },
-    models::{GetFingerprintMatchesRequestBody, GetModFilesRequestBody, GetModsByIdsListRequestBody},
+    models::{GetFingerprintMatchesRequestBody, GetModFilesRequestBody, GetModsByIdsListRequestBody, ModSearchSortField, SortOrder},
 };
+use tokio::sync::Semaphore;
 
 use super::{
     ApiOps, ForgeClient,
     common::{self, compute_lookup_hashes},
-    schema::{GameVersion, Project, ProjectIdSvcType, Version, VersionIdSvcType},
+    schema::{GameVersion, Project, ProjectIdSvcType, ReleaseChannel, SearchQuery, SearchSort, Version, VersionIdSvcType},
 };
 use crate::{
     Result,
     client::schema::{ProjectId, VersionId},
     config::{ModLoader, ProjectWithVersion, VersionedProject},
     hash,
-    mgmt::LockedMod,
+    mgmt::{LockedMod, ModOrigin},
 };
 
 impl ApiOps for ForgeClient {
@@ -101,7 +104,36 @@ impl ApiOps for ForgeClient {
         Ok(versions)
     }
 
-    async fn get_updates(&self, game_version: &str, loader: ModLoader, mods: &[&LockedMod]) -> Result<Vec<LockedMod>> {
+    async fn get_changelogs(&self, versions: &[&Version]) -> Result<HashMap<VersionId, String>> {
+        // There is no batched endpoint, so each file is fetched concurrently
+        let ((), changelogs) = TokioScope::scope_and_block(|s| {
+            for v in versions {
+                let (Ok(mod_id), Ok(file_id)) = (v.project_id.get_forge(), v.id.get_forge()) else {
+                    continue;
+                };
+                s.spawn(async move {
+                    let html = self
+                        .files()
+                        .get_mod_file_changelog(&GetModFileChangelogParams { mod_id, file_id })
+                        .await?
+                        .data;
+                    Ok::<_, crate::Error>((v.id.clone(), from::html_text(&html)))
+                });
+            }
+        });
+        changelogs
+            .into_iter()
+            .map(|r| r.map_err(|e| crate::Error::from(anyhow::Error::new(e))).and_then(|r| r))
+            .collect()
+    }
+
+    async fn get_updates(
+        &self,
+        game_version: &str,
+        loader: ModLoader,
+        channel: Option<ReleaseChannel>,
+        mods: &[&LockedMod],
+    ) -> Result<Vec<LockedMod>> {
         let mods = &mods
             .iter()
             .filter_map(|m| m.project().get_forge().map(|id| (id, *m)).ok())
@@ -113,21 +145,34 @@ impl ApiOps for ForgeClient {
 
         let updates = data
             .into_iter()
-            .flat_map(|m| {
-                m.latest_files_indexes.into_iter().filter_map(move |fi| {
-                    let vid = mods[&m.id].version().unwrap();
-                    if fi.mod_loader == loader.into() && vid != &fi.file_id && fi.game_version == game_version {
-                        fi.filename.try_into().ok().map(|file| LockedMod {
-                            id: ProjectWithVersion::new(ProjectId::Forge(m.id), Some(VersionId::Forge(fi.file_id)))
-                                .unwrap()
-                                .try_into()
-                                .unwrap(),
-                            sha1: String::new(),
-                            file,
-                        })
-                    } else {
-                        None
-                    }
+            .filter_map(|m| {
+                // Indexes hold the newest file of each release type, so take the newest allowed
+                // one
+                let fi = m
+                    .latest_files_indexes
+                    .into_iter()
+                    .filter(|fi| {
+                        fi.mod_loader == loader.into()
+                            && fi.game_version == game_version
+                            && channel.is_none_or(|c| ReleaseChannel::from(fi.release_type) >= c)
+                    })
+                    .max_by_key(|fi| fi.file_id)?;
+                if mods[&m.id].version().unwrap() == &fi.file_id {
+                    return None;
+                }
+                fi.filename.try_into().ok().map(|file| LockedMod {
+                    id: ProjectWithVersion::new(ProjectId::Forge(m.id), Some(VersionId::Forge(fi.file_id)))
+                        .unwrap()
+                        .try_into()
+                        .unwrap(),
+                    sha1: String::new(),
+                    size: 0,
+                    urls: vec![],
+                    file,
+                    channel: fi.release_type.into(),
+                    origin: ModOrigin::Direct,
+                    substitute: None,
+                    requires: None,
                 })
             })
             .collect();
@@ -135,8 +180,13 @@ impl ApiOps for ForgeClient {
         Ok(updates)
     }
 
-    async fn lookup(&self, files: &[impl AsRef<Path>], out_results: &mut HashMap<PathBuf, Version>) -> Result<Vec<crate::Error>> {
-        let (fprints, errors) = compute_lookup_hashes(files, out_results, hash::forge_fingerprint);
+    async fn lookup(
+        &self,
+        files: &[impl AsRef<Path>],
+        jobs: &Semaphore,
+        out_results: &mut HashMap<PathBuf, Version>,
+    ) -> Result<Vec<crate::Error>> {
+        let (fprints, errors) = compute_lookup_hashes(files, jobs, out_results, hash::forge_fingerprint);
         if fprints.is_empty() {
             return Ok(errors);
         }
@@ -161,6 +211,68 @@ impl ApiOps for ForgeClient {
 
         Ok(errors)
     }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<Project>> {
+        let class_id = query.project_type.map(from::class_id);
+        let category_id = match &query.category {
+            Some(cat) => match find_category(self, class_id, cat).await? {
+                Some(id) => Some(id),
+                // Unknown category can't match anything
+                None => return Ok(vec![]),
+            },
+            None => None,
+        };
+        let sort_field = match query.sort {
+            SearchSort::Relevance => None,
+            SearchSort::Downloads => Some(ModSearchSortField::TotalDownloads),
+            SearchSort::Follows => Some(ModSearchSortField::Popularity),
+            SearchSort::Newest => Some(ModSearchSortField::ReleaseDate),
+            SearchSort::Updated => Some(ModSearchSortField::LastUpdated),
+        };
+        let mods = self
+            .mods()
+            .search_mods(
+                &SearchModsParams::builder()
+                    .game_id(from::MINECRAFT_GAME_ID)
+                    .index(query.offset)
+                    .page_size(query.limit.clamp(1, 50).into())
+                    .maybe_class_id(class_id)
+                    .maybe_category_id(category_id)
+                    .maybe_game_version(query.game_version.as_deref())
+                    .maybe_search_filter(Some(query.query.as_str()).filter(|q| !q.is_empty()))
+                    .maybe_sort_field(sort_field)
+                    .maybe_sort_order(sort_field.map(|_| SortOrder::Descending))
+                    .maybe_mod_loader_type(query.loader.and_then(ModLoader::known).map(Into::into))
+                    .build(),
+            )
+            .await?
+            .data
+            .into_iter()
+            .filter_map(|m| m.try_into().ok())
+            .collect();
+
+        Ok(mods)
+    }
+}
+
+/// Find the id of the category matching `name` by id, slug, or name
+async fn find_category(client: &ForgeClient, class_id: Option<u32>, name: &str) -> Result<Option<u32>> {
+    if let Ok(id) = name.parse() {
+        return Ok(Some(id));
+    }
+    let id = client
+        .categories()
+        .get_categories(&GetCategoriesParams {
+            game_id: from::MINECRAFT_GAME_ID,
+            class_id,
+            classes_only: None,
+        })
+        .await?
+        .data
+        .into_iter()
+        .find(|c| c.slug.eq_ignore_ascii_case(name) || c.name.eq_ignore_ascii_case(name))
+        .map(|c| c.id);
+    Ok(id)
 }
 
 async fn fetch_mods(client: &ForgeClient, mod_ids: Vec<u64>) -> Result<Vec<curseforge::models::Mod>> {
@@ -182,7 +294,7 @@ mod from {
 
     use curseforge::{
         Error as ApiError, ErrorResponse,
-        models::{File, FileDependency, FileRelationType, HashAlgo, MinecraftGameVersion, ModAuthor, ModLoaderType},
+        models::{File, FileDependency, FileRelationType, FileReleaseType, HashAlgo, MinecraftGameVersion, ModAuthor, ModLoaderType},
     };
     use reqwest::StatusCode;
     use url::Url;
@@ -191,7 +303,9 @@ mod from {
         ErrorKind,
         client::{
             Client, ClientInner, ForgeClient,
-            schema::{Author, Dependency, DependencyType, GameVersion, Project, ProjectId, ProjectType, Version, VersionId},
+            schema::{
+                Author, Dependency, DependencyType, GameVersion, Project, ProjectId, ProjectType, ReleaseChannel, Version, VersionId,
+            },
         },
         config::ModLoader,
     };
@@ -249,6 +363,17 @@ mod from {
     }
 
 
+    pub const fn class_id(typ: ProjectType) -> u32 {
+        match typ {
+            ProjectType::Mod => ClassId::MOD,
+            ProjectType::ModPack => ClassId::MODPACK,
+            ProjectType::ResourcePack => ClassId::RESOURCEPACK,
+            ProjectType::DataPack => ClassId::DATAPACK,
+            ProjectType::Shader => ClassId::SHADER,
+        }
+    }
+
+
     impl From<ForgeClient> for Client {
         fn from(value: ForgeClient) -> Self {
             ClientInner::Forge(value).into()
@@ -329,6 +454,8 @@ mod from {
                 id: VersionId::Forge(file.id),
                 project_id: ProjectId::Forge(file.mod_id),
                 title: file.display_name,
+                number: None,
+                mirrors: file.download_url.as_ref().map(cdn_mirrors).unwrap_or_default(),
                 download_url: file.download_url,
                 filename: file
                     .file_name
@@ -336,6 +463,7 @@ mod from {
                     .expect("Curseforge API should always return a proper relative file"),
                 length: file.file_length,
                 date: file.file_date,
+                channel: file.release_type.into(),
                 sha1: file.hashes.into_iter().find(|h| matches!(h.algo, HashAlgo::Sha1)).map(|h| h.value),
                 deps: file.dependencies.into_iter().map(Into::into).collect(),
                 game_versions,
@@ -344,6 +472,16 @@ mod from {
         }
     }
 
+    impl From<FileReleaseType> for ReleaseChannel {
+        fn from(value: FileReleaseType) -> Self {
+            match value {
+                FileReleaseType::Release => Self::Release,
+                FileReleaseType::Beta => Self::Beta,
+                FileReleaseType::Alpha => Self::Alpha,
+            }
+        }
+    }
+
     impl From<FileDependency> for Dependency {
         fn from(value: FileDependency) -> Self {
             Self {
@@ -380,6 +518,40 @@ mod from {
     }
 
 
+    /// Convert the html of a changelog to plain text, keeping line breaks
+    pub fn html_text(html: &str) -> String {
+        const BREAKS: [&str; 5] = ["br", "/p", "/li", "/div", "/h"];
+        let mut text = String::with_capacity(html.len());
+        let mut rest = html;
+        while let Some(start) = rest.find('<') {
+            text.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find('>') else {
+                rest = &rest[start..];
+                break;
+            };
+            let tag = rest[start + 1..start + end].trim().to_ascii_lowercase();
+            if tag.starts_with("li") {
+                text.push_str("- ");
+            } else if BREAKS.iter().any(|b| tag.starts_with(b)) {
+                text.push('\n');
+            }
+            rest = &rest[start + end + 1..];
+        }
+        text.push_str(rest);
+        let text = text
+            .replace("&nbsp;", " ")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&amp;", "&");
+        text.lines()
+            .map(str::trim_end)
+            .filter(|l| !l.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     fn proj_website(class_id: Option<u32>, slug: &str) -> Option<Url> {
         class_id
             .map(ClassId)
@@ -388,6 +560,23 @@ mod from {
             .and_then(|url| url.join(slug).ok())
     }
 
+    /// Curseforge serves the same files from each of these hosts
+    const CDN_HOSTS: [&str; 2] = ["edge.forgecdn.net", "mediafilez.forgecdn.net"];
+    fn cdn_mirrors(url: &Url) -> Vec<Url> {
+        if !url.host_str().is_some_and(|host| CDN_HOSTS.contains(&host)) {
+            return vec![];
+        }
+        CDN_HOSTS
+            .iter()
+            .filter(|&&host| url.host_str() != Some(host))
+            .filter_map(|host| {
+                let mut mirror = url.clone();
+                mirror.set_host(Some(host)).ok()?;
+                Some(mirror)
+            })
+            .collect()
+    }
+
 
     #[cfg(test)]
     mod tests {
@@ -402,5 +591,11 @@ mod from {
         fn proj_website_unknown() {
             assert_eq!(proj_website(Some(u32::MAX), "test"), None);
         }
+
+        #[test]
+        fn changelog_text() {
+            let html = "<h3>Changes</h3><ul><li>Fixed &quot;crash&quot;</li><li>A &amp; B</li></ul><p>Thanks!<br>&nbsp;</p>";
+            assert_eq!(html_text(html), "Changes\n- Fixed \"crash\"\n- A & B\nThanks!");
+        }
     }
 }
@@ -0,0 +1,170 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+use tokio::sync::Semaphore;
+use url::Url;
+
+use super::{
+    ApiOps, Client, ClientInner,
+    github::from::guess_type,
+    schema::{
+        GameVersion, Project, ProjectId, ProjectIdSvcType, ProjectType, ReleaseChannel, SearchQuery, UrlVersion, Version, VersionId,
+        VersionIdSvcType,
+    },
+};
+use crate::{ErrorKind, Result, config::ModLoader, mgmt::LockedMod};
+
+/// Provides [url](ProjectId::Url) projects, which are downloaded directly from
+/// their url. No requests are made by this client, so a project is anything
+/// that is a valid url to a file
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DirectClient;
+
+impl From<DirectClient> for Client {
+    fn from(value: DirectClient) -> Self {
+        ClientInner::Direct(value).into()
+    }
+}
+
+impl ApiOps for DirectClient {
+    async fn get_project(&self, id: &(impl ProjectIdSvcType + ?Sized)) -> Result<Project> {
+        url_project(id.get_url()?)
+    }
+
+    async fn get_projects(&self, ids: &[&dyn ProjectIdSvcType]) -> Result<Vec<Project>> {
+        Ok(ids.iter().filter_map(|id| url_project(id.get_url().ok()?).ok()).collect())
+    }
+
+    async fn get_project_versions(
+        &self,
+        id: &(impl ProjectIdSvcType + ?Sized),
+        _game_version: Option<&str>,
+        _loader: Option<ModLoader>,
+    ) -> Result<Vec<Version>> {
+        Ok(vec![url_version(id.get_url()?, UrlVersion::default())?])
+    }
+
+    async fn get_game_versions(&self) -> Result<BTreeSet<GameVersion>> {
+        Err(ErrorKind::Unsupported.into())
+    }
+
+    async fn get_versions(&self, _ids: &[&dyn VersionIdSvcType]) -> Result<Vec<Version>> {
+        // Versions don't include the url they belong to
+        Err(ErrorKind::Unsupported.into())
+    }
+
+    async fn get_version(&self, _id: &(impl VersionIdSvcType + ?Sized)) -> Result<Version> {
+        Err(ErrorKind::Unsupported.into())
+    }
+
+    async fn get_latest(
+        &self,
+        id: &(impl ProjectIdSvcType + ?Sized),
+        _game_version: Option<&str>,
+        _loader: Option<ModLoader>,
+        _channel: Option<ReleaseChannel>,
+    ) -> Result<Version> {
+        url_version(id.get_url()?, UrlVersion::default())
+    }
+
+    async fn get_changelogs(&self, _versions: &[&Version]) -> Result<HashMap<VersionId, String>> {
+        Ok(HashMap::new())
+    }
+
+    async fn get_updates(
+        &self,
+        _game_version: &str,
+        _loader: ModLoader,
+        _channel: Option<ReleaseChannel>,
+        _mods: &[&LockedMod],
+    ) -> Result<Vec<LockedMod>> {
+        // Nothing to compare against without downloading the file again
+        Ok(vec![])
+    }
+
+    async fn lookup(
+        &self,
+        _files: &[impl AsRef<Path>],
+        _jobs: &Semaphore,
+        _out_results: &mut HashMap<PathBuf, Version>,
+    ) -> Result<Vec<crate::Error>> {
+        // Use Ok so multi client doesn't fail...
+        Ok(vec![ErrorKind::Unsupported.into()])
+    }
+
+    async fn search(&self, _query: &SearchQuery) -> Result<Vec<Project>> {
+        Err(ErrorKind::Unsupported.into())
+    }
+}
+
+/// The name of the file downloaded from `url`
+fn file_name(url: &Url) -> Result<&str> {
+    url.path_segments()
+        .and_then(Iterator::last)
+        .filter(|name| !name.is_empty())
+        .ok_or(ErrorKind::InvalidIdentifier.into())
+}
+
+fn url_project(url: Url) -> Result<Project> {
+    let name = file_name(&url)?.to_owned();
+    Ok(Project {
+        project_type: if name.ends_with(".mrpack") {
+            ProjectType::ModPack
+        } else {
+            guess_type(&name)
+        },
+        slug: url.to_string(),
+        name,
+        description: String::new(),
+        downloads: 0,
+        created: None,
+        updated: None,
+        icon: None,
+        authors: vec![],
+        categories: vec![],
+        license: None,
+        website: None,
+        source_url: None,
+        id: ProjectId::Url(url),
+    })
+}
+
+/// Build the [version](Version) downloaded from `url` with the `expected`
+/// contents
+pub(crate) fn url_version(url: Url, expected: UrlVersion) -> Result<Version> {
+    let filename = file_name(&url)?.to_owned();
+    Ok(Version {
+        title: filename.clone(),
+        number: None,
+        filename: filename.try_into().map_err(|_| ErrorKind::InvalidIdentifier)?,
+        length: expected.size.unwrap_or_default(),
+        sha1: expected.sha1.clone(),
+        date: String::new(),
+        channel: ReleaseChannel::Release,
+        deps: vec![],
+        game_versions: vec![],
+        loaders: vec![],
+        download_url: Some(url.clone()),
+        mirrors: vec![],
+        project_id: ProjectId::Url(url),
+        id: VersionId::Url(expected),
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_from_url() {
+        let url: Url = "https://example.com/files/cool-mod-1.2.jar?dl=1".parse().unwrap();
+        let v = url_version(url.clone(), UrlVersion::default()).unwrap();
+        assert_eq!(v.filename.as_os_str(), "cool-mod-1.2.jar");
+        assert_eq!(v.project_id, ProjectId::Url(url));
+
+        assert!(url_version("https://example.com/".parse().unwrap(), UrlVersion::default()).is_err());
+    }
+}
@@ -1,16 +1,23 @@
 #![allow(clippy::cast_sign_loss)]
 
 use std::{
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeSet, HashMap, HashSet},
+    ops::Deref,
     path::{Path, PathBuf},
 };
 
 use async_scoped::TokioScope;
-use github::models::repos::Asset;
+use github::{
+    Octocrab,
+    models::{AssetId, repos::Asset},
+};
+use tokio::sync::Semaphore;
 
 use super::{
-    ApiOps, GithubClient, common,
-    schema::{GameVersion, Project, ProjectId, ProjectIdSvcType, Version, VersionId, VersionIdSvcType},
+    ApiOps, Client, common,
+    schema::{
+        GameVersion, Project, ProjectId, ProjectIdSvcType, ReleaseChannel, SearchQuery, SearchSort, Version, VersionId, VersionIdSvcType,
+    },
 };
 use crate::{
     ErrorKind, Result,
@@ -18,6 +25,59 @@ use crate::{
     mgmt::LockedMod,
 };
 
+mod graphql;
+
+/// The Github API client used by this project. Convert `into`
+/// [Client](super::Client) to use, don't access directly
+///
+/// Batched lookups use GraphQL when [authenticated](Self::new), since it
+/// always requires a token, and the REST api otherwise
+#[derive(Debug, Clone)]
+pub struct GithubClient {
+    octocrab: Octocrab,
+    authenticated: bool,
+}
+
+impl GithubClient {
+    /// Wrap `octocrab`, which should only be marked as `authenticated` when it
+    /// was built with a token
+    pub fn new(octocrab: Octocrab, authenticated: bool) -> Self {
+        Self { octocrab, authenticated }
+    }
+}
+
+/// Unauthenticated
+impl From<Octocrab> for GithubClient {
+    fn from(octocrab: Octocrab) -> Self {
+        Self::new(octocrab, false)
+    }
+}
+
+impl Deref for GithubClient {
+    type Target = Octocrab;
+
+    fn deref(&self) -> &Self::Target {
+        &self.octocrab
+    }
+}
+
+impl Client {
+    /// Get the version `vid` of the project `pid`. Github assets that can't be
+    /// fetched by id alone, such as when GraphQL fails, are fetched from their
+    /// repo with the REST api instead
+    pub(crate) async fn get_project_version(&self, pid: &ProjectId, vid: &VersionId) -> Result<Version> {
+        let res = self.get_version(vid).await;
+        if let (Err(_), Some(gh), Ok(repo), Ok(asset)) = (&res, self.as_github(), pid.get_github(), vid.get_github()) {
+            if !self.is_offline() {
+                if let Some(v) = fetch_assets(gh, &[(repo, asset)]).pop() {
+                    return Ok(v);
+                }
+            }
+        }
+        res
+    }
+}
+
 impl ApiOps for GithubClient {
     common::get_latest!();
 
@@ -28,41 +88,17 @@ impl ApiOps for GithubClient {
     }
 
     async fn get_projects(&self, ids: &[&dyn ProjectIdSvcType]) -> Result<Vec<Project>> {
-        // let repos = ids
-        //     .iter()
-        //     .filter_map(|id| id.get_github().ok())
-        //     .format_with(" ", |(own, repo), f| f(&format_args!("repo:{own}/{repo}")))
-        //     .to_string();
-        // let query = format!(
-        //     "query($q:String!){{\
-        //         search(type:REPOSITORY,query:$q,first:100){{\
-        //             ... on Repository{{\
-        //                 owner:owner.login \
-        //                 name \
-        //                 slug:full_name \
-        //                 created:created_at \
-        //                 updated:updated_at \
-        //                 authors:topics \
-        //                 website:url \
-        //                 source_url:url \
-        //                 license{{\
-        //                     name \
-        //                     spdx_id \
-        //                     url:html_url \
-        //                 }\
-        //             }\
-        //         }\
-        //     }\
-        //     variables{}",
-        //     json!({"q": repos})
-        // );
-        // let resp = self.graphql(&HashMap::from([("query", query)])).await?;
-
         let ids: Vec<_> = ids.iter().filter_map(|id| id.get_github().ok()).collect();
         if ids.is_empty() {
             return Ok(vec![]);
         }
-        // FIXME: Rate limiting
+        if self.authenticated {
+            if let Ok(projects) = graphql::fetch_repos(self, &ids).await {
+                return Ok(projects);
+            }
+        }
+
+        // Fall back to fetching each individually
         let ((), mods) = TokioScope::scope_and_block(|s| {
             for id in ids {
                 s.spawn(fetch_repo(self, id));
@@ -105,25 +141,21 @@ impl ApiOps for GithubClient {
                 .await?
                 .items
                 .into_iter()
-                .flat_map(|r| r.assets)
-                .filter_map(|a| {
-                    if check(&a) {
-                        Some(Version {
-                            id: VersionId::Github(a.id),
-                            project_id: ProjectId::Github((owner.to_owned(), repo.to_owned())),
-                            title: a.label.unwrap_or_default(),
-                            download_url: Some(a.url),
-                            filename: a.name.try_into().expect("Github API should always return a proper relative file"),
-                            length: a.size as _,
-                            date: a.updated_at.to_rfc3339(),
-                            sha1: None,
-                            deps: vec![],
-                            game_versions: game_version.iter().map(ToString::to_string).collect(),
-                            loaders: loader.iter().copied().collect(),
-                        })
+                .flat_map(|r| {
+                    let channel = if r.prerelease {
+                        ReleaseChannel::Beta
                     } else {
-                        None
-                    }
+                        ReleaseChannel::Release
+                    };
+                    r.assets.into_iter().map(move |a| (a, channel))
+                })
+                .filter(|(a, _)| check(a))
+                .map(|(a, channel)| {
+                    let mut v = asset_version(a, (owner, repo));
+                    v.channel = channel;
+                    v.game_versions.extend(game_version.map(ToString::to_string));
+                    v.loaders.extend(loader);
+                    v
                 })
                 .collect();
 
@@ -141,11 +173,52 @@ impl ApiOps for GithubClient {
         if ids.is_empty() {
             return Ok(vec![]);
         }
-        // Rest API doesn't support getting arbitrary assets by id. Needs GraphQL
-        Err(ErrorKind::Unsupported.into())
+        if !self.authenticated {
+            // Rest API doesn't support getting arbitrary assets by id without knowing the
+            // repo
+            return Err(ErrorKind::Unsupported.into());
+        }
+        graphql::fetch_assets(self, &ids).await
     }
 
-    async fn get_updates(&self, game_version: &str, loader: ModLoader, mods: &[&LockedMod]) -> Result<Vec<LockedMod>> {
+    async fn get_changelogs(&self, versions: &[&Version]) -> Result<HashMap<VersionId, String>> {
+        let mut by_repo = HashMap::<_, HashSet<_>>::new();
+        for v in versions {
+            if let (Ok((owner, repo)), Ok(asset)) = (v.project_id.get_github(), v.id.get_github()) {
+                by_repo.entry((owner, repo)).or_default().insert(asset);
+            }
+        }
+
+        // Page through the releases of each repo once, until every asset is found
+        let mut changelogs = HashMap::new();
+        for ((owner, repo), mut assets) in by_repo {
+            let mut page = self.repos(owner, repo).releases().list().per_page(100).send().await?;
+            loop {
+                for release in page.items {
+                    let body = release.body.unwrap_or_default();
+                    for asset in release.assets.iter().filter(|a| assets.remove(&a.id)) {
+                        changelogs.insert(VersionId::Github(asset.id), body.clone());
+                    }
+                }
+                if assets.is_empty() {
+                    break;
+                }
+                let Some(next) = self.get_page(&page.next).await? else {
+                    break;
+                };
+                page = next;
+            }
+        }
+        Ok(changelogs)
+    }
+
+    async fn get_updates(
+        &self,
+        game_version: &str,
+        loader: ModLoader,
+        channel: Option<ReleaseChannel>,
+        mods: &[&LockedMod],
+    ) -> Result<Vec<LockedMod>> {
         let mods: Vec<_> = mods.iter().filter(|lm| matches!(lm.project(), ProjectId::Github(_))).collect();
         if mods.is_empty() {
             return Ok(vec![]);
@@ -153,7 +226,7 @@ impl ApiOps for GithubClient {
 
         let mut updates = vec![];
         for m in mods {
-            if let Ok(up) = self.get_latest(m.project(), Some(game_version), Some(loader)).await {
+            if let Ok(up) = self.get_latest(m.project(), Some(game_version), Some(loader), channel).await {
                 if up.id != m.version().unwrap() {
                     updates.push(up.into());
                 }
@@ -163,10 +236,89 @@ impl ApiOps for GithubClient {
         Ok(updates)
     }
 
-    async fn lookup(&self, _files: &[impl AsRef<Path>], _out_results: &mut HashMap<PathBuf, Version>) -> Result<Vec<crate::Error>> {
+    async fn lookup(
+        &self,
+        _files: &[impl AsRef<Path>],
+        _jobs: &Semaphore,
+        _out_results: &mut HashMap<PathBuf, Version>,
+    ) -> Result<Vec<crate::Error>> {
         // Use Ok so multi client doesn't fail...
         Ok(vec![ErrorKind::Unsupported.into()])
     }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<Project>> {
+        // Repository search has no real filters, so add everything as search terms
+        // and restrict to minecraft related repos
+        let terms = [
+            Some(query.query.as_str()),
+            query.game_version.as_deref(),
+            query.loader.map(ModLoader::as_str),
+            query.category.as_deref(),
+            Some("minecraft in:name,description,topics fork:false"),
+        ];
+        let q = terms.into_iter().flatten().filter(|t| !t.is_empty()).collect::<Vec<_>>().join(" ");
+        let limit = query.limit.clamp(1, 100);
+        let repos = self
+            .octocrab
+            .search()
+            .repositories(&q)
+            .sort::<&str>(match query.sort {
+                SearchSort::Relevance | SearchSort::Newest => None,
+                SearchSort::Downloads | SearchSort::Follows => Some("stars"),
+                SearchSort::Updated => Some("updated"),
+            })
+            .order("desc")
+            .per_page(limit)
+            .page(query.offset / u32::from(limit) + 1)
+            .send()
+            .await?
+            .items
+            .into_iter()
+            .map(Project::from)
+            .filter(|p| query.project_type.is_none_or(|t| t == p.project_type))
+            .collect();
+
+        Ok(repos)
+    }
+}
+
+/// Fetch release assets from their known repos one at a time. Used as a
+/// fallback for [`get_versions`](ApiOps::get_versions) when GraphQL is not
+/// available. Any failures are ignored
+pub(crate) fn fetch_assets(client: &GithubClient, ids: &[((&str, &str), AssetId)]) -> Vec<Version> {
+    let ((), versions) = TokioScope::scope_and_block(|s| {
+        for &(repo, id) in ids {
+            s.spawn(async move {
+                let asset = client.repos(repo.0, repo.1).release_assets().get(id.into_inner()).await?;
+                Ok::<_, crate::Error>(asset_version(asset, repo))
+            });
+        }
+    });
+    versions.into_iter().filter_map(|r| r.ok().and_then(Result::ok)).collect()
+}
+
+fn asset_version(asset: Asset, (owner, repo): (&str, &str)) -> Version {
+    Version {
+        id: VersionId::Github(asset.id),
+        project_id: ProjectId::Github((owner.to_owned(), repo.to_owned())),
+        title: asset.label.unwrap_or_default(),
+        number: None,
+        // The api `url` only returns the file itself when requested as `application/octet-stream`, so use the
+        // same direct url as the GraphQL `downloadUrl`
+        download_url: Some(asset.browser_download_url),
+        mirrors: vec![],
+        filename: asset
+            .name
+            .try_into()
+            .expect("Github API should always return a proper relative file"),
+        length: asset.size as _,
+        date: asset.updated_at.to_rfc3339(),
+        channel: ReleaseChannel::Release,
+        sha1: None,
+        deps: vec![],
+        game_versions: vec![],
+        loaders: vec![],
+    }
 }
 
 #[inline]
@@ -175,7 +327,7 @@ async fn fetch_repo(client: &GithubClient, (owner, name): (&str, &str)) -> Resul
 }
 
 
-mod from {
+pub(super) mod from {
     use github::models::{Author, License};
     use reqwest::StatusCode;
 
@@ -250,7 +402,7 @@ mod from {
     }
 
 
-    fn guess_type(repo: &str) -> ProjectType {
+    pub(in crate::client) fn guess_type(repo: &str) -> ProjectType {
         let repo = repo.to_ascii_lowercase();
         if repo.contains("modpack") {
             ProjectType::ModPack
@@ -265,3 +417,51 @@ mod from {
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn rest_asset_version() {
+        let asset: Asset = serde_json::from_value(json!({
+            "url": "https://api.github.com/repos/CaffeineMC/sodium/releases/assets/12345",
+            "browser_download_url": "https://github.com/CaffeineMC/sodium/releases/download/0.5.8/sodium-0.5.8.jar",
+            "id": 12345,
+            "node_id": "RA_12345",
+            "name": "sodium-0.5.8.jar",
+            "label": null,
+            "state": "uploaded",
+            "content_type": "application/java-archive",
+            "size": 1024,
+            "download_count": 0,
+            "created_at": "2024-03-02T12:00:00Z",
+            "updated_at": "2024-03-02T12:00:00Z",
+            "uploader": null,
+        }))
+        .unwrap();
+        let v = asset_version(asset, ("CaffeineMC", "sodium"));
+
+        assert_eq!(v.id, VersionId::Github(AssetId(12345)));
+        assert_eq!(v.project_id, ProjectId::Github(("CaffeineMC".into(), "sodium".into())));
+        assert_eq!(v.filename.as_os_str(), "sodium-0.5.8.jar");
+        assert_eq!(v.length, 1024);
+        assert_eq!(
+            v.download_url.unwrap().as_str(),
+            "https://github.com/CaffeineMC/sodium/releases/download/0.5.8/sodium-0.5.8.jar"
+        );
+    }
+
+    #[tokio::test]
+    async fn unauthenticated_skips_graphql() {
+        let client = GithubClient::from(Octocrab::builder().build().unwrap());
+        let id = VersionId::Github(AssetId(12345));
+        // Fails before sending any request
+        let err = client.get_versions(&[&id]).await.unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::Unsupported));
+        assert!(client.get_projects(&[]).await.unwrap().is_empty());
+    }
+}
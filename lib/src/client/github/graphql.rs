@@ -0,0 +1,320 @@
+//! Batched lookups using the GraphQL api. All queries require authentication
+
+use std::{collections::HashMap, fmt::Write};
+
+use anyhow::anyhow;
+use github::models::AssetId;
+use itertools::Itertools;
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use url::Url;
+
+use super::from::guess_type;
+use crate::{
+    Result,
+    client::{
+        GithubClient,
+        schema::{Author, License, Project, ProjectId, ReleaseChannel, Version, VersionId},
+    },
+};
+
+/// Max number of items requested by a single query
+const BATCH_SIZE: usize = 50;
+
+const REPO_FRAGMENT: &str = "\
+fragment repo on Repository {
+    name
+    nameWithOwner
+    description
+    createdAt
+    updatedAt
+    url
+    owner { login url }
+    repositoryTopics(first: 20) { nodes { topic { name } } }
+    licenseInfo { name spdxId url }
+}";
+
+const ASSETS_QUERY: &str = "\
+query($ids: [ID!]!) {
+    nodes(ids: $ids) {
+        ... on ReleaseAsset {
+            name
+            size
+            updatedAt
+            downloadUrl
+            release {
+                name
+                isPrerelease
+                repository { name owner { login } }
+            }
+        }
+    }
+}";
+
+
+/// Fetch all repositories in `ids`. Any that don't exist are silently ignored
+pub async fn fetch_repos(client: &GithubClient, ids: &[(&str, &str)]) -> Result<Vec<Project>> {
+    let mut projects = Vec::with_capacity(ids.len());
+    for chunk in ids.chunks(BATCH_SIZE) {
+        let mut vars = serde_json::Map::new();
+        let mut params = String::new();
+        let mut fields = String::new();
+        for (i, (owner, name)) in chunk.iter().enumerate() {
+            vars.insert(format!("o{i}"), Value::from(*owner));
+            vars.insert(format!("n{i}"), Value::from(*name));
+            let _ = write!(params, "$o{i}:String!,$n{i}:String!,");
+            let _ = writeln!(fields, "r{i}: repository(owner:$o{i}, name:$n{i}) {{ ...repo }}");
+        }
+        let query = format!("query({params}) {{\n{fields}}}\n{REPO_FRAGMENT}");
+
+        let repos: HashMap<String, Option<Repo>> = query_data(client, &query, vars.into()).await?;
+        projects.extend(repos.into_values().flatten().map(Into::into));
+    }
+    Ok(projects)
+}
+
+/// Fetch all release assets with `ids`. Any that don't exist are silently
+/// ignored
+pub async fn fetch_assets(client: &GithubClient, ids: &[AssetId]) -> Result<Vec<Version>> {
+    #[derive(Deserialize)]
+    struct Nodes {
+        nodes: Vec<Option<Asset>>,
+    }
+
+    let mut versions = Vec::with_capacity(ids.len());
+    for chunk in ids.chunks(BATCH_SIZE) {
+        let node_ids = chunk.iter().map(|&id| asset_node_id(id)).collect_vec();
+        let Nodes { nodes } = query_data(client, ASSETS_QUERY, json!({ "ids": node_ids })).await?;
+        // Results are in the same order as the requested ids
+        versions.extend(chunk.iter().zip(nodes).filter_map(|(&id, asset)| asset?.into_version(id)));
+    }
+    Ok(versions)
+}
+
+/// Run `query` and return the response data. Errors are only returned if there
+/// is no data, since missing items are reported as errors along with any
+/// successful results
+async fn query_data<T: DeserializeOwned>(client: &GithubClient, query: &str, variables: Value) -> Result<T> {
+    #[derive(Deserialize)]
+    struct Response<T> {
+        data: Option<T>,
+        #[serde(default)]
+        errors: Vec<QueryError>,
+    }
+    #[derive(Deserialize)]
+    struct QueryError {
+        message: String,
+    }
+
+    let resp: Response<T> = client.graphql(&json!({ "query": query, "variables": variables })).await?;
+    resp.data.ok_or_else(|| {
+        anyhow!(
+            "Github GraphQL query failed: {}",
+            resp.errors.into_iter().map(|e| e.message).join("; ")
+        )
+        .into()
+    })
+}
+
+/// Build the legacy global node id of a release asset, which is still accepted
+/// as input by the api
+fn asset_node_id(id: AssetId) -> String {
+    use base64::prelude::*;
+    BASE64_STANDARD.encode(format!("012:ReleaseAsset{id}"))
+}
+
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Repo {
+    name: String,
+    name_with_owner: String,
+    description: Option<String>,
+    created_at: String,
+    updated_at: String,
+    url: Url,
+    owner: Owner,
+    repository_topics: Topics,
+    license_info: Option<RepoLicense>,
+}
+
+#[derive(Deserialize)]
+struct Owner {
+    login: String,
+    url: Option<Url>,
+}
+
+#[derive(Deserialize)]
+struct Topics {
+    nodes: Vec<TopicNode>,
+}
+#[derive(Deserialize)]
+struct TopicNode {
+    topic: Topic,
+}
+#[derive(Deserialize)]
+struct Topic {
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RepoLicense {
+    name: String,
+    spdx_id: Option<String>,
+    url: Option<Url>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Asset {
+    name: String,
+    size: u64,
+    updated_at: String,
+    download_url: Url,
+    release: Option<Release>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Release {
+    name: Option<String>,
+    is_prerelease: bool,
+    repository: ReleaseRepo,
+}
+#[derive(Deserialize)]
+struct ReleaseRepo {
+    name: String,
+    owner: Owner,
+}
+
+impl From<Repo> for Project {
+    fn from(repo: Repo) -> Self {
+        Self {
+            id: ProjectId::Github((repo.owner.login.clone(), repo.name.clone())),
+            project_type: guess_type(&repo.name_with_owner),
+            name: repo.name,
+            slug: repo.name_with_owner,
+            description: repo.description.unwrap_or_default(),
+            created: Some(repo.created_at),
+            updated: Some(repo.updated_at),
+            icon: None,
+            downloads: 0,
+            authors: vec![Author {
+                name: repo.owner.login,
+                url: repo.owner.url,
+            }],
+            categories: repo.repository_topics.nodes.into_iter().map(|n| n.topic.name).collect(),
+            license: repo.license_info.map(|l| License {
+                name: l.name,
+                spdx_id: l.spdx_id.unwrap_or_default(),
+                url: l.url,
+            }),
+            website: Some(repo.url.clone()),
+            source_url: Some(repo.url),
+        }
+    }
+}
+
+impl Asset {
+    fn into_version(self, id: AssetId) -> Option<Version> {
+        let release = self.release?;
+        Some(Version {
+            id: VersionId::Github(id),
+            project_id: ProjectId::Github((release.repository.owner.login, release.repository.name)),
+            title: release.name.unwrap_or_default(),
+            number: None,
+            download_url: Some(self.download_url),
+            mirrors: vec![],
+            filename: self.name.try_into().ok()?,
+            length: self.size,
+            date: self.updated_at,
+            channel: if release.is_prerelease {
+                ReleaseChannel::Beta
+            } else {
+                ReleaseChannel::Release
+            },
+            sha1: None,
+            deps: vec![],
+            game_versions: vec![],
+            loaders: vec![],
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_asset_id() {
+        assert_eq!(asset_node_id(AssetId(12345)), "MDEyOlJlbGVhc2VBc3NldDEyMzQ1");
+    }
+
+    #[test]
+    fn asset_into_version() {
+        let asset: Asset = serde_json::from_value(json!({
+            "name": "sodium-0.5.8.jar",
+            "size": 1024,
+            "updatedAt": "2024-03-02T12:00:00Z",
+            "downloadUrl": "https://github.com/CaffeineMC/sodium/releases/download/0.5.8/sodium-0.5.8.jar",
+            "release": {
+                "name": "Sodium 0.5.8",
+                "isPrerelease": true,
+                "repository": { "name": "sodium", "owner": { "login": "CaffeineMC" } },
+            },
+        }))
+        .unwrap();
+        let v = asset.into_version(AssetId(12345)).unwrap();
+
+        assert_eq!(v.id, VersionId::Github(AssetId(12345)));
+        assert_eq!(v.project_id, ProjectId::Github(("CaffeineMC".into(), "sodium".into())));
+        assert_eq!(v.title, "Sodium 0.5.8");
+        assert_eq!(v.filename.as_os_str(), "sodium-0.5.8.jar");
+        assert_eq!(v.length, 1024);
+        assert_eq!(v.channel, ReleaseChannel::Beta);
+        assert_eq!(
+            v.download_url.unwrap().as_str(),
+            "https://github.com/CaffeineMC/sodium/releases/download/0.5.8/sodium-0.5.8.jar"
+        );
+    }
+
+    #[test]
+    fn asset_without_release() {
+        let asset: Asset = serde_json::from_value(json!({
+            "name": "sodium-0.5.8.jar",
+            "size": 1024,
+            "updatedAt": "2024-03-02T12:00:00Z",
+            "downloadUrl": "https://github.com/CaffeineMC/sodium/releases/download/0.5.8/sodium-0.5.8.jar",
+            "release": null,
+        }))
+        .unwrap();
+        assert!(asset.into_version(AssetId(12345)).is_none());
+    }
+
+    #[test]
+    fn repo_into_project() {
+        let repo: Repo = serde_json::from_value(json!({
+            "name": "sodium",
+            "nameWithOwner": "CaffeineMC/sodium",
+            "description": null,
+            "createdAt": "2020-01-01T00:00:00Z",
+            "updatedAt": "2024-03-02T12:00:00Z",
+            "url": "https://github.com/CaffeineMC/sodium",
+            "owner": { "login": "CaffeineMC", "url": "https://github.com/CaffeineMC" },
+            "repositoryTopics": { "nodes": [{ "topic": { "name": "minecraft" } }] },
+            "licenseInfo": { "name": "GNU LGPLv3", "spdxId": "LGPL-3.0", "url": null },
+        }))
+        .unwrap();
+        let p = Project::from(repo);
+
+        assert_eq!(p.id, ProjectId::Github(("CaffeineMC".into(), "sodium".into())));
+        assert_eq!(p.slug, "CaffeineMC/sodium");
+        assert_eq!(p.description, "");
+        assert_eq!(p.authors[0].name, "CaffeineMC");
+        assert_eq!(p.categories, ["minecraft"]);
+        assert_eq!(p.license.unwrap().spdx_id, "LGPL-3.0");
+        assert_eq!(p.source_url.unwrap().as_str(), "https://github.com/CaffeineMC/sodium");
+    }
+}
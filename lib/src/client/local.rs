@@ -0,0 +1,400 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use itertools::Itertools;
+use tokio::sync::Semaphore;
+use url::Url;
+
+use super::{
+    ApiOps, Client, ClientInner,
+    github::from::guess_type,
+    schema::{GameVersion, Project, ProjectId, ProjectIdSvcType, ReleaseChannel, SearchQuery, Version, VersionId, VersionIdSvcType},
+};
+use crate::{
+    ErrorKind, Result,
+    checked_types::{PathAbsolute, PathScopedRef},
+    config::{ModLoader, VersionedProject},
+    hash::sha1_file,
+    mgmt::LockedMod,
+    versioning::wildcard_match,
+};
+
+/// Provides [local](ProjectId::Local) projects from the files under a root
+/// directory, such as a network share of in-house builds. A project is a path
+/// relative to the root where any part may contain `*` and `?` wildcards, and
+/// every file it matches is a version of it. The most recently modified file is
+/// the latest version
+#[derive(Debug, Clone)]
+pub struct LocalClient {
+    root: PathAbsolute,
+}
+
+impl LocalClient {
+    /// Create a client for the projects under `root`
+    pub fn new(root: PathAbsolute) -> Self {
+        Self { root }
+    }
+
+    /// Fetch the version of `pattern` that is the matching `file`, including
+    /// its checksum
+    pub(crate) async fn fetch_version(&self, pattern: &str, file: &str) -> Result<Version> {
+        let pattern_parts = scoped(pattern)?.components().collect::<Vec<_>>();
+        let file_parts = scoped(file)?.components().collect::<Vec<_>>();
+        let matched = pattern_parts.len() == file_parts.len()
+            && pattern_parts
+                .iter()
+                .zip(&file_parts)
+                .all(|(p, f)| matches!((p.as_os_str().to_str(), f.as_os_str().to_str()), (Some(p), Some(f)) if wildcard_match(p, f)));
+        if !matched {
+            return Err(ErrorKind::MissingVersion(ProjectId::Local(pattern.to_owned())).into());
+        }
+        let found = self.local_file(PathBuf::from(file)).await?;
+        with_sha1(local_version(pattern, &found)?, &found).await
+    }
+
+    /// All files matching `pattern`, ordered from oldest to newest
+    async fn files(&self, pattern: &str) -> Result<Vec<LocalFile>> {
+        let root = self.root.to_path_buf();
+        let pattern = scoped(pattern)?.to_path_buf();
+        let found = tokio::task::spawn_blocking(move || find_files(&root, &pattern))
+            .await
+            .map_err(anyhow::Error::new)??;
+
+        let mut files = Vec::with_capacity(found.len());
+        for rel in found {
+            files.push(self.local_file(rel).await?);
+        }
+        files.sort_by(|a, b| a.modified.cmp(&b.modified).then_with(|| a.rel.cmp(&b.rel)));
+        Ok(files)
+    }
+
+    /// The url of the file at `rel` under the root
+    fn file_url(&self, rel: &str) -> Option<Url> {
+        Url::from_file_path(self.root.join(scoped(rel).ok()?)).ok()
+    }
+
+    async fn local_file(&self, rel: PathBuf) -> Result<LocalFile> {
+        let path = self.root.join(&rel);
+        let meta = tokio::fs::metadata(&path).await?;
+        if !meta.is_file() {
+            return Err(ErrorKind::DoesNotExist.into());
+        }
+        Ok(LocalFile {
+            rel: rel.iter().map(|part| part.to_string_lossy()).join("/"),
+            modified: meta.modified()?,
+            size: meta.len(),
+            path: path.into(),
+        })
+    }
+}
+
+impl From<LocalClient> for Client {
+    fn from(value: LocalClient) -> Self {
+        ClientInner::Local(value).into()
+    }
+}
+
+impl Client {
+    /// Make sure `version` can only be read from disk if it is a
+    /// [local](VersionId::Local) version, by pointing it at its file under the
+    /// root of the [`LocalClient`]. Any other `file` urls, such as from a
+    /// modpack index or the lockfile, are removed
+    pub(crate) fn restrict_local(&self, version: &mut Version) {
+        if let VersionId::Local(rel) = &version.id {
+            version.download_url = self.as_local().and_then(|local| local.file_url(rel));
+            version.mirrors.clear();
+        } else {
+            version.download_url = version.download_url.take().filter(|url| url.scheme() != "file");
+            version.mirrors.retain(|url| url.scheme() != "file");
+        }
+    }
+}
+
+impl ApiOps for LocalClient {
+    async fn get_project(&self, id: &(impl ProjectIdSvcType + ?Sized)) -> Result<Project> {
+        let pattern = id.get_local()?;
+        if self.files(pattern).await?.is_empty() {
+            return Err(ErrorKind::DoesNotExist.into());
+        }
+        Ok(local_project(pattern))
+    }
+
+    /// Patterns that fail to be searched are left out like missing projects
+    async fn get_projects(&self, ids: &[&dyn ProjectIdSvcType]) -> Result<Vec<Project>> {
+        let mut projects = vec![];
+        for pattern in ids.iter().filter_map(|id| id.get_local().ok()) {
+            if self.files(pattern).await.is_ok_and(|files| !files.is_empty()) {
+                projects.push(local_project(pattern));
+            }
+        }
+        Ok(projects)
+    }
+
+    /// Versions are listed without computing their checksum
+    async fn get_project_versions(
+        &self,
+        id: &(impl ProjectIdSvcType + ?Sized),
+        _game_version: Option<&str>,
+        _loader: Option<ModLoader>,
+    ) -> Result<Vec<Version>> {
+        let pattern = id.get_local()?;
+        self.files(pattern).await?.iter().map(|f| local_version(pattern, f)).collect()
+    }
+
+    async fn get_game_versions(&self) -> Result<BTreeSet<GameVersion>> {
+        Err(ErrorKind::Unsupported.into())
+    }
+
+    async fn get_versions(&self, _ids: &[&dyn VersionIdSvcType]) -> Result<Vec<Version>> {
+        // Versions don't include the pattern they matched
+        Err(ErrorKind::Unsupported.into())
+    }
+
+    async fn get_version(&self, _id: &(impl VersionIdSvcType + ?Sized)) -> Result<Version> {
+        Err(ErrorKind::Unsupported.into())
+    }
+
+    async fn get_latest(
+        &self,
+        id: &(impl ProjectIdSvcType + ?Sized),
+        _game_version: Option<&str>,
+        _loader: Option<ModLoader>,
+        _channel: Option<ReleaseChannel>,
+    ) -> Result<Version> {
+        let pattern = id.get_local()?;
+        let latest = self
+            .files(pattern)
+            .await?
+            .pop()
+            .ok_or_else(|| ErrorKind::MissingVersion(ProjectId::Local(pattern.to_owned())))?;
+        with_sha1(local_version(pattern, &latest)?, &latest).await
+    }
+
+    async fn get_changelogs(&self, _versions: &[&Version]) -> Result<HashMap<VersionId, String>> {
+        Ok(HashMap::new())
+    }
+
+    async fn get_updates(
+        &self,
+        game_version: &str,
+        loader: ModLoader,
+        channel: Option<ReleaseChannel>,
+        mods: &[&LockedMod],
+    ) -> Result<Vec<LockedMod>> {
+        let mut updates = vec![];
+        for m in mods.iter().filter(|lm| matches!(lm.project(), ProjectId::Local(_))) {
+            if let Ok(up) = self.get_latest(m.project(), Some(game_version), Some(loader), channel).await {
+                // Builds may replace the same file, so also check the contents
+                if up.id != m.version().unwrap() || up.sha1.as_deref() != Some(m.sha1.as_str()) {
+                    updates.push(up.into());
+                }
+            }
+        }
+        Ok(updates)
+    }
+
+    async fn lookup(
+        &self,
+        _files: &[impl AsRef<Path>],
+        _jobs: &Semaphore,
+        _out_results: &mut HashMap<PathBuf, Version>,
+    ) -> Result<Vec<crate::Error>> {
+        // Use Ok so multi client doesn't fail...
+        Ok(vec![ErrorKind::Unsupported.into()])
+    }
+
+    async fn search(&self, _query: &SearchQuery) -> Result<Vec<Project>> {
+        Err(ErrorKind::Unsupported.into())
+    }
+}
+
+#[derive(Debug)]
+struct LocalFile {
+    /// Path relative to the root, always using `/`
+    rel: String,
+    path: PathBuf,
+    modified: SystemTime,
+    size: u64,
+}
+
+fn scoped(path: &str) -> Result<&PathScopedRef> {
+    match PathScopedRef::new(path) {
+        Ok(scoped) if !path.is_empty() => Ok(scoped),
+        _ => Err(ErrorKind::InvalidIdentifier.into()),
+    }
+}
+
+/// Find every file under `root` matching `pattern`, relative to `root`
+fn find_files(root: &Path, pattern: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut found = vec![PathBuf::new()];
+    for part in pattern.iter().map(|p| p.to_string_lossy()) {
+        let mut next = vec![];
+        for dir in found {
+            if part.contains(['*', '?']) {
+                let Ok(entries) = std::fs::read_dir(root.join(&dir)) else {
+                    continue;
+                };
+                for entry in entries {
+                    let name = entry?.file_name();
+                    if name.to_str().is_some_and(|name| wildcard_match(&part, name)) {
+                        next.push(dir.join(name));
+                    }
+                }
+            } else {
+                next.push(dir.join(&*part));
+            }
+        }
+        found = next;
+    }
+    found.retain(|rel| root.join(rel).is_file());
+    Ok(found)
+}
+
+/// Use the last part of `pattern` up to any wildcard as the name
+fn local_project(pattern: &str) -> Project {
+    let last = pattern.rsplit('/').next().unwrap_or(pattern);
+    let name = match last.find(['*', '?']) {
+        Some(i) => last[..i].trim_end_matches(['-', '_', '.', ' ']),
+        None => Path::new(last).file_stem().and_then(|s| s.to_str()).unwrap_or(last),
+    };
+    let name = if name.is_empty() { pattern } else { name };
+    Project {
+        slug: pattern.to_owned(),
+        name: name.to_owned(),
+        description: String::new(),
+        project_type: guess_type(name),
+        downloads: 0,
+        created: None,
+        updated: None,
+        icon: None,
+        authors: vec![],
+        categories: vec![],
+        license: None,
+        website: None,
+        source_url: None,
+        id: ProjectId::Local(pattern.to_owned()),
+    }
+}
+
+fn local_version(pattern: &str, file: &LocalFile) -> Result<Version> {
+    let filename = file.rel.rsplit('/').next().unwrap_or(&file.rel).to_owned();
+    Ok(Version {
+        title: file.rel.clone(),
+        number: None,
+        filename: filename.try_into().map_err(|_| ErrorKind::InvalidIdentifier)?,
+        length: file.size,
+        sha1: None,
+        date: rfc3339(file.modified),
+        channel: ReleaseChannel::Release,
+        deps: vec![],
+        game_versions: vec![],
+        loaders: vec![],
+        download_url: Url::from_file_path(&file.path).ok(),
+        mirrors: vec![],
+        project_id: ProjectId::Local(pattern.to_owned()),
+        id: VersionId::Local(file.rel.clone()),
+    })
+}
+
+async fn with_sha1(version: Version, file: &LocalFile) -> Result<Version> {
+    Ok(Version {
+        sha1: Some(sha1_file(&file.path).await?),
+        ..version
+    })
+}
+
+/// Format `time` as an RFC 3339 UTC timestamp, like the dates from other
+/// services
+fn rfc3339(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, time) = (secs / 86_400, secs % 86_400);
+
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let doe = days % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn wildcards() {
+        assert!(wildcard_match("cool-mod-*.jar", "cool-mod-1.2.jar"));
+        assert!(wildcard_match("cool-mod-?.jar", "cool-mod-1.jar"));
+        assert!(wildcard_match("*-*.jar", "a-b-c.jar"));
+        assert!(!wildcard_match("cool-mod-*.jar", "cool-mod-1.2.zip"));
+        assert!(!wildcard_match("cool-mod-?.jar", "cool-mod-12.jar"));
+
+        assert_eq!(local_project("builds/cool-mod-*.jar").name, "cool-mod");
+        assert_eq!(local_project("builds/cool-mod.jar").name, "cool-mod");
+    }
+
+    #[test]
+    fn only_local_versions_read_from_disk() {
+        let client = Client::from(LocalClient::new(PathAbsolute::new("/share").unwrap()));
+        let file_url = |path: &str| Url::from_file_path(path).unwrap();
+
+        let mut local = Version {
+            id: VersionId::Local("builds/cool-mod-1.2.jar".to_owned()),
+            download_url: Some(file_url("/home/user/secret.jar")),
+            mirrors: vec![file_url("/home/user/other.jar")],
+            ..Version::stub("AANobbMI", "abc")
+        };
+        client.restrict_local(&mut local);
+        assert_eq!(local.download_url, Some(file_url("/share/builds/cool-mod-1.2.jar")));
+        assert!(local.mirrors.is_empty());
+
+        let mut escaped = Version {
+            id: VersionId::Local("../secret.jar".to_owned()),
+            ..local.clone()
+        };
+        client.restrict_local(&mut escaped);
+        assert_eq!(escaped.download_url, None);
+
+        let mirror: Url = "https://cdn.example.com/cool-mod.jar".parse().unwrap();
+        let mut remote = Version {
+            download_url: Some(file_url("/home/user/secret.jar")),
+            mirrors: vec![file_url("/home/user/other.jar"), mirror.clone()],
+            ..Version::stub("AANobbMI", "abc")
+        };
+        client.restrict_local(&mut remote);
+        assert_eq!(remote.download_url, None);
+        assert_eq!(remote.mirrors, [mirror]);
+
+        // Nothing is read from disk without a local client
+        let mut local = Version {
+            id: VersionId::Local("builds/cool-mod-1.2.jar".to_owned()),
+            ..Version::stub("AANobbMI", "abc")
+        };
+        Client::from(crate::client::DirectClient).restrict_local(&mut local);
+        assert_eq!(local.download_url, None);
+    }
+
+    #[test]
+    fn mtime_date() {
+        assert_eq!(rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        assert_eq!(rfc3339(UNIX_EPOCH + Duration::from_secs(951_827_696)), "2000-02-29T12:34:56Z");
+    }
+}
@@ -0,0 +1,255 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+use async_scoped::TokioScope;
+use tokio::sync::Semaphore;
+use url::Url;
+
+use super::{
+    ApiOps, Client, ClientInner,
+    github::from::guess_type,
+    schema::{
+        GameVersion, MavenId, Project, ProjectId, ProjectIdSvcType, ReleaseChannel, SearchQuery, Version, VersionId, VersionIdSvcType,
+    },
+};
+use crate::{
+    ErrorKind, Result,
+    config::{ModLoader, VersionedProject},
+    maven,
+    mgmt::LockedMod,
+};
+
+const META_FILE: &str = "maven-metadata.xml";
+
+/// Provides [maven](ProjectId::Maven) projects from any maven repository.
+/// Versions are listed from the artifact's metadata, and checksums are taken
+/// from the sidecar files published next to each artifact. Maven artifacts
+/// have no game version or loader info, so those filters are ignored
+#[derive(Debug, Clone, Default)]
+pub struct MavenClient(reqwest::Client);
+
+impl MavenClient {
+    /// Create a client that sends `user_agent` with every request
+    pub fn new(user_agent: &str) -> Result<Self> {
+        Ok(Self(reqwest::Client::builder().user_agent(user_agent).build()?))
+    }
+
+    /// Create a client that sends every request with `http`, such as one made
+    /// by [`DownloadSettings::http_client`](crate::config::DownloadSettings::http_client)
+    /// to use a proxy
+    #[must_use]
+    pub fn with_http(http: reqwest::Client) -> Self {
+        Self(http)
+    }
+
+    /// Fetch `version` of the `id` artifact, including its size and checksum
+    pub(crate) async fn fetch_version(&self, id: &MavenId, version: &str) -> Result<Version> {
+        let file_name = file_name(id, version);
+        let artifact = maven::artifact(&self.0, &meta_url(id), version, &file_name).await?;
+        Ok(Version {
+            length: artifact.length,
+            sha1: artifact.sha1,
+            download_url: Some(artifact.url),
+            mirrors: vec![],
+            ..maven_version(id, version)?
+        })
+    }
+}
+
+impl From<MavenClient> for Client {
+    fn from(value: MavenClient) -> Self {
+        ClientInner::Maven(value).into()
+    }
+}
+
+impl ApiOps for MavenClient {
+    async fn get_project(&self, id: &(impl ProjectIdSvcType + ?Sized)) -> Result<Project> {
+        let id = id.get_maven()?;
+        maven::versions(&self.0, &meta_url(&id)).await?;
+        Ok(maven_project(id))
+    }
+
+    async fn get_projects(&self, ids: &[&dyn ProjectIdSvcType]) -> Result<Vec<Project>> {
+        let mut projects = vec![];
+        for id in ids.iter().filter_map(|id| id.get_maven().ok()) {
+            match self.get_project(&ProjectId::Maven(id)).await {
+                Ok(p) => projects.push(p),
+                Err(e) if matches!(e.kind(), ErrorKind::DoesNotExist) => {},
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(projects)
+    }
+
+    /// Versions are listed without fetching their size or checksum
+    async fn get_project_versions(
+        &self,
+        id: &(impl ProjectIdSvcType + ?Sized),
+        _game_version: Option<&str>,
+        _loader: Option<ModLoader>,
+    ) -> Result<Vec<Version>> {
+        let id = id.get_maven()?;
+        maven::versions(&self.0, &meta_url(&id))
+            .await?
+            .iter()
+            .map(|v| maven_version(&id, v))
+            .collect()
+    }
+
+    async fn get_game_versions(&self) -> Result<BTreeSet<GameVersion>> {
+        Err(ErrorKind::Unsupported.into())
+    }
+
+    async fn get_versions(&self, _ids: &[&dyn VersionIdSvcType]) -> Result<Vec<Version>> {
+        // Versions don't include the artifact they belong to
+        Err(ErrorKind::Unsupported.into())
+    }
+
+    async fn get_version(&self, _id: &(impl VersionIdSvcType + ?Sized)) -> Result<Version> {
+        Err(ErrorKind::Unsupported.into())
+    }
+
+    async fn get_latest(
+        &self,
+        id: &(impl ProjectIdSvcType + ?Sized),
+        _game_version: Option<&str>,
+        _loader: Option<ModLoader>,
+        channel: Option<ReleaseChannel>,
+    ) -> Result<Version> {
+        let id = id.get_maven()?;
+        let versions = maven::versions(&self.0, &meta_url(&id)).await?;
+        let Some(latest) = maven::latest(versions, |v| channel.is_none_or(|c| ReleaseChannel::from_name(v) >= c)) else {
+            return Err(ErrorKind::MissingVersion(ProjectId::Maven(id)).into());
+        };
+        self.fetch_version(&id, &latest).await
+    }
+
+    async fn get_changelogs(&self, _versions: &[&Version]) -> Result<HashMap<VersionId, String>> {
+        Ok(HashMap::new())
+    }
+
+    async fn get_updates(
+        &self,
+        game_version: &str,
+        loader: ModLoader,
+        channel: Option<ReleaseChannel>,
+        mods: &[&LockedMod],
+    ) -> Result<Vec<LockedMod>> {
+        let ((), latest) = TokioScope::scope_and_block(|s| {
+            for m in mods.iter().filter(|lm| matches!(lm.project(), ProjectId::Maven(_))) {
+                s.spawn(async move {
+                    let up = self.get_latest(m.project(), Some(game_version), Some(loader), channel).await.ok()?;
+                    (up.id != m.version().unwrap()).then(|| up.into())
+                });
+            }
+        });
+        Ok(latest.into_iter().filter_map(|r| r.ok().flatten()).collect())
+    }
+
+    async fn lookup(
+        &self,
+        _files: &[impl AsRef<Path>],
+        _jobs: &Semaphore,
+        _out_results: &mut HashMap<PathBuf, Version>,
+    ) -> Result<Vec<crate::Error>> {
+        // Use Ok so multi client doesn't fail...
+        Ok(vec![ErrorKind::Unsupported.into()])
+    }
+
+    async fn search(&self, _query: &SearchQuery) -> Result<Vec<Project>> {
+        Err(ErrorKind::Unsupported.into())
+    }
+}
+
+/// The directory of `id` in its repository
+fn artifact_url(id: &MavenId) -> Url {
+    let mut url = id.repo().clone();
+    url.path_segments_mut()
+        .expect("repository should be an http(s) url")
+        .pop_if_empty()
+        .extend(id.group().split('.'))
+        .push(id.artifact());
+    url
+}
+
+fn meta_url(id: &MavenId) -> Url {
+    let mut url = artifact_url(id);
+    url.path_segments_mut().unwrap().push(META_FILE);
+    url
+}
+
+fn file_name(id: &MavenId, version: &str) -> String {
+    match id.classifier() {
+        Some(classifier) => format!("{}-{version}-{classifier}.jar", id.artifact()),
+        None => format!("{}-{version}.jar", id.artifact()),
+    }
+}
+
+fn maven_project(id: MavenId) -> Project {
+    Project {
+        slug: format!("{}:{}", id.group(), id.artifact()),
+        name: id.artifact().to_owned(),
+        description: String::new(),
+        project_type: guess_type(id.artifact()),
+        downloads: 0,
+        created: None,
+        updated: None,
+        icon: None,
+        authors: vec![],
+        categories: vec![],
+        license: None,
+        website: Some(artifact_url(&id)),
+        source_url: None,
+        id: ProjectId::Maven(id),
+    }
+}
+
+fn maven_version(id: &MavenId, version: &str) -> Result<Version> {
+    let mut url = artifact_url(id);
+    let filename = file_name(id, version);
+    url.path_segments_mut().unwrap().push(version).push(&filename);
+    Ok(Version {
+        title: format!("{} {version}", id.artifact()),
+        number: Some(version.to_owned()),
+        filename: filename.try_into().map_err(|_| ErrorKind::InvalidIdentifier)?,
+        length: 0,
+        sha1: None,
+        date: String::new(),
+        channel: ReleaseChannel::from_name(version),
+        deps: vec![],
+        game_versions: vec![],
+        loaders: vec![],
+        download_url: Some(url),
+        mirrors: vec![],
+        project_id: ProjectId::Maven(id.clone()),
+        id: VersionId::Maven(version.to_owned()),
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn artifact_paths() {
+        let id: MavenId = "com.example.mods:cool-mod:fabric@https://maven.example.com/releases"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            meta_url(&id).as_str(),
+            "https://maven.example.com/releases/com/example/mods/cool-mod/maven-metadata.xml"
+        );
+
+        let v = maven_version(&id, "1.2.0").unwrap();
+        assert_eq!(v.filename.as_os_str(), "cool-mod-1.2.0-fabric.jar");
+        assert_eq!(
+            v.download_url.unwrap().as_str(),
+            "https://maven.example.com/releases/com/example/mods/cool-mod/1.2.0/cool-mod-1.2.0-fabric.jar"
+        );
+        assert_eq!(id.to_string().parse::<MavenId>().unwrap(), id);
+        assert!("com.example:".parse::<MavenId>().is_err());
+    }
+}
@@ -5,17 +5,20 @@ use std::{
 
 use modrinth::{
     apis::{
-        projects_api::{GetProjectParams, GetProjectsParams},
+        projects_api::{GetProjectParams, GetProjectsParams, SearchProjectsParams},
         version_files_api::{GetLatestVersionsFromHashesParams, VersionsFromHashesParams},
         versions_api::{GetProjectVersionsParams, GetVersionParams, GetVersionsParams},
     },
     models::{GetLatestVersionsFromHashesBody, HashList, Project as ApiProject, game_version_tag::VersionType},
 };
+use tokio::sync::Semaphore;
 
 use super::{
     ApiOps, ModrinthClient,
     common::{self, compute_lookup_hashes},
-    schema::{GameVersion, Project, ProjectIdSvcType, Version, VersionIdSvcType},
+    schema::{
+        GameVersion, Project, ProjectIdSvcType, ProjectType, ReleaseChannel, SearchQuery, SearchSort, Version, VersionId, VersionIdSvcType,
+    },
 };
 use crate::{
     Result,
@@ -107,7 +110,27 @@ impl ApiOps for ModrinthClient {
             .map_err(Into::into)
     }
 
-    async fn get_updates(&self, game_version: &str, loader: ModLoader, mods: &[&LockedMod]) -> Result<Vec<LockedMod>> {
+    async fn get_changelogs(&self, versions: &[&Version]) -> Result<HashMap<VersionId, String>> {
+        let ids: &Vec<_> = &versions.iter().filter_map(|v| v.id.get_modrinth().ok()).collect();
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        Ok(self
+            .versions()
+            .get_versions(&GetVersionsParams { ids })
+            .await?
+            .into_iter()
+            .filter_map(|v| Some((VersionId::Modrinth(v.id), v.changelog?)))
+            .collect())
+    }
+
+    async fn get_updates(
+        &self,
+        game_version: &str,
+        loader: ModLoader,
+        channel: Option<ReleaseChannel>,
+        mods: &[&LockedMod],
+    ) -> Result<Vec<LockedMod>> {
         use modrinth::models::get_latest_versions_from_hashes_body::Algorithm;
 
         let mods = mods
@@ -118,7 +141,7 @@ impl ApiOps for ModrinthClient {
             return Ok(vec![]);
         }
 
-        let updates = self
+        let latest = self
             .version_files()
             .get_latest_versions_from_hashes(&GetLatestVersionsFromHashesParams {
                 get_latest_versions_from_hashes_body: Some(&GetLatestVersionsFromHashesBody {
@@ -129,20 +152,42 @@ impl ApiOps for ModrinthClient {
                     loaders: vec![/* loader.as_str() */],
                 }),
             })
-            .await?
-            .into_iter()
-            .filter_map(|(sha1, v)| {
-                // Check loader here since api filter doesn't work
-                (mods[sha1.as_str()].version().unwrap() != &v.id && v.loaders.iter().any(|l| l == loader.as_str()))
-                    .then_some(Version::from(v).into())
-            })
-            .collect();
+            .await?;
+
+        let mut updates = vec![];
+        let mut unstable = vec![];
+        for (sha1, v) in latest {
+            let m = mods[sha1.as_str()];
+            // Check loader here since api filter doesn't work
+            if !v.loaders.iter().any(|l| l == loader.as_str()) {
+                continue;
+            }
+            let v = Version::from(v);
+            if !v.in_channel(channel) {
+                // Api only returns the newest, so search for a stable enough one instead
+                unstable.push(m);
+            } else if m.version().unwrap() != &v.id {
+                updates.push(v.into());
+            }
+        }
+        for m in unstable {
+            if let Ok(up) = self.get_latest(m.project(), Some(game_version), Some(loader), channel).await {
+                if up.id != m.version().unwrap() {
+                    updates.push(up.into());
+                }
+            }
+        }
 
         Ok(updates)
     }
 
-    async fn lookup(&self, files: &[impl AsRef<Path>], out_results: &mut HashMap<PathBuf, Version>) -> Result<Vec<crate::Error>> {
-        let (hashes, errors) = compute_lookup_hashes(files, out_results, |p| async move {
+    async fn lookup(
+        &self,
+        files: &[impl AsRef<Path>],
+        jobs: &Semaphore,
+        out_results: &mut HashMap<PathBuf, Version>,
+    ) -> Result<Vec<crate::Error>> {
+        let (hashes, errors) = compute_lookup_hashes(files, jobs, out_results, |p| async move {
             use tokio::{fs, io};
             let mut sha1 = Sha1Async::new();
             io::copy(&mut fs::File::open(p).await?, &mut sha1).await?;
@@ -152,24 +197,69 @@ impl ApiOps for ModrinthClient {
             return Ok(errors);
         }
 
-        let versions = self
-            .version_files()
-            .versions_from_hashes(&VersionsFromHashesParams {
-                hash_list: Some(&HashList {
-                    hashes: hashes.keys().cloned().collect(),
-                    algorithm: modrinth::models::hash_list::Algorithm::Sha1,
-                }),
-            })
-            .await?;
-
-        for (sha1, version) in versions {
+        for (sha1, version) in versions_from_sha1(self, hashes.keys().cloned().collect()).await? {
             if let Some(path) = hashes.get(&sha1) {
-                out_results.insert(path.to_path_buf(), version.into());
+                out_results.insert(path.to_path_buf(), version);
             }
         }
 
         Ok(errors)
     }
+
+    async fn search(&self, query: &SearchQuery) -> Result<Vec<Project>> {
+        let facets = search_facets(query);
+        let projects = self
+            .projects()
+            .search_projects(&SearchProjectsParams {
+                query: Some(&query.query).filter(|q| !q.is_empty()).map(String::as_str),
+                facets: Some(&facets).filter(|f| f != &"[]").map(String::as_str),
+                index: Some(match query.sort {
+                    SearchSort::Relevance => "relevance",
+                    SearchSort::Downloads => "downloads",
+                    SearchSort::Follows => "follows",
+                    SearchSort::Newest => "newest",
+                    SearchSort::Updated => "updated",
+                }),
+                offset: query.offset.try_into().ok(),
+                limit: Some(query.limit.clamp(1, 100)),
+            })
+            .await?
+            .hits
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        Ok(projects)
+    }
+}
+
+/// Build the search facets json. Each inner array is OR'd, and all arrays are
+/// AND'd together
+fn search_facets(query: &SearchQuery) -> String {
+    let mut facets = vec![];
+    if let Some(typ) = query.project_type {
+        facets.push(vec![
+            match typ {
+                ProjectType::Mod => "project_type:mod",
+                ProjectType::ModPack => "project_type:modpack",
+                ProjectType::ResourcePack => "project_type:resourcepack",
+                // Datapacks are listed as mods with the datapack "loader"
+                ProjectType::DataPack => "categories:datapack",
+                ProjectType::Shader => "project_type:shader",
+            }
+            .to_owned(),
+        ]);
+    }
+    if let Some(version) = &query.game_version {
+        facets.push(vec![format!("versions:{version}")]);
+    }
+    if let Some(loader) = query.loader.and_then(ModLoader::known) {
+        facets.push(vec![format!("categories:{}", loader.as_str())]);
+    }
+    if let Some(category) = &query.category {
+        facets.push(vec![format!("categories:{}", category.to_lowercase())]);
+    }
+    serde_json::to_string(&facets).expect("facets should always serialize")
 }
 
 #[inline]
@@ -181,13 +271,28 @@ async fn fetch_project(client: &ModrinthClient, mod_id: &str) -> Result<ApiProje
         .map_err(Into::into)
 }
 
+/// Find the versions of any files with the given `sha1` hashes, by their hash
+pub(crate) async fn versions_from_sha1(client: &ModrinthClient, hashes: Vec<String>) -> Result<HashMap<String, Version>> {
+    let versions = client
+        .version_files()
+        .versions_from_hashes(&VersionsFromHashesParams {
+            hash_list: Some(&HashList {
+                hashes,
+                algorithm: modrinth::models::hash_list::Algorithm::Sha1,
+            }),
+        })
+        .await?;
+    Ok(versions.into_iter().map(|(sha1, v)| (sha1, v.into())).collect())
+}
+
 mod from {
     use std::{str::FromStr, sync::LazyLock};
 
     use modrinth::{
         Error as ApiError, ErrorResponse,
         models::{
-            GameVersionTag, Project as ApiProject, ProjectLicense, Version as ApiVersion, VersionDependency, project::ProjectType,
+            GameVersionTag, Project as ApiProject, ProjectLicense, ProjectResult, Version as ApiVersion, VersionDependency,
+            project::ProjectType, project_result::ProjectType as ResultProjectType, version::VersionType,
             version_dependency::DependencyType as ModrinthDepType,
         },
     };
@@ -247,6 +352,35 @@ mod from {
         }
     }
 
+    impl From<ProjectResult> for schema::Project {
+        fn from(project: ProjectResult) -> Self {
+            let project_type = result_type(project.project_type);
+            Self {
+                id: ProjectId::Modrinth(project.project_id),
+                name: project.title,
+                website: HOME.join(proj_type_path(project_type)).and_then(|url| url.join(&project.slug)).ok(),
+                slug: project.slug,
+                description: project.description,
+                project_type: project_type.into(),
+                created: Some(project.date_created),
+                updated: Some(project.date_modified),
+                icon: project.icon_url.and_then(|url| url.parse().ok()),
+                downloads: project.downloads.try_into().unwrap_or_default(),
+                authors: vec![Author {
+                    name: project.author,
+                    url: None,
+                }],
+                categories: project.categories,
+                license: Some(schema::License {
+                    name: project.license.clone(),
+                    spdx_id: project.license,
+                    url: None,
+                }),
+                source_url: None,
+            }
+        }
+    }
+
     impl From<ApiVersion> for schema::Version {
         fn from(value: ApiVersion) -> Self {
             let file = {
@@ -260,13 +394,16 @@ mod from {
                 id: VersionId::Modrinth(value.id),
                 project_id: ProjectId::Modrinth(value.project_id),
                 title: value.name,
+                number: Some(value.version_number),
                 download_url: Some(file.url),
+                mirrors: vec![],
                 filename: file
                     .filename
                     .try_into()
                     .expect("Modrinth API should always return a proper relative file"),
                 length: file.size.try_into().unwrap_or_default(),
                 date: value.date_published,
+                channel: value.version_type.into(),
                 sha1: Some(file.hashes.sha1),
                 deps: value.dependencies.into_iter().filter_map(|d| d.try_into().ok()).collect(),
                 game_versions: value.game_versions,
@@ -279,6 +416,16 @@ mod from {
         }
     }
 
+    impl From<VersionType> for schema::ReleaseChannel {
+        fn from(value: VersionType) -> Self {
+            match value {
+                VersionType::Release => Self::Release,
+                VersionType::Beta => Self::Beta,
+                VersionType::Alpha => Self::Alpha,
+            }
+        }
+    }
+
     impl TryFrom<VersionDependency> for schema::Dependency {
         type Error = ();
 
@@ -331,6 +478,15 @@ mod from {
         }
     }
 
+    const fn result_type(typ: ResultProjectType) -> ProjectType {
+        match typ {
+            ResultProjectType::Mod => ProjectType::Mod,
+            ResultProjectType::Modpack => ProjectType::Modpack,
+            ResultProjectType::Resourcepack => ProjectType::Resourcepack,
+            ResultProjectType::Shader => ProjectType::Shader,
+        }
+    }
+
     const fn proj_type_path(ty: ProjectType) -> &'static str {
         // Trailing slash is necessary for Url::join
         match ty {
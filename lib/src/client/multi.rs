@@ -36,9 +36,41 @@ macro_rules! proxy {
         ret.filter(|c| errs.is_empty() || !c.is_empty())
             .ok_or_else(|| crate::ErrorKind::Multi(stringify!($name), errs).into())
     }};
+    ($clients:expr; $name:ident($($arg:expr),*) +~ $ret:ty) => {{
+        let mut results = vec![];
+        let mut errs = vec![];
+        for client in $clients {
+            match Box::pin(client.$name($($arg),*)).await {
+                Err(e) => errs.push(e),
+                Ok(res) => results.push(res.into_iter()),
+            }
+        }
+        if results.is_empty() {
+            Err(crate::ErrorKind::Multi(stringify!($name), errs).into())
+        } else {
+            Ok(multi::interleave(results).collect::<$ret>())
+        }
+    }};
 }
 pub(super) use proxy;
 
+/// Round-robin the items of all `iters` until every one is exhausted
+pub(super) fn interleave<T>(mut iters: Vec<impl Iterator<Item = T>>) -> impl Iterator<Item = T> {
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        while !iters.is_empty() {
+            i %= iters.len();
+            if let Some(item) = iters[i].next() {
+                i += 1;
+                return Some(item);
+            }
+            // Drop exhausted iterators, leaving `i` pointing at the next one
+            iters.remove(i);
+        }
+        None
+    })
+}
+
 
 impl TryFrom<Vec<Client>> for Client {
     type Error = Error;
@@ -67,3 +99,21 @@ impl TryFrom<&[Client]> for Client {
         value.to_vec().try_into()
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interleave_uneven() {
+        let res: Vec<_> = interleave(vec![
+            vec![1, 4, 6].into_iter(),
+            vec![].into_iter(),
+            vec![2, 5].into_iter(),
+            vec![3].into_iter(),
+        ])
+        .collect();
+        assert_eq!(res, [1, 2, 3, 4, 5, 6]);
+    }
+}
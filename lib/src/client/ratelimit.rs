@@ -0,0 +1,450 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use bytes::Bytes;
+use github::{
+    AuthState, OctocrabBuilder,
+    service::middleware::{auth_header::AuthHeaderLayer, base_uri::BaseUriLayer, extra_headers::ExtraHeadersLayer},
+};
+use http_body_util::BodyExt;
+use reqwest::{
+    StatusCode,
+    header::{HeaderMap, HeaderValue, USER_AGENT},
+};
+use serde::{Deserialize, Serialize};
+use tower::{Layer, Service};
+
+use super::GithubClient;
+use crate::Result;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// How a [`RateLimiter`] throttles and retries requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitPolicy {
+    /// Max number of times a request will be retried after a transient failure
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled with jitter for each following
+    /// attempt. Only used when the server doesn't say how long to wait
+    #[serde(with = "secs")]
+    pub backoff: Duration,
+    /// Upper limit on any single delay, whether from backoff or waiting for
+    /// the rate limit to reset
+    #[serde(with = "secs")]
+    pub max_wait: Duration,
+    /// Requests will be held until the rate limit resets once the remaining
+    /// requests reported by the server drops to this amount
+    pub reserve: u32,
+}
+
+impl Default for RateLimitPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            backoff: Duration::from_secs(1),
+            max_wait: Duration::from_secs(60),
+            reserve: 2,
+        }
+    }
+}
+
+/// Shared request middleware that reads the rate limit headers of responses to
+/// throttle requests ahead of time, and retries any that fail with a transient
+/// error. Clones share the same rate limit state
+///
+/// Can be added to the [`ModrinthClient`](super::ModrinthClient) and
+/// [`ForgeClient`](super::ForgeClient) builders as middleware, or used to
+/// [build](Self::github_client) a [`GithubClient`]
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter(Arc<Inner>);
+
+#[derive(Debug, Default)]
+struct Inner {
+    policy: RateLimitPolicy,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    remaining: Option<u32>,
+    reset: Option<Instant>,
+}
+
+impl RateLimiter {
+    /// Create a new limiter following `policy`
+    pub fn new(policy: RateLimitPolicy) -> Self {
+        Self(Arc::new(Inner {
+            policy,
+            state: Mutex::default(),
+        }))
+    }
+
+    /// The [policy](RateLimitPolicy) used by this limiter
+    pub fn policy(&self) -> &RateLimitPolicy {
+        &self.0.policy
+    }
+
+    /// Build a [`GithubClient`] with all requests passing through this limiter,
+    /// authenticated with the personal `token` if provided. Requests are sent
+    /// to `server` instead of the official api if set
+    ///
+    /// This replaces the retry layer of the default
+    /// [`Octocrab`](github::Octocrab) client, so connection failures are
+    /// retried following the limiter's [policy](RateLimitPolicy) along with
+    /// any other transient failure
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the system TLS certificates can't be loaded,
+    /// `server` isn't a valid uri, or `user_agent` isn't a valid header value
+    pub fn github_client(&self, user_agent: &str, server: Option<&url::Url>, token: Option<&str>) -> Result<GithubClient> {
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_native_roots()?
+            .https_or_http()
+            .enable_http1()
+            .build();
+        let client = hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new()).build(connector);
+
+        let (base_uri, upload_uri) = match server {
+            Some(server) => {
+                let uri = http::Uri::try_from(server.as_str()).map_err(anyhow::Error::from)?;
+                (uri.clone(), uri)
+            },
+            None => (
+                http::Uri::from_static("https://api.github.com"),
+                http::Uri::from_static("https://uploads.github.com"),
+            ),
+        };
+        let auth = token
+            .map(|t| HeaderValue::try_from(format!("Bearer {t}")))
+            .transpose()
+            .map_err(anyhow::Error::from)?;
+        let Ok(client) = OctocrabBuilder::new_empty()
+            .with_service(client)
+            .with_layer(self)
+            .with_layer(&ExtraHeadersLayer::new(Arc::new(vec![(
+                USER_AGENT,
+                HeaderValue::try_from(user_agent).map_err(anyhow::Error::from)?,
+            )])))
+            .with_layer(&BaseUriLayer::new(base_uri.clone()))
+            .with_layer(&AuthHeaderLayer::new(auth, base_uri, upload_uri))
+            .with_auth(AuthState::None)
+            .build();
+        Ok(GithubClient::new(client, token.is_some()))
+    }
+
+    /// Wait until the rate limit allows another request to be sent
+    async fn throttle(&self) {
+        let wait = {
+            let mut state = self.0.state.lock().unwrap();
+            let now = Instant::now();
+            let wait = match (state.remaining, state.reset) {
+                (_, Some(reset)) if reset <= now => {
+                    *state = State::default();
+                    None
+                },
+                (Some(remaining), Some(reset)) if remaining <= self.0.policy.reserve => Some(reset - now),
+                _ => None,
+            };
+            // Account for requests still in flight before their response updates the limit
+            if let Some(remaining) = &mut state.remaining {
+                *remaining = remaining.saturating_sub(1);
+            }
+            wait
+        };
+        if let Some(wait) = wait {
+            tokio::time::sleep(wait.min(self.0.policy.max_wait)).await;
+        }
+    }
+
+    /// Update the rate limit state from the response `headers`, returning how
+    /// long the server asked to wait before retrying, if at all
+    fn update(&self, status: StatusCode, headers: &HeaderMap, format: Reset) -> Option<Duration> {
+        let header = |name: &str| headers.get(name)?.to_str().ok()?.trim().parse::<u64>().ok();
+        let remaining = header("x-ratelimit-remaining");
+        let reset = header("x-ratelimit-reset").map(|reset| match format {
+            Reset::Delay => Duration::from_secs(reset),
+            Reset::Epoch => {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+                Duration::from_secs(reset.saturating_sub(now))
+            },
+        });
+
+        if remaining.is_some() || reset.is_some() {
+            let mut state = self.0.state.lock().unwrap();
+            state.remaining = remaining.map(|r| u32::try_from(r).unwrap_or(u32::MAX));
+            state.reset = reset.map(|r| Instant::now() + r);
+        }
+
+        header("retry-after").map(Duration::from_secs).or_else(|| {
+            let limited = status == StatusCode::TOO_MANY_REQUESTS || remaining == Some(0);
+            reset.filter(|_| limited)
+        })
+    }
+
+    /// How long to wait before retrying after the `attempt`th failure, or
+    /// [`None`] if the request shouldn't be retried. A `status` of [`None`]
+    /// indicates a connection failure
+    fn retry_delay(&self, attempt: u32, status: Option<StatusCode>, hint: Option<Duration>) -> Option<Duration> {
+        let policy = &self.0.policy;
+        if attempt >= policy.max_retries {
+            return None;
+        }
+        let transient = status.is_none_or(|s| {
+            matches!(
+                s,
+                StatusCode::TOO_MANY_REQUESTS
+                    | StatusCode::INTERNAL_SERVER_ERROR
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            )
+            // Github uses forbidden for exceeded rate limits
+            || (s == StatusCode::FORBIDDEN && hint.is_some())
+        });
+        if !transient {
+            return None;
+        }
+
+        let delay = hint.unwrap_or_else(|| {
+            let backoff = policy.backoff.saturating_mul(2u32.saturating_pow(attempt));
+            backoff.mul_f64(0.5 + fastrand::f64() / 2.0)
+        });
+        Some(delay.min(policy.max_wait))
+    }
+
+    /// Send `request` with `client`, throttling and retrying it as needed
+    pub(super) async fn send(&self, client: &reqwest::Client, mut request: reqwest::Request) -> reqwest::Result<reqwest::Response> {
+        let mut attempt = 0;
+        loop {
+            // Requests with a streaming body can't be cloned, so only get one attempt
+            let next = request.try_clone();
+            self.throttle().await;
+            let res = client.execute(request).await;
+            let delay = match &res {
+                Ok(resp) => {
+                    let hint = self.update(resp.status(), resp.headers(), Reset::Delay);
+                    self.retry_delay(attempt, Some(resp.status()), hint)
+                },
+                Err(e) if e.is_connect() || e.is_timeout() => self.retry_delay(attempt, None, None),
+                Err(_) => None,
+            };
+            match (next, delay) {
+                (Some(next), Some(delay)) => {
+                    tokio::time::sleep(delay).await;
+                    request = next;
+                    attempt += 1;
+                },
+                _ => return res,
+            }
+        }
+    }
+}
+
+/// How the `x-ratelimit-reset` header is sent
+#[derive(Debug, Clone, Copy)]
+enum Reset {
+    /// Seconds until the limit resets, as sent by Modrinth
+    Delay,
+    /// Unix timestamp of when the limit resets, as documented by Github
+    Epoch,
+}
+
+/// Implement the middleware of each generated `$client` for `$ty` using its
+/// inherent `send` method
+macro_rules! middleware {
+    ($ty:ty: $($client:ident),*) => {$(
+        impl $client::client::Middleware for $ty {
+            fn send<'a>(
+                &'a self,
+                client: &'a reqwest::Client,
+                request: reqwest::Request,
+            ) -> std::pin::Pin<Box<dyn std::future::Future<Output = reqwest::Result<reqwest::Response>> + Send + 'a>> {
+                Box::pin(Self::send(self, client, request))
+            }
+        }
+    )*};
+}
+pub(super) use middleware;
+
+middleware!(RateLimiter: modrinth, curseforge);
+
+
+impl<S> Layer<S> for RateLimiter {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            limiter: self.clone(),
+        }
+    }
+}
+
+/// [`Service`] created by the [`RateLimiter`] layer
+#[derive(Debug, Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: RateLimiter,
+}
+
+impl<S, ReqBody, B> Service<http::Request<ReqBody>> for RateLimitService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<B>> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+    ReqBody: http_body::Body<Data = Bytes> + From<Bytes> + Send + 'static,
+    ReqBody::Error: Into<BoxError>,
+    B: Send + 'static,
+{
+    type Error = BoxError;
+    type Future = BoxFuture<'static, std::result::Result<Self::Response, Self::Error>>;
+    type Response = S::Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: http::Request<ReqBody>) -> Self::Future {
+        // Use the service that was polled ready, leaving a fresh clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limiter = self.limiter.clone();
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+            // Buffer the body so it can be resent
+            let body = body.collect().await.map_err(Into::into)?.to_bytes();
+            let mut attempt = 0;
+            loop {
+                let mut req = http::Request::new(ReqBody::from(body.clone()));
+                *req.method_mut() = parts.method.clone();
+                *req.uri_mut() = parts.uri.clone();
+                *req.version_mut() = parts.version;
+                *req.headers_mut() = parts.headers.clone();
+
+                limiter.throttle().await;
+                std::future::poll_fn(|cx| inner.poll_ready(cx)).await.map_err(Into::into)?;
+                // The inner service only fails when the connection does
+                let (delay, res) = match inner.call(req).await {
+                    Ok(resp) => {
+                        let hint = limiter.update(resp.status(), resp.headers(), Reset::Epoch);
+                        (limiter.retry_delay(attempt, Some(resp.status()), hint), Ok(resp))
+                    },
+                    Err(e) => (limiter.retry_delay(attempt, None, None), Err(e.into())),
+                };
+                match delay {
+                    Some(delay) => {
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    },
+                    None => return res,
+                }
+            }
+        })
+    }
+}
+
+
+/// SerDe a [`Duration`] as fractional seconds
+mod secs {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Duration, ser: S) -> Result<S::Ok, S::Error> {
+        ser.serialize_f64(value.as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<Duration, D::Error> {
+        Duration::try_from_secs_f64(f64::deserialize(de)?).map_err(serde::de::Error::custom)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|&(k, v)| (k.parse().unwrap(), HeaderValue::from_str(v).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn retry_after_header() {
+        let limiter = RateLimiter::default();
+        let hint = limiter.update(StatusCode::TOO_MANY_REQUESTS, &headers(&[("retry-after", "5")]), Reset::Delay);
+        assert_eq!(hint, Some(Duration::from_secs(5)));
+        assert_eq!(
+            limiter.retry_delay(0, Some(StatusCode::TOO_MANY_REQUESTS), hint),
+            Some(Duration::from_secs(5))
+        );
+    }
+
+    #[test]
+    fn exhausted_limit_waits_for_reset() {
+        let limiter = RateLimiter::default();
+        let hint = limiter.update(
+            StatusCode::TOO_MANY_REQUESTS,
+            &headers(&[("x-ratelimit-remaining", "0"), ("x-ratelimit-reset", "30")]),
+            Reset::Delay,
+        );
+        assert_eq!(hint, Some(Duration::from_secs(30)));
+        let state = limiter.0.state.lock().unwrap();
+        assert_eq!(state.remaining, Some(0));
+        assert!(state.reset.is_some());
+    }
+
+    #[test]
+    fn epoch_reset() {
+        let limiter = RateLimiter::default();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let reset = (now + 30).to_string();
+        let hint = limiter
+            .update(
+                StatusCode::FORBIDDEN,
+                &headers(&[("x-ratelimit-remaining", "0"), ("x-ratelimit-reset", &reset)]),
+                Reset::Epoch,
+            )
+            .unwrap();
+        assert!(hint <= Duration::from_secs(30) && hint >= Duration::from_secs(29), "{hint:?}");
+
+        // Already passed
+        let hint = limiter.update(
+            StatusCode::FORBIDDEN,
+            &headers(&[("x-ratelimit-remaining", "0"), ("x-ratelimit-reset", "1000")]),
+            Reset::Epoch,
+        );
+        assert_eq!(hint, Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn no_retry_for_client_errors() {
+        let limiter = RateLimiter::default();
+        assert_eq!(limiter.retry_delay(0, Some(StatusCode::NOT_FOUND), None), None);
+        assert_eq!(limiter.retry_delay(0, Some(StatusCode::FORBIDDEN), None), None);
+    }
+
+    #[test]
+    fn backoff_is_bounded() {
+        let limiter = RateLimiter::new(RateLimitPolicy {
+            max_retries: 10,
+            max_wait: Duration::from_secs(5),
+            ..Default::default()
+        });
+        for attempt in 0..10 {
+            let delay = limiter.retry_delay(attempt, Some(StatusCode::BAD_GATEWAY), None).unwrap();
+            assert!(delay <= Duration::from_secs(5), "{delay:?}");
+        }
+        assert_eq!(limiter.retry_delay(10, Some(StatusCode::BAD_GATEWAY), None), None);
+    }
+}
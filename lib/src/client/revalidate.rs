@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+
+use reqwest::{
+    Method, StatusCode,
+    header::{ETAG, HeaderMap, HeaderValue, IF_NONE_MATCH},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{RateLimiter, ratelimit::middleware};
+
+/// Request middleware that stores the body of any `GET` response with an
+/// `ETag`, and sends `If-None-Match` with later requests to the same url so an
+/// unchanged response doesn't need to be downloaded again. Requests are sent
+/// through a [`RateLimiter`]
+///
+/// Can be added to the [`ModrinthClient`](super::ModrinthClient) and
+/// [`ForgeClient`](super::ForgeClient) builders as middleware
+#[derive(Debug, Clone)]
+pub struct Revalidator {
+    dir: PathBuf,
+    limiter: RateLimiter,
+}
+
+impl Revalidator {
+    /// Store revalidation data in `dir`, sending requests through `limiter`
+    pub fn new(dir: impl AsRef<Path>, limiter: RateLimiter) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            limiter,
+        }
+    }
+
+    async fn send(&self, client: &reqwest::Client, mut request: reqwest::Request) -> reqwest::Result<reqwest::Response> {
+        let Some(path) = self.etag_path(&request) else {
+            return self.limiter.send(client, request).await;
+        };
+
+        let stored = tokio::fs::read(&path)
+            .await
+            .ok()
+            .and_then(|data| serde_json::from_slice::<Tagged>(&data).ok());
+        if let Some(Ok(etag)) = stored.as_ref().map(|s| HeaderValue::from_str(&s.etag)) {
+            request.headers_mut().insert(IF_NONE_MATCH, etag);
+        }
+        let resp = self.limiter.send(client, request).await?;
+        match stored {
+            Some(stored) if resp.status() == StatusCode::NOT_MODIFIED => {
+                Ok(rebuild(StatusCode::OK, resp.headers().clone(), resp.version(), stored.body))
+            },
+            _ if resp.status() == StatusCode::OK => {
+                let Some(etag) = resp.headers().get(ETAG).and_then(|v| v.to_str().ok()).map(str::to_owned) else {
+                    return Ok(resp);
+                };
+                let status = resp.status();
+                let headers = resp.headers().clone();
+                let version = resp.version();
+                let body = resp.bytes().await?;
+                // Failures are ignored since this is only an optimization
+                if let Ok(text) = std::str::from_utf8(&body) {
+                    let tagged = Tagged { etag, body: text.into() };
+                    if let Ok(json) = serde_json::to_vec(&tagged) {
+                        if let Some(parent) = path.parent() {
+                            let _ = tokio::fs::create_dir_all(parent).await;
+                        }
+                        let _ = tokio::fs::write(&path, json).await;
+                    }
+                }
+                Ok(rebuild(status, headers, version, body))
+            },
+            _ => Ok(resp),
+        }
+    }
+
+    /// Where the revalidation data for `request` is stored, if it can be
+    /// revalidated
+    fn etag_path(&self, request: &reqwest::Request) -> Option<PathBuf> {
+        use sha1::{Digest, Sha1};
+
+        if request.method() != Method::GET || request.body().is_some() {
+            return None;
+        }
+        let key = Sha1::digest(request.url().as_str());
+        Some(self.dir.join(format!("{key:x}.json")))
+    }
+}
+
+middleware!(Revalidator: modrinth, curseforge);
+
+/// The stored body of a response that can be revalidated
+#[derive(Serialize, Deserialize)]
+struct Tagged {
+    etag: String,
+    body: String,
+}
+
+/// Create a new response after the body of the original has been consumed or
+/// replaced
+fn rebuild(status: StatusCode, headers: HeaderMap, version: http::Version, body: impl Into<reqwest::Body>) -> reqwest::Response {
+    let mut rebuilt = http::Response::new(body.into());
+    *rebuilt.status_mut() = status;
+    *rebuilt.headers_mut() = headers;
+    *rebuilt.version_mut() = version;
+    rebuilt.into()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_revalidate_get() {
+        let url: url::Url = "https://example.com/v2/project/abc".parse().unwrap();
+        let revalidator = Revalidator::new("/etags", RateLimiter::default());
+        let path = revalidator.etag_path(&reqwest::Request::new(Method::GET, url.clone())).unwrap();
+        assert!(path.starts_with("/etags"));
+        assert_eq!(revalidator.etag_path(&reqwest::Request::new(Method::GET, url.clone())), Some(path));
+        assert!(revalidator.etag_path(&reqwest::Request::new(Method::POST, url)).is_none());
+    }
+}
@@ -7,5 +7,6 @@ crate::mod_export! {
     game_version,
     license,
     project,
+    search,
     version,
 }
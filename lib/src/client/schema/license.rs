@@ -1,6 +1,7 @@
+use serde::{Deserialize, Serialize};
 use url::Url;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct License {
     pub name: String,
     pub spdx_id: String,
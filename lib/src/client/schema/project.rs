@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 use url::Url;
@@ -13,10 +13,41 @@ use crate::{
 svc_id_type! {
     /// The [client](crate::client) specific project id types
     #[derive(Debug, Clone, Eq, Hash, Ord)]
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
     pub enum ProjectId {
         Forge(u64),
         Modrinth(String = &str),
         Github((String, String) = (&str, &str)),
+        Url(Url),
+        Maven(MavenId),
+        Local(String = &str),
+    }
+}
+
+/// The coordinates of an artifact in a maven repository. Written as
+/// `group:artifact[:classifier]@repository`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MavenId {
+    repo: Url,
+    /// Validated `group:artifact[:classifier]`
+    coords: String,
+}
+impl MavenId {
+    pub fn repo(&self) -> &Url {
+        &self.repo
+    }
+
+    pub fn group(&self) -> &str {
+        self.coords.split(':').next().unwrap_or_default()
+    }
+
+    pub fn artifact(&self) -> &str {
+        self.coords.split(':').nth(1).unwrap_or_default()
+    }
+
+    pub fn classifier(&self) -> Option<&str> {
+        self.coords.split(':').nth(2)
     }
 }
 
@@ -43,7 +74,7 @@ impl ProjectType {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Project {
     pub id: ProjectId,
     pub slug: String,
@@ -67,6 +98,9 @@ impl Display for ProjectId {
             Self::Forge(id) => f.write_fmt(format_args!("{id}")),
             Self::Modrinth(id) => f.write_str(id),
             Self::Github((owner, name)) => f.write_fmt(format_args!("{}/{}", owner.to_lowercase(), name.to_lowercase())),
+            Self::Url(url) => f.write_str(url.as_str()),
+            Self::Maven(id) => id.fmt(f),
+            Self::Local(path) => write!(f, "{LOCAL_PREFIX}{path}"),
         }
     }
 }
@@ -79,6 +113,9 @@ impl<T: ProjectIdSvcType + ?Sized> PartialEq<T> for ProjectId {
             Self::Github((owner, repo)) => other
                 .get_github()
                 .is_ok_and(|(owner_other, repo_other)| owner == owner_other && repo == repo_other),
+            Self::Url(url) => other.get_url().is_ok_and(|other| *url == other),
+            Self::Maven(id) => other.get_maven().is_ok_and(|other| *id == other),
+            Self::Local(path) => other.get_local().is_ok_and(|other| path == other),
         }
     }
 }
@@ -91,6 +128,9 @@ impl<T: ProjectIdSvcType + ?Sized> PartialOrd<T> for ProjectId {
                 .get_github()
                 .ok()
                 .and_then(|other| (owner.as_str(), repo.as_str()).partial_cmp(&other)),
+            Self::Url(url) => other.get_url().ok().map(|other| url.cmp(&other)),
+            Self::Maven(id) => other.get_maven().ok().map(|other| id.cmp(&other)),
+            Self::Local(path) => other.get_local().ok().map(|other| path.as_str().cmp(other)),
         }
     }
 }
@@ -122,6 +162,33 @@ impl ProjectIdSvcType for ProjectId {
             Err(crate::ErrorKind::WrongService(self.to_string()))?
         }
     }
+
+    #[inline]
+    fn get_url(&self) -> Result<Url> {
+        if let Self::Url(url) = self {
+            Ok(url.clone())
+        } else {
+            Err(crate::ErrorKind::WrongService(self.to_string()))?
+        }
+    }
+
+    #[inline]
+    fn get_maven(&self) -> Result<MavenId> {
+        if let Self::Maven(id) = self {
+            Ok(id.clone())
+        } else {
+            Err(crate::ErrorKind::WrongService(self.to_string()))?
+        }
+    }
+
+    #[inline]
+    fn get_local(&self) -> Result<&str> {
+        if let Self::Local(path) = self {
+            Ok(path)
+        } else {
+            Err(crate::ErrorKind::WrongService(self.to_string()))?
+        }
+    }
 }
 
 impl ProjectIdSvcType for u64 {
@@ -139,6 +206,21 @@ impl ProjectIdSvcType for u64 {
     fn get_github(&self) -> Result<(&str, &str)> {
         Err(ErrorKind::InvalidIdentifier.into())
     }
+
+    #[inline]
+    fn get_url(&self) -> Result<Url> {
+        Err(ErrorKind::InvalidIdentifier.into())
+    }
+
+    #[inline]
+    fn get_maven(&self) -> Result<MavenId> {
+        Err(ErrorKind::InvalidIdentifier.into())
+    }
+
+    #[inline]
+    fn get_local(&self) -> Result<&str> {
+        Err(ErrorKind::InvalidIdentifier.into())
+    }
 }
 
 impl ProjectIdSvcType for str {
@@ -149,13 +231,38 @@ impl ProjectIdSvcType for str {
 
     #[inline]
     fn get_modrinth(&self) -> Result<&str> {
+        if is_foreign(self) {
+            return Err(ErrorKind::InvalidIdentifier.into());
+        }
         Ok(self)
     }
 
     #[inline]
     fn get_github(&self) -> Result<(&str, &str)> {
+        if is_foreign(self) {
+            return Err(ErrorKind::InvalidIdentifier.into());
+        }
         self.split_once('/').ok_or(ErrorKind::InvalidIdentifier.into())
     }
+
+    #[inline]
+    fn get_url(&self) -> Result<Url> {
+        if !is_url(self) {
+            return Err(ErrorKind::InvalidIdentifier.into());
+        }
+        self.parse().map_err(|_| ErrorKind::InvalidIdentifier.into())
+    }
+
+    #[inline]
+    fn get_maven(&self) -> Result<MavenId> {
+        self.parse()
+    }
+
+    /// Only accepts paths prefixed with `local:`
+    #[inline]
+    fn get_local(&self) -> Result<&str> {
+        self.strip_prefix(LOCAL_PREFIX).ok_or(ErrorKind::InvalidIdentifier.into())
+    }
 }
 
 impl ProjectIdSvcType for String {
@@ -173,4 +280,70 @@ impl ProjectIdSvcType for String {
     fn get_github(&self) -> Result<(&str, &str)> {
         self.as_str().get_github()
     }
+
+    #[inline]
+    fn get_url(&self) -> Result<Url> {
+        self.as_str().get_url()
+    }
+
+    #[inline]
+    fn get_maven(&self) -> Result<MavenId> {
+        self.as_str().get_maven()
+    }
+
+    #[inline]
+    fn get_local(&self) -> Result<&str> {
+        self.as_str().get_local()
+    }
+}
+
+/// Only absolute http(s) urls are treated as [url](ProjectId::Url) ids, so
+/// they are never mistaken for ids of another service
+fn is_url(id: &str) -> bool {
+    id.starts_with("https://") || id.starts_with("http://")
+}
+
+/// Prefix of [local](ProjectId::Local) ids when parsed from a string
+const LOCAL_PREFIX: &str = "local:";
+
+/// Urls, maven coordinates, and local paths always contain a `:`, which is
+/// never part of a modrinth or github id
+fn is_foreign(id: &str) -> bool {
+    id.contains(':')
+}
+
+
+impl Display for MavenId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}", self.coords, self.repo)
+    }
+}
+
+impl FromStr for MavenId {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (coords, repo) = s.split_once('@').ok_or(ErrorKind::InvalidIdentifier)?;
+        let repo = repo.get_url()?;
+        let parts = coords.split(':').count();
+        if !(2..=3).contains(&parts) || coords.split(':').any(|part| part.is_empty() || part.contains('/')) {
+            return Err(ErrorKind::InvalidIdentifier.into());
+        }
+        Ok(Self {
+            repo,
+            coords: coords.to_owned(),
+        })
+    }
+}
+
+impl Serialize for MavenId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MavenId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
 }
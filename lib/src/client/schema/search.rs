@@ -0,0 +1,56 @@
+use super::ProjectType;
+use crate::config::ModLoader;
+
+/// Parameters for a project [search](crate::Client::search)
+///
+/// Any unset filters are not applied. Paging is done per client, so a
+/// multi-client search may return up to `limit` results from each client
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    pub query: String,
+    pub project_type: Option<ProjectType>,
+    pub game_version: Option<String>,
+    pub loader: Option<ModLoader>,
+    pub category: Option<String>,
+    pub sort: SearchSort,
+    pub offset: u32,
+    pub limit: u8,
+}
+
+impl Default for SearchQuery {
+    fn default() -> Self {
+        Self {
+            query: String::new(),
+            project_type: None,
+            game_version: None,
+            loader: None,
+            category: None,
+            sort: SearchSort::default(),
+            offset: 0,
+            limit: 20,
+        }
+    }
+}
+
+impl SearchQuery {
+    /// Create a new query searching for `query` with all other values at their
+    /// defaults
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// How [search](crate::Client::search) results should be ordered. Not all
+/// clients support every option, in which case the closest match is used
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum SearchSort {
+    #[default]
+    Relevance,
+    Downloads,
+    Follows,
+    Newest,
+    Updated,
+}
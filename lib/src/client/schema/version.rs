@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
 use github::models::AssetId;
+use serde::{Deserialize, Serialize};
 use url::Url;
 
 use super::ProjectId;
@@ -14,28 +15,94 @@ use crate::{
 
 svc_id_type! {
     #[derive(Debug, Clone, Eq, Hash)]
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
     pub enum VersionId {
         Forge(u64),
         Modrinth(String = &str),
         Github(AssetId),
+        Url(UrlVersion),
+        Maven(String = &str),
+        Local(String = &str),
     }
 }
 
-#[derive(Debug, Clone)]
+/// The expected contents of a file downloaded directly from a
+/// [url](ProjectId::Url). Any missing values are filled in from the file once it
+/// is downloaded
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize)]
+pub struct UrlVersion {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha1: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Version {
     pub id: VersionId,
     pub project_id: ProjectId,
     pub title: String,
+    /// The version number given by its author, when it's separate from the
+    /// [title](Self::title)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<String>,
     pub download_url: Option<Url>,
+    /// Other urls the file can be downloaded from, tried in order when
+    /// `download_url` fails
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mirrors: Vec<Url>,
     pub filename: PathScoped,
     pub length: u64,
     pub date: String,
+    #[serde(default)]
+    pub channel: ReleaseChannel,
     pub sha1: Option<String>,
     pub deps: Vec<Dependency>,
     pub game_versions: Vec<String>,
     pub loaders: Vec<ModLoader>,
 }
 
+impl Version {
+    /// Whether this version is at least as stable as `min`. Always `true` when
+    /// `min` is `None`
+    pub fn in_channel(&self, min: Option<ReleaseChannel>) -> bool {
+        min.is_none_or(|min| self.channel >= min)
+    }
+
+    /// The project ids of all [required](DependencyType::Required) dependencies
+    pub fn required_deps(&self) -> impl Iterator<Item = &ProjectId> {
+        self.deps
+            .iter()
+            .filter(|d| matches!(d.dep_type, DependencyType::Required))
+            .map(|d| &d.project_id)
+    }
+}
+
+#[cfg(test)]
+impl Version {
+    /// A Modrinth release for fabric `1.21.1`, without any dependencies
+    pub(crate) fn stub(project: &str, id: &str) -> Self {
+        Self {
+            id: VersionId::Modrinth(id.to_owned()),
+            project_id: ProjectId::Modrinth(project.to_owned()),
+            title: id.to_owned(),
+            number: None,
+            download_url: None,
+            mirrors: vec![],
+            filename: format!("{id}.jar").try_into().unwrap(),
+            length: 0,
+            date: String::new(),
+            channel: ReleaseChannel::Release,
+            sha1: None,
+            deps: vec![],
+            game_versions: vec!["1.21.1".to_owned()],
+            loaders: vec![ModLoader::Fabric],
+        }
+    }
+}
+
 impl VersionedProject for Version {
     fn project(&self) -> &ProjectId {
         &self.project_id
@@ -46,14 +113,61 @@ impl VersionedProject for Version {
     }
 }
 
-#[derive(Debug, Clone)]
+/// How stable a [version](Version) is, as labeled by its author. Ordered from
+/// least to most stable
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseChannel {
+    Alpha,
+    Beta,
+    #[default]
+    Release,
+}
+
+impl ReleaseChannel {
+    #[inline]
+    #[allow(clippy::trivially_copy_pass_by_ref)] // Needed for serde
+    pub fn is_release(&self) -> bool {
+        matches!(self, Self::Release)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Alpha => "alpha",
+            Self::Beta => "beta",
+            Self::Release => "release",
+        }
+    }
+
+    /// Guess the channel of a version from its name, for services that don't
+    /// label them
+    pub(crate) fn from_name(name: &str) -> Self {
+        let name = name.to_ascii_lowercase();
+        if ["alpha", "snapshot"].iter().any(|s| name.contains(s)) {
+            Self::Alpha
+        } else if ["beta", "-pre", "-rc", ".rc"].iter().any(|s| name.contains(s)) {
+            Self::Beta
+        } else {
+            Self::Release
+        }
+    }
+}
+
+impl Display for ReleaseChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Dependency {
     pub project_id: ProjectId,
     pub id: Option<VersionId>,
     pub dep_type: DependencyType,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum DependencyType {
     Required,
     Optional,
@@ -65,8 +179,12 @@ impl Display for VersionId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let val: &dyn Display = match self {
             Self::Forge(id) => id,
-            Self::Modrinth(id) => id,
+            Self::Modrinth(id) | Self::Maven(id) | Self::Local(id) => id,
             Self::Github(id) => id,
+            Self::Url(UrlVersion { sha1, .. }) => match sha1 {
+                Some(sha1) => sha1,
+                None => &"any",
+            },
         };
         write!(f, "{val}")
     }
@@ -77,6 +195,9 @@ impl<T: VersionIdSvcType> PartialEq<T> for VersionId {
             VersionId::Forge(id) => other.get_forge().is_ok_and(|oid| *id == oid),
             VersionId::Modrinth(id) => other.get_modrinth().is_ok_and(|other| id == other),
             VersionId::Github(id) => other.get_github().is_ok_and(|oid| *id == oid),
+            VersionId::Url(v) => other.get_url().is_ok_and(|other| *v == other),
+            VersionId::Maven(id) => other.get_maven().is_ok_and(|other| id == other),
+            VersionId::Local(id) => other.get_local().is_ok_and(|other| id == other),
         }
     }
 }
@@ -86,6 +207,9 @@ impl<T: VersionIdSvcType> PartialOrd<T> for VersionId {
             VersionId::Forge(id) => other.get_forge().ok().and_then(|oid| id.partial_cmp(&oid)),
             VersionId::Modrinth(id) => other.get_modrinth().ok().map(|other| id.as_str().cmp(other)),
             VersionId::Github(id) => other.get_github().ok().and_then(|oid| id.partial_cmp(&oid)),
+            VersionId::Url(v) => other.get_url().ok().map(|other| v.cmp(&other)),
+            VersionId::Maven(id) => other.get_maven().ok().map(|other| crate::versioning::compare_versions(id, other)),
+            VersionId::Local(id) => other.get_local().ok().map(|other| id.as_str().cmp(other)),
         }
     }
 }
@@ -117,6 +241,33 @@ impl VersionIdSvcType for VersionId {
             Err(crate::ErrorKind::WrongService(self.to_string()))?
         }
     }
+
+    #[inline]
+    fn get_url(&self) -> Result<UrlVersion> {
+        if let Self::Url(v) = self {
+            Ok(v.clone())
+        } else {
+            Err(crate::ErrorKind::WrongService(self.to_string()))?
+        }
+    }
+
+    #[inline]
+    fn get_maven(&self) -> Result<&str> {
+        if let Self::Maven(v) = self {
+            Ok(v)
+        } else {
+            Err(crate::ErrorKind::WrongService(self.to_string()))?
+        }
+    }
+
+    #[inline]
+    fn get_local(&self) -> Result<&str> {
+        if let Self::Local(v) = self {
+            Ok(v)
+        } else {
+            Err(crate::ErrorKind::WrongService(self.to_string()))?
+        }
+    }
 }
 
 impl VersionIdSvcType for u64 {
@@ -134,6 +285,21 @@ impl VersionIdSvcType for u64 {
     fn get_github(&self) -> Result<<VersionId as ServiceId>::GithubT> {
         Err(ErrorKind::InvalidIdentifier.into())
     }
+
+    #[inline]
+    fn get_url(&self) -> Result<UrlVersion> {
+        Err(ErrorKind::InvalidIdentifier.into())
+    }
+
+    #[inline]
+    fn get_maven(&self) -> Result<&str> {
+        Err(ErrorKind::InvalidIdentifier.into())
+    }
+
+    #[inline]
+    fn get_local(&self) -> Result<&str> {
+        Err(ErrorKind::InvalidIdentifier.into())
+    }
 }
 
 impl VersionIdSvcType for <VersionId as ServiceId>::GithubT {
@@ -151,6 +317,21 @@ impl VersionIdSvcType for <VersionId as ServiceId>::GithubT {
     fn get_github(&self) -> Result<<VersionId as ServiceId>::GithubT> {
         Ok(*self)
     }
+
+    #[inline]
+    fn get_url(&self) -> Result<UrlVersion> {
+        Err(ErrorKind::InvalidIdentifier.into())
+    }
+
+    #[inline]
+    fn get_maven(&self) -> Result<&str> {
+        Err(ErrorKind::InvalidIdentifier.into())
+    }
+
+    #[inline]
+    fn get_local(&self) -> Result<&str> {
+        Err(ErrorKind::InvalidIdentifier.into())
+    }
 }
 
 impl VersionIdSvcType for str {
@@ -168,6 +349,29 @@ impl VersionIdSvcType for str {
     fn get_github(&self) -> Result<<VersionId as ServiceId>::GithubT> {
         self.parse::<u64>().map(Into::into).map_err(|_| ErrorKind::InvalidIdentifier.into())
     }
+
+    /// Only accepts a sha1 hex string
+    #[inline]
+    fn get_url(&self) -> Result<UrlVersion> {
+        if self.len() == 40 && self.bytes().all(|b| b.is_ascii_hexdigit()) {
+            Ok(UrlVersion {
+                sha1: Some(self.to_ascii_lowercase()),
+                size: None,
+            })
+        } else {
+            Err(ErrorKind::InvalidIdentifier.into())
+        }
+    }
+
+    #[inline]
+    fn get_maven(&self) -> Result<&str> {
+        Ok(self)
+    }
+
+    #[inline]
+    fn get_local(&self) -> Result<&str> {
+        Ok(self)
+    }
 }
 
 impl VersionIdSvcType for String {
@@ -185,4 +389,34 @@ impl VersionIdSvcType for String {
     fn get_github(&self) -> Result<<VersionId as ServiceId>::GithubT> {
         self.as_str().get_github()
     }
+
+    #[inline]
+    fn get_url(&self) -> Result<UrlVersion> {
+        self.as_str().get_url()
+    }
+
+    #[inline]
+    fn get_maven(&self) -> Result<&str> {
+        Ok(self)
+    }
+
+    #[inline]
+    fn get_local(&self) -> Result<&str> {
+        Ok(self)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_from_name() {
+        assert_eq!(ReleaseChannel::from_name("1.2.3"), ReleaseChannel::Release);
+        assert_eq!(ReleaseChannel::from_name("1.2.3-BETA.1"), ReleaseChannel::Beta);
+        assert_eq!(ReleaseChannel::from_name("2.0.0-rc2"), ReleaseChannel::Beta);
+        assert_eq!(ReleaseChannel::from_name("2.0-SNAPSHOT"), ReleaseChannel::Alpha);
+        assert!(ReleaseChannel::Alpha < ReleaseChannel::Beta && ReleaseChannel::Beta < ReleaseChannel::Release);
+    }
 }
@@ -10,6 +10,9 @@ pub trait ServiceId: super::Sealed {
     type ForgeT;
     type ModrinthT;
     type GithubT;
+    type UrlT;
+    type MavenT;
+    type LocalT;
 }
 macro_rules! svc_id_type {
     (@def $name:ident -> $ty:ty) => {
@@ -36,7 +39,10 @@ macro_rules! svc_id_type {
         $vis:vis enum $name:ident {
             Forge($F:ty $(= $FR:ty)?),
             Modrinth($M:ty $(= $MR:ty)?),
-            Github($G:ty $(= $GR:ty)?)$(,)?
+            Github($G:ty $(= $GR:ty)?),
+            Url($U:ty $(= $UR:ty)?),
+            Maven($V:ty $(= $VR:ty)?),
+            Local($L:ty $(= $LR:ty)?)$(,)?
         }
     ) => {
         $(#[$attr])*
@@ -44,24 +50,36 @@ macro_rules! svc_id_type {
             Forge($F),
             Modrinth($M),
             Github($G),
+            Url($U),
+            Maven($V),
+            Local($L),
         }
         impl crate::client::Sealed for $name {}
         impl crate::client::ServiceId for $name {
             type ForgeT = $F;
             type ModrinthT = $M;
             type GithubT = $G;
+            type UrlT = $U;
+            type MavenT = $V;
+            type LocalT = $L;
         }
         ::paste::paste! {
             pub trait [<$name SvcType>]: Sync {
                 svc_id_type!(@def Forge -> $F $(= $FR)?);
                 svc_id_type!(@def Modrinth -> $M $(= $MR)?);
                 svc_id_type!(@def Github -> $G $(= $GR)?);
+                svc_id_type!(@def Url -> $U $(= $UR)?);
+                svc_id_type!(@def Maven -> $V $(= $VR)?);
+                svc_id_type!(@def Local -> $L $(= $LR)?);
             }
 
             impl<T: [<$name SvcType>] + ?Sized> [<$name SvcType>] for &T {
                 svc_id_type!(@impl Forge -> $F $(= $FR)?);
                 svc_id_type!(@impl Modrinth -> $M $(= $MR)?);
                 svc_id_type!(@impl Github -> $G $(= $GR)?);
+                svc_id_type!(@impl Url -> $U $(= $UR)?);
+                svc_id_type!(@impl Maven -> $V $(= $VR)?);
+                svc_id_type!(@impl Local -> $L $(= $LR)?);
             }
         }
     };
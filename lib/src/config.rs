@@ -1,5 +1,10 @@
 //! Configuration types used for managing and interacting with mods/modpacks on
 //! the system
+mod clients;
+mod compat;
+mod constraint;
+mod downloads;
+mod install;
 mod loader;
 mod modpack;
 mod mods;
@@ -17,7 +22,7 @@ use ::serde::{Deserialize, Serialize};
 #[doc(inline)]
 pub use self::profile::Profile;
 use self::profile::ProfileByPath;
-pub use self::{loader::*, modpack::*, mods::*, project_with_version::*};
+pub use self::{clients::*, compat::*, constraint::*, downloads::*, install::*, loader::*, modpack::*, mods::*, project_with_version::*};
 use crate::{
     CONF_DIR, ErrorKind, PathAbsolute, Result, StdResult,
     fs_util::{FsUtil, FsUtils},
@@ -47,6 +52,24 @@ pub struct Config {
         serialize_with = "self::serde::profiles::serialize"
     )]
     profiles: ProfilesList,
+
+    #[serde(skip_serializing_if = "RateLimits::is_default")]
+    rate_limits: RateLimits,
+
+    #[serde(skip_serializing_if = "Endpoints::is_default")]
+    endpoints: Endpoints,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    mirrors: Vec<Mirror>,
+
+    #[serde(skip_serializing_if = "DownloadSettings::is_default")]
+    downloads: DownloadSettings,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    local_root: Option<PathAbsolute>,
+
+    #[serde(skip_serializing_if = "InstallStrategy::is_copy")]
+    install_strategy: InstallStrategy,
 }
 
 
@@ -185,6 +208,74 @@ impl Config {
     }
 }
 
+// Clients
+impl Config {
+    /// The [rate limits](RateLimits) to use for each api client
+    pub fn rate_limits(&self) -> &RateLimits {
+        &self.rate_limits
+    }
+
+    /// See [`rate_limits`](Self::rate_limits)
+    pub fn rate_limits_mut(&mut self) -> &mut RateLimits {
+        &mut self.rate_limits
+    }
+
+    /// The base urls of remote services, for using mirrors instead of the
+    /// official servers
+    pub fn endpoints(&self) -> &Endpoints {
+        &self.endpoints
+    }
+
+    /// See [`endpoints`](Self::endpoints)
+    pub fn endpoints_mut(&mut self) -> &mut Endpoints {
+        &mut self.endpoints
+    }
+
+    /// Rules for downloading files from mirrors, in order of preference
+    pub fn mirrors(&self) -> &[Mirror] {
+        &self.mirrors
+    }
+
+    /// See [`mirrors`](Self::mirrors)
+    pub fn mirrors_mut(&mut self) -> &mut Vec<Mirror> {
+        &mut self.mirrors
+    }
+
+    /// Concurrency, speed, and connection settings for downloads
+    pub fn downloads(&self) -> &DownloadSettings {
+        &self.downloads
+    }
+
+    /// See [`downloads`](Self::downloads)
+    pub fn downloads_mut(&mut self) -> &mut DownloadSettings {
+        &mut self.downloads
+    }
+
+    /// The directory containing [local](crate::client::LocalClient) projects
+    pub fn local_root(&self) -> Option<&PathAbsolute> {
+        self.local_root.as_ref()
+    }
+
+    /// See [`local_root`](Self::local_root)
+    pub fn set_local_root(&mut self, root: Option<PathAbsolute>) {
+        self.local_root = root;
+    }
+}
+
+// Install
+impl Config {
+    /// How cached files are installed into profiles that don't
+    /// [set their own](profile::ProfileData::install)
+    pub fn install_strategy(&self) -> InstallStrategy {
+        self.install_strategy
+    }
+
+    /// See [`install_strategy`](Self::install_strategy)
+    pub fn set_install_strategy(&mut self, strategy: InstallStrategy) {
+        self.install_strategy = strategy;
+    }
+}
+
 // Load/Save
 impl Config {
     /// Load a [config](Config) from the file located at the
@@ -248,6 +339,12 @@ struct ConfigDe {
     active: Option<PathAbsolute>,
     #[serde(deserialize_with = "self::serde::profiles::deserialize")]
     profiles: ProfilesList,
+    rate_limits: RateLimits,
+    endpoints: Endpoints,
+    mirrors: Vec<Mirror>,
+    downloads: DownloadSettings,
+    local_root: Option<PathAbsolute>,
+    install_strategy: InstallStrategy,
 }
 impl From<ConfigDe> for Config {
     fn from(de: ConfigDe) -> Self {
@@ -259,6 +356,12 @@ impl From<ConfigDe> for Config {
                 // Activate first profile from list if present and not already set
                 .or_else(|| de.profiles.first().map(ProfileByPath::as_absolute).map(ToOwned::to_owned)),
             profiles: de.profiles,
+            rate_limits: de.rate_limits,
+            endpoints: de.endpoints,
+            mirrors: de.mirrors,
+            downloads: de.downloads,
+            local_root: de.local_root,
+            install_strategy: de.install_strategy,
         }
     }
 }
@@ -294,6 +397,12 @@ mod tests {
                 .map(|(name, path)| Profile::new((*name).to_string(), path.clone()))
                 .map(Into::into)
                 .collect(),
+            rate_limits: RateLimits::default(),
+            endpoints: Endpoints::default(),
+            mirrors: vec![],
+            downloads: DownloadSettings::default(),
+            local_root: None,
+            install_strategy: InstallStrategy::Copy,
         }
     }
     fn test_ser_data() -> (Config, Vec<Token>) {
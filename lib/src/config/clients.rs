@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{ErrorKind, Result, client::RateLimitPolicy};
+
+/// The [rate limit policy](RateLimitPolicy) of each api client
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+#[allow(missing_docs)] // fields are self explanatory
+pub struct RateLimits {
+    pub modrinth: RateLimitPolicy,
+    pub curseforge: RateLimitPolicy,
+    pub github: RateLimitPolicy,
+}
+
+impl RateLimits {
+    pub(super) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Overrides for the base url of every remote service. Any left unset will use
+/// the official server
+///
+/// Maven urls are the root of the repository, not the path of any artifact in
+/// it
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Endpoints {
+    /// Modrinth api, e.g. `https://api.modrinth.com/v2`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modrinth: Option<Url>,
+    /// Curseforge api, e.g. `https://api.curseforge.com`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub curseforge: Option<Url>,
+    /// Github api, e.g. `https://api.github.com`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub github: Option<Url>,
+    /// Fabric meta server, e.g. `https://meta.fabricmc.net`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fabric_meta: Option<Url>,
+    /// Mojang launcher meta server, e.g. `https://launchermeta.mojang.com`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mojang_meta: Option<Url>,
+    /// Forge maven, e.g. `https://maven.minecraftforge.net`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forge_maven: Option<Url>,
+    /// NeoForge maven, e.g. `https://maven.neoforged.net/releases`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub neoforge_maven: Option<Url>,
+    /// Quilt maven, e.g. `https://maven.quiltmc.org/repository/release`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quilt_maven: Option<Url>,
+}
+
+impl Endpoints {
+    pub(super) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Override endpoints using the value returned by `var` for the uppercase
+    /// name of each field prefixed by `prefix` and suffixed by `_URL`. E.g.
+    /// with a prefix of `APP_`, `modrinth` is read from `APP_MODRINTH_URL`
+    ///
+    /// # Errors
+    ///
+    /// [`ErrorKind::InvalidUrl`]: if any returned value isn't a valid url
+    pub fn override_with(&mut self, prefix: &str, var: impl Fn(&str) -> Option<String>) -> Result<()> {
+        macro_rules! fields {
+            ($($field:ident),*) => {$(
+                if let Some(url) = var(&format!("{prefix}{}_URL", stringify!($field).to_uppercase())) {
+                    self.$field = Some(url.parse().map_err(|_| ErrorKind::InvalidUrl(url))?);
+                }
+            )*};
+        }
+        fields!(
+            modrinth,
+            curseforge,
+            github,
+            fabric_meta,
+            mojang_meta,
+            forge_maven,
+            neoforge_maven,
+            quilt_maven
+        );
+        Ok(())
+    }
+}
+
+/// Rewrites download urls starting with `from` to start with `to` instead, such
+/// as to download files from an internal mirror of `https://cdn.modrinth.com`.
+/// The rewritten url is tried before the original
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[allow(missing_docs)] // fields are self explanatory
+pub struct Mirror {
+    pub from: Url,
+    pub to: Url,
+}
+
+impl Mirror {
+    /// The url to try before `url`, if it starts with [`from`](Self::from).
+    /// Only whole path segments are matched
+    pub fn rewrite(&self, url: &Url) -> Option<Url> {
+        let from = self.from.as_str().trim_end_matches('/');
+        let rest = url.as_str().strip_prefix(from)?;
+        if !(rest.is_empty() || rest.starts_with(['/', '?', '#'])) {
+            return None;
+        }
+        format!("{}{rest}", self.to.as_str().trim_end_matches('/')).parse().ok()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn override_from_vars() {
+        let mut endpoints = Endpoints::default();
+        endpoints
+            .override_with("APP_", |name| match name {
+                "APP_MODRINTH_URL" => Some("http://localhost:8080".into()),
+                "APP_FORGE_MAVEN_URL" => Some("http://localhost/maven/".into()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(endpoints.modrinth.unwrap().as_str(), "http://localhost:8080/");
+        assert_eq!(endpoints.forge_maven.unwrap().as_str(), "http://localhost/maven/");
+        assert!(endpoints.github.is_none());
+
+        assert!(Endpoints::default().override_with("", |_| Some("not a url".into())).is_err());
+    }
+
+    #[test]
+    fn mirror_rewrite() {
+        let mirror = Mirror {
+            from: "https://cdn.modrinth.com".parse().unwrap(),
+            to: "http://mirror.local/modrinth/".parse().unwrap(),
+        };
+        let rewrite = |url: &str| mirror.rewrite(&url.parse().unwrap()).map(String::from);
+        assert_eq!(
+            rewrite("https://cdn.modrinth.com/data/AANobbMI/sodium.jar").as_deref(),
+            Some("http://mirror.local/modrinth/data/AANobbMI/sodium.jar")
+        );
+        assert_eq!(rewrite("https://cdn.modrinth.com.evil/data/sodium.jar"), None);
+        assert_eq!(rewrite("https://github.com/owner/repo"), None);
+    }
+}
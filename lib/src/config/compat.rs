@@ -0,0 +1,111 @@
+use std::{convert::Infallible, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// Which game versions a mod version can be made for and still be installed to
+/// a profile. Versions for the exact profile game version are always preferred
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum GameVersionPolicy {
+    /// Only versions made for the exact profile game version
+    #[default]
+    Exact,
+    /// Versions made for any game version in the same minor line, e.g. `1.21`
+    /// or `1.21.3` for a `1.21.1` profile
+    Minor,
+    /// Versions made for any of the listed game versions
+    List(Vec<String>),
+}
+
+impl GameVersionPolicy {
+    /// Returns `true` if the policy is `Exact`
+    #[must_use]
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Self::Exact)
+    }
+
+    /// Whether versions made for `candidate` can be installed to a profile for
+    /// `game_version`
+    pub fn accepts(&self, game_version: &str, candidate: &str) -> bool {
+        candidate == game_version
+            || match self {
+                Self::Exact => false,
+                Self::Minor => minor_line(candidate).is_some_and(|line| minor_line(game_version) == Some(line)),
+                Self::List(list) => list.iter().any(|v| v == candidate),
+            }
+    }
+}
+
+impl Display for GameVersionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exact => f.write_str("exact"),
+            Self::Minor => f.write_str("minor"),
+            Self::List(list) => f.write_str(&list.join(",")),
+        }
+    }
+}
+
+/// Parses `exact`, `minor`, or a comma separated list of game versions
+impl FromStr for GameVersionPolicy {
+    type Err = Infallible;
+
+    fn from_str(from: &str) -> Result<Self, Self::Err> {
+        Ok(match from.trim().to_lowercase().as_str() {
+            "" | "exact" => Self::Exact,
+            "minor" => Self::Minor,
+            list => Self::List(
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect(),
+            ),
+        })
+    }
+}
+
+/// The `major.minor` prefix of a release `version`. Snapshots and other
+/// versions not in that format have no line
+fn minor_line(version: &str) -> Option<&str> {
+    let mut parts = version.splitn(3, '.');
+    let major = parts.next()?;
+    let minor = parts.next()?;
+    if [major, minor]
+        .iter()
+        .all(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()))
+        && parts.next().is_none_or(|patch| patch.bytes().all(|b| b.is_ascii_digit()))
+    {
+        Some(&version[..major.len() + 1 + minor.len()])
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_versions() {
+        let exact = GameVersionPolicy::Exact;
+        assert!(exact.accepts("1.21.1", "1.21.1"));
+        assert!(!exact.accepts("1.21.1", "1.21"));
+
+        let minor = GameVersionPolicy::Minor;
+        assert!(minor.accepts("1.21.1", "1.21"));
+        assert!(minor.accepts("1.21", "1.21.4"));
+        assert!(!minor.accepts("1.21.1", "1.20.1"));
+        assert!(!minor.accepts("1.2.1", "1.21"));
+        assert!(!minor.accepts("24w10a", "1.21"));
+        assert!(!minor.accepts("1.21.1", "1.21-pre1"));
+
+        let list = GameVersionPolicy::List(vec!["1.20.6".to_owned()]);
+        assert!(list.accepts("1.21.1", "1.20.6"));
+        assert!(!list.accepts("1.21.1", "1.21"));
+        assert_eq!(
+            "1.20.6, 1.21".parse(),
+            Ok(GameVersionPolicy::List(vec!["1.20.6".to_owned(), "1.21".to_owned()]))
+        );
+    }
+}
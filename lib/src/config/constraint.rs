@@ -0,0 +1,238 @@
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ErrorKind,
+    client::schema::Version,
+    versioning::{compare_versions, wildcard_match},
+};
+
+/// Limits which versions of a mod can be installed beyond its [release
+/// channel](crate::client::schema::ReleaseChannel). Every rule must be
+/// satisfied
+///
+/// Rules compare the version number of a version, which is the first number in
+/// its version number, title, or file name that isn't one of its game versions.
+/// For example `0.5.8` for `mc1.20.4-0.5.8`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct VersionConstraint(Vec<VersionRule>);
+
+/// A single rule of a [`VersionConstraint`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum VersionRule {
+    /// The version number matches a pattern where `*` matches anything, e.g.
+    /// `0.5.*`. Without a `*` the version number must match exactly
+    Matches(String),
+    /// The version number is at least this version, and compatible with it by
+    /// Cargo's caret rules: everything up to its first non-zero part must
+    /// match. E.g. `^1.2` allows `1.9` but not `2.0`, and `^0.5.2` allows
+    /// `0.5.9` but not `0.6.0`
+    Compatible(String),
+    /// The version number is at least this version, e.g. `>=0.5.2`
+    AtLeast(String),
+    /// Released no later than this `YYYY-MM-DD` date, e.g. `until:2024-06-01`
+    Until(String),
+}
+
+impl VersionConstraint {
+    #[allow(missing_docs)]
+    pub fn rules(&self) -> &[VersionRule] {
+        &self.0
+    }
+
+    /// Whether `version` satisfies every rule
+    pub fn accepts(&self, version: &Version) -> bool {
+        let number = version_number(version);
+        self.0.iter().all(|rule| match rule {
+            VersionRule::Matches(pattern) => number.is_some_and(|n| wildcard_match(pattern, n)),
+            VersionRule::Compatible(min) => number.is_some_and(|n| compatible(n, min) && compare_versions(n, min) != Ordering::Less),
+            VersionRule::AtLeast(min) => number.is_some_and(|n| compare_versions(n, min) != Ordering::Less),
+            // Dates of unknown versions can't be checked
+            VersionRule::Until(date) => version.date.get(..10).is_some_and(|released| released <= date.as_str()),
+        })
+    }
+}
+
+impl Display for VersionConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, rule) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            match rule {
+                VersionRule::Matches(pattern) => f.write_str(pattern)?,
+                VersionRule::Compatible(min) => write!(f, "^{min}")?,
+                VersionRule::AtLeast(min) => write!(f, ">={min}")?,
+                VersionRule::Until(date) => write!(f, "until:{date}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses a comma separated list of rules: a version pattern like `0.5.*`,
+/// `^<version>`, `>=<version>`, or `until:<YYYY-MM-DD>`
+impl FromStr for VersionConstraint {
+    type Err = ErrorKind;
+
+    fn from_str(from: &str) -> Result<Self, Self::Err> {
+        let invalid = |rule: &str| ErrorKind::InvalidConstraint(rule.to_owned());
+        let rules = { from.split(',').map(str::trim).filter(|r| !r.is_empty()) }
+            .map(|rule| {
+                let parsed = if let Some(min) = rule.strip_prefix('^') {
+                    VersionRule::Compatible(min.trim().to_owned())
+                } else if let Some(min) = rule.strip_prefix(">=") {
+                    VersionRule::AtLeast(min.trim().to_owned())
+                } else if let Some(date) = rule.strip_prefix("until:") {
+                    let date = date.trim();
+                    if !is_date(date) {
+                        return Err(invalid(rule));
+                    }
+                    VersionRule::Until(date.to_owned())
+                } else {
+                    VersionRule::Matches(rule.to_owned())
+                };
+                match &parsed {
+                    VersionRule::Compatible(v) | VersionRule::AtLeast(v) if v.is_empty() => Err(invalid(rule)),
+                    _ => Ok(parsed),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if rules.is_empty() {
+            return Err(invalid(from));
+        }
+        Ok(Self(rules))
+    }
+}
+
+impl TryFrom<String> for VersionConstraint {
+    type Error = ErrorKind;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<VersionConstraint> for String {
+    fn from(value: VersionConstraint) -> Self {
+        value.to_string()
+    }
+}
+
+/// The number of `version` that constraints are compared against
+fn version_number(version: &Version) -> Option<&str> {
+    let filename = version.filename.file_stem().and_then(|f| f.to_str());
+    {
+        version
+            .number
+            .as_deref()
+            .into_iter()
+            .chain([version.title.as_str()])
+            .chain(filename)
+    }
+    .find_map(|text| first_number(text, &version.game_versions))
+}
+
+/// The first segment of `text` that starts with a digit, after an optional `v`
+/// prefix like in `v0.5.8`, and isn't one of the `game_versions`
+fn first_number<'t>(text: &'t str, game_versions: &[String]) -> Option<&'t str> {
+    text.split(|c: char| c.is_whitespace() || matches!(c, '-' | '+' | '_'))
+        .map(|seg| seg.strip_prefix(['v', 'V']).unwrap_or(seg))
+        .find(|seg| seg.starts_with(|c: char| c.is_ascii_digit()) && !game_versions.iter().any(|gv| gv == seg))
+}
+
+/// Whether `version` matches every part of `min` up to and including its first
+/// non-zero part, or every part if they are all zero
+fn compatible(version: &str, min: &str) -> bool {
+    let parts = min.split('.').collect::<Vec<_>>();
+    let len = parts
+        .iter()
+        .position(|p| p.parse::<u64>().is_ok_and(|n| n != 0))
+        .map_or(parts.len(), |i| i + 1);
+    let mut version = version.split('.');
+    parts[..len]
+        .iter()
+        .all(|p| version.next().is_some_and(|v| compare_versions(v, p) == Ordering::Equal))
+}
+
+fn is_date(date: &str) -> bool {
+    let bytes = date.as_bytes();
+    bytes.len() == 10
+        && bytes
+            .iter()
+            .enumerate()
+            .all(|(i, b)| if i == 4 || i == 7 { *b == b'-' } else { b.is_ascii_digit() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(number: &str, date: &str) -> Version {
+        Version {
+            id: crate::client::schema::VersionId::Modrinth("abc123".to_owned()),
+            project_id: crate::client::schema::ProjectId::Modrinth("AANobbMI".to_owned()),
+            title: String::new(),
+            number: Some(number.to_owned()),
+            download_url: None,
+            mirrors: vec![],
+            filename: "sodium.jar".try_into().unwrap(),
+            length: 0,
+            date: date.to_owned(),
+            channel: Default::default(),
+            sha1: None,
+            deps: vec![],
+            game_versions: vec!["1.20.4".to_owned()],
+            loaders: vec![],
+        }
+    }
+
+    #[test]
+    fn accepts_versions() {
+        let c = |s: &str| s.parse::<VersionConstraint>().unwrap();
+        let v = version("mc1.20.4-0.5.8", "2024-03-02T12:00:00Z");
+
+        assert!(c("0.5.*").accepts(&v));
+        assert!(c("0.5.8").accepts(&v));
+        assert!(!c("0.6.*").accepts(&v));
+        assert!(c("^0.5.2").accepts(&v));
+        assert!(!c("^0.5.10").accepts(&v));
+        assert!(!c("^1.0").accepts(&v));
+        // Caret rules stop at the first non-zero part
+        assert!(!c("^0.4.0").accepts(&v));
+        assert!(c("^0").accepts(&v));
+        assert!(c("^0.5.8").accepts(&version("0.5.8", "")));
+        assert!(!c("^0.0.3").accepts(&version("0.0.4", "")));
+        assert!(c("^0.0.3").accepts(&version("0.0.3", "")));
+        assert!(c("^1.2").accepts(&version("1.9.0", "")));
+        assert!(!c("^1.2").accepts(&version("2.0.0", "")));
+        assert!(c(">=0.5.8").accepts(&v));
+        assert!(!c(">=0.5.10").accepts(&v));
+        assert!(c("until:2024-03-02").accepts(&v));
+        assert!(!c("until:2024-03-01").accepts(&v));
+        assert!(c("0.5.*, until:2024-12-31").accepts(&v));
+        assert!(!c("0.5.*, until:2024-01-01").accepts(&v));
+        assert!(c(">=0.92").accepts(&version("1.20.4-0.92.0+1.20.4", "")));
+        // Version tags usually start with a `v`
+        assert!(c("0.5.*").accepts(&version("v0.5.8", "")));
+        let tagged = Version {
+            number: None,
+            title: "Sodium V0.5.8".to_owned(),
+            ..version("", "")
+        };
+        assert!(c("0.5.*").accepts(&tagged));
+    }
+
+    #[test]
+    fn parse_constraints() {
+        assert_eq!(
+            "0.5.*, >=0.5.2,until:2024-06-01".parse::<VersionConstraint>().unwrap().to_string(),
+            "0.5.*,>=0.5.2,until:2024-06-01"
+        );
+        assert!("".parse::<VersionConstraint>().is_err());
+        assert!(">=".parse::<VersionConstraint>().is_err());
+        assert!("until:June".parse::<VersionConstraint>().is_err());
+    }
+}
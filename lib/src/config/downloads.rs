@@ -0,0 +1,77 @@
+use anyhow::{Context, anyhow};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{Result, checked_types::PathAbsolute};
+
+/// Limits and connection settings used by a
+/// [`ProfileManager`](crate::ProfileManager) to download and hash files
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct DownloadSettings {
+    /// The maximum number of files downloaded at once
+    pub max_concurrent: usize,
+    /// The maximum number of files hashed at once
+    pub max_hashing: usize,
+    /// The maximum combined speed of all downloads in bytes per second.
+    /// Unlimited when not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_speed: Option<u64>,
+    /// The proxy every download is sent through. When not set, the proxy is
+    /// read from the `HTTP_PROXY`/`HTTPS_PROXY` environment variables
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<Url>,
+    /// PEM files of additional root certificates to trust, such as for a
+    /// mirror or proxy using a private CA
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ca_certs: Vec<PathAbsolute>,
+}
+
+impl DownloadSettings {
+    /// The default [`max_concurrent`](Self::max_concurrent)
+    pub const DEFAULT_CONCURRENT: usize = 10;
+    /// The default [`max_hashing`](Self::max_hashing)
+    pub const DEFAULT_HASHING: usize = 50;
+
+    pub(super) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Create an http client that sends every request through the
+    /// [`proxy`](Self::proxy), trusts the [`ca_certs`](Self::ca_certs), and
+    /// sends `user_agent`. Cloning it shares its connection pool
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if any CA certificate can't be read or parsed, or
+    /// the proxy url isn't supported
+    pub fn http_client(&self, user_agent: &str) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder().user_agent(user_agent);
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy.as_str())?);
+        }
+        for path in &self.ca_certs {
+            let pem = std::fs::read(path).with_context(|| format!("Failed to read CA certificate `{}`", path.display()))?;
+            let certs = reqwest::Certificate::from_pem_bundle(&pem)
+                .ok()
+                .filter(|certs| !certs.is_empty())
+                .ok_or_else(|| anyhow!("No valid certificates found in `{}`", path.display()))?;
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        Ok(builder.build()?)
+    }
+}
+
+impl Default for DownloadSettings {
+    fn default() -> Self {
+        Self {
+            max_concurrent: Self::DEFAULT_CONCURRENT,
+            max_hashing: Self::DEFAULT_HASHING,
+            max_speed: None,
+            proxy: None,
+            ca_certs: vec![],
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// How files already downloaded to the [cache](crate::mgmt::CACHE_DIR) are
+/// installed into a profile
+///
+/// Strategies that aren't supported by the filesystem, such as linking across
+/// drives, fall back to copying the file
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallStrategy {
+    /// Copy the file from the cache
+    #[default]
+    Copy,
+    /// Hard link the file to the cache, sharing its contents
+    Hardlink,
+    /// Symbolic link the file to the cache. The installed file is broken if
+    /// the cache is cleared
+    Symlink,
+    /// Copy-on-write clone of the file, on filesystems that support it
+    Reflink,
+}
+
+impl InstallStrategy {
+    /// Variant name as a lowercase string
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Copy => "copy",
+            Self::Hardlink => "hardlink",
+            Self::Symlink => "symlink",
+            Self::Reflink => "reflink",
+        }
+    }
+
+    /// Returns `true` if the strategy is `Copy`
+    #[must_use]
+    pub fn is_copy(&self) -> bool {
+        matches!(self, Self::Copy)
+    }
+}
+
+impl Display for InstallStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
-use super::{ProjectWithVersion, project_with_version::VersionedProject};
-use crate::client::schema::{Project, ProjectId, ProjectType, VersionId};
+use super::{GameVersionPolicy, ProjectWithVersion, VersionConstraint, project_with_version::VersionedProject};
+use crate::client::schema::{Project, ProjectId, ProjectType, ReleaseChannel, VersionId};
 
 /// The basic data needed to lookup and install a particular mod from one of the
 /// [supported clients](crate::client)
@@ -24,6 +24,21 @@ pub struct Mod {
     /// If `true`, will prevent this mod from being installed by a modpack
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub exclude: bool,
+
+    /// The least stable [release channel](ReleaseChannel) to install for this
+    /// mod. Overrides the [profile
+    /// channel](super::profile::ProfileData::channel) when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<ReleaseChannel>,
+
+    /// Which game versions this mod can be installed for. Overrides the
+    /// [profile policy](super::profile::ProfileData::compat) when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compat: Option<GameVersionPolicy>,
+
+    /// Limits which versions of this mod can be installed or updated to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constraint: Option<VersionConstraint>,
 }
 
 impl VersionedProject for Mod {
@@ -51,6 +66,23 @@ impl std::hash::Hash for Mod {
     }
 }
 
+#[cfg(test)]
+impl Mod {
+    /// An unversioned mod, without any overrides or constraint
+    pub(crate) fn stub(project: ProjectId, name: &str) -> Self {
+        Self {
+            id: ProjectWithVersion::new(project, None).unwrap(),
+            slug: name.to_owned(),
+            name: name.to_owned(),
+            project_type: ProjectType::Mod,
+            exclude: false,
+            channel: None,
+            compat: None,
+            constraint: None,
+        }
+    }
+}
+
 impl From<Project> for Mod {
     fn from(proj: Project) -> Self {
         Self {
@@ -58,6 +90,9 @@ impl From<Project> for Mod {
             slug: proj.slug,
             name: proj.name,
             exclude: false,
+            channel: None,
+            compat: None,
+            constraint: None,
             project_type: proj.project_type,
         }
     }
@@ -133,6 +133,9 @@ mod tests {
                 slug: "test-3".to_owned(),
                 name: "Test 3".to_owned(),
                 exclude: false,
+                channel: None,
+                compat: None,
+                constraint: None,
                 project_type: ProjectType::Mod,
             },
             Mod {
@@ -140,6 +143,9 @@ mod tests {
                 slug: "test-1".to_owned(),
                 name: "test 1".to_owned(),
                 exclude: false,
+                channel: None,
+                compat: None,
+                constraint: None,
                 project_type: ProjectType::ModPack,
             },
             Mod {
@@ -147,6 +153,9 @@ mod tests {
                 slug: "test-2".to_owned(),
                 name: "Test 2".to_owned(),
                 exclude: false,
+                channel: None,
+                compat: None,
+                constraint: None,
                 project_type: ProjectType::Shader,
             },
             Mod {
@@ -154,6 +163,9 @@ mod tests {
                 slug: "test-0".to_owned(),
                 name: "test 0".to_owned(),
                 exclude: false,
+                channel: None,
+                compat: None,
+                constraint: None,
                 project_type: ProjectType::DataPack,
             },
         ];
@@ -7,7 +7,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     PathAbsolute, Result, StdResult,
-    config::{Mod, ModLoader, Modpack, VersionedProject},
+    client::schema::{ProjectId, ReleaseChannel},
+    config::{GameVersionPolicy, InstallStrategy, Mod, ModLoader, Modpack, VersionConstraint, VersionedProject},
     fs_util::{FsUtil, FsUtils},
 };
 
@@ -61,6 +62,23 @@ pub struct ProfileData {
     /// When `true`, client-only mods from modpacks will not be installed
     #[serde(default, rename = "server")]
     pub is_server: bool,
+
+    /// The least stable [release channel](ReleaseChannel) to install versions
+    /// from, unless [overridden](Mod::channel) by a mod. Any channel is allowed
+    /// when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<ReleaseChannel>,
+
+    /// Which game versions mods can be installed for, unless
+    /// [overridden](Mod::compat) by a mod. Versions for the exact
+    /// [`game_version`](Self::game_version) are always preferred
+    #[serde(default, skip_serializing_if = "GameVersionPolicy::is_exact")]
+    pub compat: GameVersionPolicy,
+
+    /// How cached files are installed into this profile. Uses the
+    /// [global strategy](crate::config::Config::install_strategy) when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub install: Option<InstallStrategy>,
 }
 
 macro_rules! remove_sorted {
@@ -103,17 +121,32 @@ impl ProfileData {
         let mut up = vec![];
         for new in mods {
             match cur_mods.get_key_value(new) {
-                Some((exist, i)) if exist.exclude != new.exclude => up.push((new, *i)),
+                Some((exist, i))
+                    if exist.exclude != new.exclude
+                        || new.channel.is_some_and(|c| exist.channel != Some(c))
+                        || new.compat.as_ref().is_some_and(|c| exist.compat.as_ref() != Some(c))
+                        || new.constraint.as_ref().is_some_and(|c| exist.constraint.as_ref() != Some(c)) =>
+                {
+                    up.push((new, *i));
+                },
                 Some(_) => checked.push(Err(new)),
                 None => checked.push(Ok(new)),
             }
         }
 
-        // Update excluded for existing
+        // Update excluded, channel, compat, and constraint for existing. Overrides are
+        // only replaced when set, so re-adding a mod keeps them
         let up: Vec<_> = up
             .into_iter()
             .map(|(m, i)| {
                 self.mods[i].exclude = m.exclude;
+                self.mods[i].channel = m.channel.or(self.mods[i].channel);
+                if m.compat.is_some() {
+                    self.mods[i].compat.clone_from(&m.compat);
+                }
+                if m.constraint.is_some() {
+                    self.mods[i].constraint.clone_from(&m.constraint);
+                }
                 Ok(m)
             })
             .collect();
@@ -156,6 +189,30 @@ impl ProfileData {
         remove_sorted!(self.mods, BinaryHeap::from_iter(indices.as_ref()).into_iter())
     }
 
+    /// The least stable [release channel](ReleaseChannel) allowed for the
+    /// project with `id`
+    pub fn channel_for(&self, id: &ProjectId) -> Option<ReleaseChannel> {
+        self.mods
+            .iter()
+            .find(|m| m.project() == id)
+            .and_then(|m| m.channel)
+            .or(self.channel)
+    }
+
+    /// The [game version policy](GameVersionPolicy) for the project with `id`
+    pub fn compat_for(&self, id: &ProjectId) -> &GameVersionPolicy {
+        self.mods
+            .iter()
+            .find(|m| m.project() == id)
+            .and_then(|m| m.compat.as_ref())
+            .unwrap_or(&self.compat)
+    }
+
+    /// The [version constraint](VersionConstraint) of the project with `id`
+    pub fn constraint_for(&self, id: &ProjectId) -> Option<&VersionConstraint> {
+        self.mods.iter().find(|m| m.project() == id).and_then(|m| m.constraint.as_ref())
+    }
+
     /// Returns the path where this [`ProfileData`] would be saved given the
     /// provided base path
     pub fn file_path(path: impl AsRef<Path>) -> PathBuf {
@@ -171,6 +228,50 @@ impl Default for ProfileData {
             mods: Default::default(),
             modpack: None,
             is_server: false,
+            channel: None,
+            compat: GameVersionPolicy::Exact,
+            install: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readd_keeps_overrides() {
+        let id = ProjectId::Modrinth("a".to_owned());
+        let mut data = ProfileData {
+            mods: vec![Mod {
+                channel: Some(ReleaseChannel::Beta),
+                compat: Some(GameVersionPolicy::Minor),
+                constraint: Some("^1.2".parse().unwrap()),
+                ..Mod::stub(id.clone(), "a")
+            }],
+            ..ProfileData::default()
+        };
+
+        let readd = Mod::stub(id.clone(), "a");
+        assert_eq!(data.add_mods([&readd]), [Err(&readd)]);
+        assert_eq!(data.mods[0].channel, Some(ReleaseChannel::Beta));
+        assert_eq!(data.mods[0].compat, Some(GameVersionPolicy::Minor));
+        assert_eq!(data.mods[0].constraint, Some("^1.2".parse().unwrap()));
+
+        let changed = Mod {
+            channel: Some(ReleaseChannel::Alpha),
+            ..Mod::stub(id.clone(), "a")
+        };
+        assert_eq!(data.add_mods([&changed]), [Ok(&changed)]);
+        assert_eq!(data.mods[0].channel, Some(ReleaseChannel::Alpha));
+        assert_eq!(data.mods[0].compat, Some(GameVersionPolicy::Minor));
+
+        let constrained = Mod {
+            constraint: Some(">=1.3".parse().unwrap()),
+            ..Mod::stub(id, "a")
+        };
+        assert_eq!(data.add_mods([&constrained]), [Ok(&constrained)]);
+        assert_eq!(data.mods[0].constraint, Some(">=1.3".parse().unwrap()));
+        assert_eq!(data.mods[0].channel, Some(ReleaseChannel::Alpha));
+    }
+}
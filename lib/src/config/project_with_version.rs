@@ -37,7 +37,10 @@ fn validate(project: &ProjectId, version: Option<&VersionId>) -> StdResult<(), S
         (_, None)
         | (ProjectId::Forge(_), Some(VersionId::Forge(_)))
         | (ProjectId::Modrinth(_), Some(VersionId::Modrinth(_)))
-        | (ProjectId::Github(_), Some(VersionId::Github(_))) => Ok(()),
+        | (ProjectId::Github(_), Some(VersionId::Github(_)))
+        | (ProjectId::Url(_), Some(VersionId::Url(_)))
+        | (ProjectId::Maven(_), Some(VersionId::Maven(_)))
+        | (ProjectId::Local(_), Some(VersionId::Local(_))) => Ok(()),
         _ => Err(ServiceMismatchError),
     }
 }
@@ -68,6 +71,18 @@ impl ProjectWithVersion {
         self.version.take()
     }
 }
+impl VersionedProject for ProjectWithVersion {
+    #[inline]
+    fn project(&self) -> &ProjectId {
+        &self.project
+    }
+
+    #[inline]
+    fn version(&self) -> Option<&VersionId> {
+        self.version.as_ref()
+    }
+}
+
 impl From<ProjectId> for ProjectWithVersion {
     fn from(pid: ProjectId) -> Self {
         Self {
@@ -99,18 +114,24 @@ impl Serialize for ProjectWithVersion {
             ProjectId::Forge(_) => "forge",
             ProjectId::Modrinth(_) => "modrinth",
             ProjectId::Github(_) => "github",
+            ProjectId::Url(_) => "url",
+            ProjectId::Maven(_) => "maven",
+            ProjectId::Local(_) => "local",
         };
         let mut ser = serializer.serialize_struct("ProjectWithVersion", 1 + self.version.as_ref().map_or(0, |_| 1))?;
         match &self.project {
             ProjectId::Forge(id) => ser.serialize_field(svc, id),
-            ProjectId::Modrinth(id) => ser.serialize_field(svc, id),
+            ProjectId::Modrinth(id) | ProjectId::Local(id) => ser.serialize_field(svc, id),
             ProjectId::Github((owner, repo)) => ser.serialize_field(svc, &format_args!("{owner}/{repo}")),
+            ProjectId::Url(url) => ser.serialize_field(svc, url),
+            ProjectId::Maven(id) => ser.serialize_field(svc, id),
         }?;
         if let Some(version) = &self.version {
             match version {
                 VersionId::Forge(id) => ser.serialize_field(VERS_FIELD, id),
-                VersionId::Modrinth(id) => ser.serialize_field(VERS_FIELD, id),
+                VersionId::Modrinth(id) | VersionId::Maven(id) | VersionId::Local(id) => ser.serialize_field(VERS_FIELD, id),
                 VersionId::Github(id) => ser.serialize_field(VERS_FIELD, id),
+                VersionId::Url(v) => ser.serialize_field(VERS_FIELD, v),
             }?;
         }
         ser.end()
@@ -128,9 +149,12 @@ impl<'de> Deserialize<'de> for ProjectWithVersion {
             Forge,
             Modrinth,
             Github,
+            Url,
+            Maven,
+            Local,
             Version,
         }
-        const FIELDS: &[&str; 4] = &["forge", "modrinth", "github", VERS_FIELD];
+        const FIELDS: &[&str; 7] = &["forge", "modrinth", "github", "url", "maven", "local", VERS_FIELD];
         if let serde_value::Value::Map(fields) = deserializer.deserialize_struct("ProjectWithVersion", FIELDS, ValueVisitor)? {
             macro_rules! into {
                 ($val:expr) => {
@@ -157,6 +181,9 @@ impl<'de> Deserialize<'de> for ProjectWithVersion {
                     Field::Forge => assign!(project = val, ProjectId::Forge),
                     Field::Modrinth => assign!(project = val, ProjectId::Modrinth),
                     Field::Github => assign!(project = val, ProjectId::Github),
+                    Field::Url => assign!(project = val, ProjectId::Url),
+                    Field::Maven => assign!(project = val, ProjectId::Maven),
+                    Field::Local => assign!(project = val, ProjectId::Local),
                     Field::Version => assign!(version = val, "version"),
                 }
             }
@@ -166,6 +193,9 @@ impl<'de> Deserialize<'de> for ProjectWithVersion {
                     ProjectId::Forge(_) => VersionId::Forge(into!(val)),
                     ProjectId::Modrinth(_) => VersionId::Modrinth(into!(val)),
                     ProjectId::Github(_) => VersionId::Github(into!(val)),
+                    ProjectId::Url(_) => VersionId::Url(into!(val)),
+                    ProjectId::Maven(_) => VersionId::Maven(into!(val)),
+                    ProjectId::Local(_) => VersionId::Local(into!(val)),
                 })
             } else {
                 None
@@ -222,6 +252,20 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn serde_url_version() {
+        let val = ProjectWithVersion {
+            project: ProjectId::Url("https://example.com/mod.jar".parse().unwrap()),
+            version: Option::Some(VersionId::Url(crate::client::schema::UrlVersion {
+                sha1: Option::Some("abc".into()),
+                size: Option::None,
+            })),
+        };
+        let json = serde_json::to_string(&val).unwrap();
+        assert_eq!(json, r#"{"url":"https://example.com/mod.jar","version":{"sha1":"abc"}}"#);
+        assert_eq!(serde_json::from_str::<ProjectWithVersion>(&json).unwrap(), val);
+    }
+
     #[test]
     fn no_version() {
         ProjectWithVersion::new(ProjectId::Forge(23), Option::None).expect("project without version should succeed");
@@ -5,7 +5,10 @@ use std::fmt::Display;
 
 use itertools::Itertools;
 
-use crate::{client::schema::ProjectId, config::ModLoader};
+use crate::{
+    client::schema::ProjectId,
+    config::{ModLoader, VersionConstraint},
+};
 
 pub type Result<T> = std::result::Result<T, Error>;
 pub type StdResult<T, E> = std::result::Result<T, E>;
@@ -52,6 +55,8 @@ pub enum ErrorKind {
     Unsupported,
     #[error("All clients in the Multi client failed `{}` operation:\n\t{}", .0, .1.iter().join("\n\t"))]
     Multi(&'static str, Vec<Error>),
+    #[error("Network access is disabled in offline mode")]
+    Offline,
 
     // Config
     #[error("No profiles have been registered")]
@@ -60,16 +65,52 @@ pub enum ErrorKind {
     UnknownProfile,
     #[error("Profile path must be non-empty and absolute")]
     PathInvalid,
+    #[error("Invalid url `{0}`")]
+    InvalidUrl(String),
+    #[error("Invalid version constraint `{0}`")]
+    InvalidConstraint(String),
 
     // Management
     #[error("The developer of `{0}` has denied third party applications from downloading it")]
     DistributionDenied(String),
+    #[error(
+        "The developer of `{title}` has denied third party applications from downloading it, and no identical file was found \
+         on another service. Download it manually from {page} and save it as `{}`",
+        .path.display()
+    )]
+    ManualDownload {
+        title: String,
+        page: String,
+        path: std::path::PathBuf,
+    },
     #[error("No compatible version found for project `{0}`")]
     MissingVersion(ProjectId),
+    #[error("No version of `{0}` satisfies the constraint `{1}`")]
+    Unsatisfied(Box<ProjectId>, VersionConstraint),
     #[error("Failed to download file: {0}")]
     DownloadFailed(url::Url),
+    #[error("{0} file(s) failed to install, so the profile was left unchanged")]
+    InstallFailed(usize),
+    #[error(
+        "{} mod(s) couldn't be resolved, so the profile was left unchanged:\n\t{}",
+        .0.len(),
+        .0.iter().join("\n\t")
+    )]
+    Unresolved(Vec<Error>),
     #[error("Server install not supported for `{}` loader", .0.as_str())]
     ServerUnsupported(ModLoader),
+    #[error(
+        "Can't apply offline, the lockfile doesn't match the profile for:\n\t{}\nApply the profile online to lock them",
+        .0.iter().join("\n\t")
+    )]
+    NotLocked(Vec<ProjectId>),
+    #[error("Can't apply offline, missing from the cache:\n\t{}", .0.iter().join("\n\t"))]
+    NotCached(Vec<String>),
+    #[error(
+        "Can't apply frozen, the lockfile doesn't match the profile:\n\t{}\nApply the profile without freezing it to update the lockfile",
+        .0.iter().join("\n\t")
+    )]
+    LockMismatch(Vec<String>),
 
     // External API - From is manually implemented
     Modrinth(modrinth::Error),
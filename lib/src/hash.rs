@@ -42,6 +42,13 @@ verify_impl! {
     verify_sha1_sync(Sha1::new()) = std;
 }
 
+/// Compute the hex encoded sha1 of the file at `path`
+pub async fn sha1_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha1Async::new();
+    tokio::io::copy(&mut tokio::fs::File::open(path).await?, &mut hasher).await?;
+    Ok(hasher.finalize_str())
+}
+
 #[derive(Debug, Default)]
 pub struct Sha1Async(Sha1);
 impl Sha1Async {
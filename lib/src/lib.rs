@@ -9,7 +9,9 @@ mod cow;
 mod error;
 mod fs_util;
 mod hash;
+mod maven;
 pub mod mgmt;
+mod versioning;
 
 use std::{env, sync::LazyLock};
 
@@ -0,0 +1,115 @@
+use anyhow::anyhow;
+use reqwest::{Client, StatusCode, header::CONTENT_LENGTH};
+use serde::Deserialize;
+use url::Url;
+
+use crate::{ErrorKind, versioning::compare_versions};
+
+
+const SHA1_EXT: &str = ".sha1";
+
+
+fn meta_versions(xml: impl AsRef<str>) -> crate::Result<Vec<String>> {
+    let meta: Meta = quick_xml::de::from_str(xml.as_ref()).map_err(anyhow::Error::new)?;
+    Ok(meta.versioning.versions.version)
+}
+
+/// All versions listed in the `maven-metadata.xml` at `meta_url`
+pub async fn versions(client: &Client, meta_url: &Url) -> crate::Result<Vec<String>> {
+    let resp = client.get(meta_url.clone()).send().await?;
+    if resp.status() == StatusCode::NOT_FOUND {
+        return Err(ErrorKind::DoesNotExist.into());
+    }
+    meta_versions(resp.error_for_status()?.text().await?)
+}
+
+pub async fn latest_version(client: &Client, meta_url: &Url, filter: impl FnMut(&String) -> bool) -> crate::Result<String> {
+    latest(versions(client, meta_url).await?, filter).ok_or(anyhow!("No matching version").into())
+}
+
+/// The newest of `versions` matching `filter` by [`compare_versions`]
+pub fn latest(versions: Vec<String>, filter: impl FnMut(&String) -> bool) -> Option<String> {
+    versions.into_iter().filter(filter).max_by(|a, b| compare_versions(a, b))
+}
+
+/// Locate `file_name` of `version` relative to `meta_url`, with its size and
+/// the checksum from its sidecar file when available
+pub async fn artifact(client: &Client, meta_url: &Url, version: &str, file_name: &str) -> crate::Result<Artifact> {
+    let mut url = meta_url.clone();
+    url.path_segments_mut().unwrap().pop().push(version).push(file_name);
+
+    let head = client.head(url.clone()).send().await?;
+    if head.status() == StatusCode::NOT_FOUND {
+        return Err(ErrorKind::DoesNotExist.into());
+    }
+    let length = head
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|len| len.to_str().ok()?.parse().ok())
+        .unwrap_or_default();
+
+    let sha1 = {
+        let mut url = url.clone();
+        url.path_segments_mut().unwrap().pop().push(&format!("{file_name}{SHA1_EXT}"));
+        sidecar_sha1(client, url).await
+    };
+
+    Ok(Artifact { url, length, sha1 })
+}
+
+/// Sidecar files may also contain the file name after the hash, so only the
+/// first word is used
+async fn sidecar_sha1(client: &Client, url: Url) -> Option<String> {
+    let text = client.get(url).send().await.ok()?.error_for_status().ok()?.text().await.ok()?;
+    let sha1 = text.split_whitespace().next()?;
+    (sha1.len() == 40 && sha1.bytes().all(|b| b.is_ascii_hexdigit())).then(|| sha1.to_ascii_lowercase())
+}
+
+
+#[derive(Debug, Deserialize)]
+struct Meta {
+    versioning: Versioning,
+}
+#[derive(Debug, Deserialize)]
+struct Versioning {
+    versions: Versions,
+}
+#[derive(Debug, Deserialize)]
+struct Versions {
+    #[serde(default)]
+    version: Vec<String>,
+}
+
+
+/// A single file published to a maven repository
+#[derive(Debug)]
+pub struct Artifact {
+    pub url: Url,
+    pub length: u64,
+    pub sha1: Option<String>,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latest_server_installers() {
+        let versions = |v: &[&str]| v.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        // Same filters as the forge, neoforge, and quilt server installs
+        let forge = versions(&["1.20.1-47.2.0", "1.20.1-47.10.0", "1.20.1-47.9.1", "1.20.2-48.0.1"]);
+        let latest_forge = latest(forge, |v| v.split_once('-').is_some_and(|(mc, _)| mc == "1.20.1"));
+        assert_eq!(latest_forge.as_deref(), Some("1.20.1-47.10.0"));
+
+        let neoforge = versions(&["21.1.9", "21.1.77", "21.1.100", "21.2.0-beta"]);
+        let latest_neoforge = latest(neoforge, |v| v.rsplit_once('.').is_some_and(|(mc, _)| mc == "21.1"));
+        assert_eq!(latest_neoforge.as_deref(), Some("21.1.100"));
+
+        let quilt = versions(&["0.9.2", "0.26.4", "0.27.0-beta.1", "0.10.0"]);
+        assert_eq!(latest(quilt, |v| !v.contains('-')).as_deref(), Some("0.26.4"));
+
+        assert_eq!(latest(vec![], |_| true), None);
+    }
+}
@@ -14,12 +14,22 @@ use std::sync::mpsc::{self, Sender};
 
 use self::events::ProgressEvent;
 // Used by client in crate-scoped update fn
-pub(crate) use self::lockfile::LockedMod;
-pub use self::{cache::CACHE_DIR, mods::update::UpdateInfo};
+pub(crate) use self::lockfile::{LockedMod, ModOrigin};
+pub use self::{
+    cache::{CACHE_DIR, CacheStatus, CachedFile, CorruptAction, GcOptions, VerifiedFile, VerifyOptions},
+    mods::{
+        changelog::Changelog,
+        deps::Dependencies,
+        plan::{InstallPlan, LockChange, PlannedFile},
+        update::UpdateInfo,
+    },
+};
+use crate::config::{Endpoints, InstallStrategy, Mirror};
 
 /// Handles the actual downloading, installing, updating, etc. of the contents
 /// of a [`profile`](crate::config::Profile)
 #[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)] // Independent options
 pub struct ProfileManager {
     channel: EventChannel,
     /// Always install/overwrite files without checking if they are already
@@ -27,6 +37,31 @@ pub struct ProfileManager {
     pub force: bool,
     /// Don't use cache and download files directly to profile
     pub no_cache: bool,
+    /// Never download anything. Versions are only resolved from the lockfile
+    /// and cached metadata, and only files that already exist in the cache or
+    /// profile will be installed. [`apply`](Self::apply) fails before changing
+    /// anything if any of them are missing
+    pub offline: bool,
+    /// Install exactly the versions and files recorded in the lockfile, from
+    /// their locked urls, without resolving anything through the apis.
+    /// [`apply`](Self::apply) fails before changing anything if the profile
+    /// and lockfile disagree, or a file that isn't already in the profile or
+    /// cache has no locked url, like files that can only be downloaded manually
+    pub frozen: bool,
+    /// Install whatever succeeds directly to the profile instead of undoing
+    /// every change when any file fails to install, and skip mods that can't
+    /// be resolved instead of failing
+    pub allow_partial: bool,
+    /// Base urls used for fetching server installers. Api clients are
+    /// configured when they are built
+    pub endpoints: Endpoints,
+    /// How cached files are installed into profiles that don't
+    /// [set their own](crate::config::profile::ProfileData::install)
+    pub install_strategy: InstallStrategy,
+    /// Rules for downloading files from mirrors, in order of preference
+    pub mirrors: Vec<Mirror>,
+    hosts: download::HostHealth,
+    transfers: download::Transfers,
 }
 
 impl ProfileManager {
@@ -38,6 +73,14 @@ impl ProfileManager {
             channel: EventChannel(sender),
             force: false,
             no_cache: false,
+            offline: false,
+            frozen: false,
+            allow_partial: false,
+            endpoints: Endpoints::default(),
+            install_strategy: InstallStrategy::Copy,
+            mirrors: vec![],
+            hosts: download::HostHealth::default(),
+            transfers: download::Transfers::default(),
         }
     }
 
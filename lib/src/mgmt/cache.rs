@@ -1,14 +1,36 @@
-use std::sync::LazyLock;
+mod archive;
+mod gc;
+mod verify;
 
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use sha1::{Digest, Sha1};
+use tokio::fs;
+
+pub use self::{
+    gc::{CachedFile, GcOptions},
+    verify::{CacheStatus, CorruptAction, VerifiedFile, VerifyOptions},
+};
 use crate::{
     checked_types::{PathAbsolute, PathScopedRef},
     client::schema::{ProjectId, Version, VersionId},
-    config::ModLoader,
+    config::{InstallStrategy, ModLoader},
+    mgmt::lockfile::{LockFile, LockedMod},
 };
 
+/// Where corrupted files are moved by
+/// [`verify_cache`](crate::ProfileManager::verify_cache)
+const QUARANTINE: &str = "quarantine";
+/// Cache directories that don't contain versioned files
+const UNVERSIONED: [&str; 4] = ["meta", "http", "server", QUARANTINE];
+
 
-/// The base path where files are downloaded to before they are copied into a
-/// profile
+/// The base path where files are downloaded to before they are copied or linked
+/// into a profile
 pub static CACHE_DIR: LazyLock<PathAbsolute> = LazyLock::new(|| {
     dirs::cache_dir()
         .expect("system cache directory should be known")
@@ -29,7 +51,14 @@ pub fn version_path(v: &Version, sub: Option<&PathScopedRef>) -> PathAbsolute {
 
 pub fn versioned_path(proj_id: &ProjectId, vers_id: &VersionId, file: &std::ffi::OsStr, sub: Option<&PathScopedRef>) -> PathAbsolute {
     let mut path = CACHE_DIR.join(sub.unwrap_or_default());
-    path.push(proj_id.to_string());
+    match proj_id {
+        // Urls can't be used as a path, so use their hash instead
+        ProjectId::Url(url) => path.push(format!("url/{:x}", Sha1::digest(url.as_str()))),
+        ProjectId::Local(pattern) => path.push(format!("local/{:x}", Sha1::digest(pattern))),
+        // File names already include the classifier
+        ProjectId::Maven(id) => path.push(format!("maven/{}/{}", id.group(), id.artifact())),
+        _ => path.push(proj_id.to_string()),
+    }
     path.push(vers_id.to_string());
     path.push(file);
     path
@@ -40,3 +69,134 @@ pub fn server_path(loader: Option<ModLoader>) -> PathAbsolute {
     path.push(loader.map_or("vanilla", ModLoader::as_str));
     path
 }
+
+/// Every file in the directories of the cache containing versioned files
+async fn versioned_files() -> crate::Result<Vec<gc::CachedFile>> {
+    let files = tokio::task::spawn_blocking(|| {
+        let mut files = vec![];
+        for sub in std::fs::read_dir(&*CACHE_DIR)? {
+            let sub = sub?;
+            if sub.file_type()?.is_dir() && !UNVERSIONED.iter().any(|&u| sub.file_name() == u) {
+                cached_files(&sub.path(), &mut files)?;
+            }
+        }
+        io::Result::Ok(files)
+    })
+    .await
+    .map_err(anyhow::Error::new)?;
+    match files {
+        Ok(files) => Ok(files),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(anyhow::Error::new(e).context("Failed to read cache").into()),
+    }
+}
+
+/// Recursively collect every file in `dir`
+fn cached_files(dir: &Path, out: &mut Vec<gc::CachedFile>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let meta = entry.metadata()?;
+        if meta.is_dir() {
+            cached_files(&entry.path(), out)?;
+        } else {
+            let modified = meta.modified()?;
+            out.push(gc::CachedFile {
+                path: entry.path(),
+                size: meta.len(),
+                used: meta.accessed().map_or(modified, |accessed| accessed.max(modified)),
+                evicted: false,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Everything locked in `lockfile`, with the path its file was downloaded to in
+/// the cache
+fn locked_files(lockfile: &LockFile) -> impl Iterator<Item = (PathBuf, &LockedMod)> {
+    let pack = lockfile.pack.iter().map(|lp| {
+        let file = lp.file.file_name().unwrap_or_default();
+        let path = versioned_path(&lp.id.project, &lp.id.version, file, PathScopedRef::new("modpacks").ok());
+        (path.into(), &**lp)
+    });
+    let mods = lockfile.mods.iter().chain(&lockfile.outdated).map(|lm| {
+        // Substitutes are downloaded as the version that replaced the locked one
+        let id = lm.substitute.as_ref().unwrap_or(&lm.id);
+        let sub = lm.file.parent().and_then(|p| p.file_name_path());
+        let file = lm.file.file_name().unwrap_or_default();
+        let path = versioned_path(&id.project, &id.version, file, sub.or(PathScopedRef::new("mods").ok()));
+        (path.into(), lm)
+    });
+    mods.chain(pack)
+}
+
+/// Install the `cached` file to `dest` using `strategy`, falling back to a copy
+/// if the filesystem doesn't support it. Returns the strategy actually used
+///
+/// Any existing file at `dest` is removed first, so a file previously linked to
+/// the cache is never written through
+pub async fn install_file(strategy: InstallStrategy, cached: &Path, dest: &Path) -> io::Result<InstallStrategy> {
+    if fs::symlink_metadata(dest).await.is_ok() {
+        fs::remove_file(dest).await?;
+    }
+    let linked = match strategy {
+        InstallStrategy::Copy => return fs::copy(cached, dest).await.map(|_| strategy),
+        InstallStrategy::Hardlink => fs::hard_link(cached, dest).await,
+        InstallStrategy::Symlink => symlink(cached, dest).await,
+        InstallStrategy::Reflink => {
+            let (cached, dest) = (cached.to_owned(), dest.to_owned());
+            tokio::task::spawn_blocking(move || reflink_copy::reflink(cached, dest))
+                .await
+                .unwrap_or_else(|e| Err(io::Error::other(e)))
+        },
+    };
+    if linked.is_ok() {
+        return Ok(strategy);
+    }
+    // A failed link may still leave a partial file behind
+    let _ = fs::remove_file(dest).await;
+    fs::copy(cached, dest).await.map(|_| InstallStrategy::Copy)
+}
+
+#[cfg(unix)]
+async fn symlink(original: &Path, link: &Path) -> io::Result<()> {
+    fs::symlink(original, link).await
+}
+
+#[cfg(windows)]
+async fn symlink(original: &Path, link: &Path) -> io::Result<()> {
+    fs::symlink_file(original, link).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn copy_unsupported_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let cached = dir.path().join("cached.jar");
+        let dest = dir.path().join("a.jar");
+        std::fs::write(&cached, "cached").unwrap();
+        std::fs::write(&dest, "previous").unwrap();
+
+        // Reflinks aren't supported by most filesystems, and are independent
+        // copies where they are
+        let used = install_file(InstallStrategy::Reflink, &cached, &dest).await.unwrap();
+        assert!(matches!(used, InstallStrategy::Reflink | InstallStrategy::Copy));
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "cached");
+        std::fs::write(&dest, "changed").unwrap();
+        assert_eq!(std::fs::read_to_string(&cached).unwrap(), "cached");
+
+        // Hard links can't cross filesystems
+        #[cfg(target_os = "linux")]
+        {
+            let used = install_file(InstallStrategy::Hardlink, Path::new("/proc/version"), &dest)
+                .await
+                .unwrap();
+            assert_eq!(used, InstallStrategy::Copy);
+            assert!(dest.symlink_metadata().unwrap().is_file());
+            assert!(std::fs::read_to_string(&dest).unwrap().starts_with("Linux"));
+        }
+    }
+}
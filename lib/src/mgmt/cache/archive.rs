@@ -0,0 +1,239 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow};
+use itertools::Itertools;
+use tokio::fs;
+use zip::{CompressionMethod, ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+use super::{CACHE_DIR, locked_files};
+use crate::{
+    Result,
+    client::{CacheOptions, project_file, version_file},
+    config::Profile,
+    hash::verify_sha1_sync,
+    mgmt::{ProfileManager, events::EventSouce, lockfile::LockFile},
+};
+
+/// A file in the [cache](CACHE_DIR) needed to apply a profile
+/// [offline](ProfileManager::offline)
+struct Needed {
+    /// The locked sha1 of the file, or [`None`] for cached metadata
+    sha1: Option<String>,
+    /// Which locked file this is, when it isn't metadata
+    locked: Option<String>,
+}
+
+impl ProfileManager {
+    /// Write every cached file needed to [apply](Self::apply) `profiles`
+    /// [offline](Self::offline) to a zip archive at `path`, so it can be
+    /// [imported](Self::import_cache) into the cache on another system. This is
+    /// the locked version of every mod and modpack, and their cached metadata.
+    /// Returns the exported files, relative to the [cache](CACHE_DIR)
+    ///
+    /// Locked files missing from the cache are sent to the
+    /// [`channel`](Self::with_channel) and left out of the archive
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the lockfile of any profile fails to load, or
+    /// the archive can't be written
+    pub async fn export_cache(&self, profiles: &[&Profile], path: &Path) -> Result<Vec<PathBuf>> {
+        let mut files = vec![];
+        for (rel, needed) in needed_files(profiles).await? {
+            if fs::metadata(CACHE_DIR.join(&rel)).await.is_ok_and(|m| m.is_file()) {
+                files.push(rel);
+            } else if let Some(locked) = needed.locked {
+                self.send_err(anyhow!("{locked} is not cached").into());
+            }
+        }
+
+        let path = path.to_owned();
+        tokio::task::spawn_blocking(move || write_archive(&path, &CACHE_DIR, &files).map(|()| files))
+            .await
+            .map_err(anyhow::Error::new)?
+    }
+
+    /// Extract an archive made by [`export_cache`](Self::export_cache) at
+    /// `path` into the [cache](CACHE_DIR). Only files needed to apply
+    /// `profiles` [offline](Self::offline) are imported, and files that don't
+    /// match the sha1 locked by the profiles are sent to the
+    /// [`channel`](Self::with_channel) and skipped. Files already in the cache
+    /// are kept unless [forced](Self::force). Returns the imported files,
+    /// relative to the cache
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the lockfile of any profile fails to load, the
+    /// archive can't be read, or any file fails to be written to the cache
+    pub async fn import_cache(&self, profiles: &[&Profile], path: &Path) -> Result<Vec<PathBuf>> {
+        let needed = { needed_files(profiles).await?.into_iter() }
+            .map(|(rel, needed)| (rel, needed.sha1))
+            .collect();
+        let (path, force) = (path.to_owned(), self.force);
+        let (imported, skipped) = tokio::task::spawn_blocking(move || extract_archive(&path, &CACHE_DIR, &needed, force))
+            .await
+            .map_err(anyhow::Error::new)??;
+        for e in skipped {
+            self.send_err(e);
+        }
+        Ok(imported)
+    }
+}
+
+/// Every file needed to apply `profiles` offline, relative to the
+/// [cache](CACHE_DIR). This is the locked version of every mod and modpack,
+/// and their metadata
+async fn needed_files(profiles: &[&Profile]) -> Result<BTreeMap<PathBuf, Needed>> {
+    let meta = CacheOptions::default().dir;
+    let mut needed = BTreeMap::new();
+    for profile in profiles {
+        let lockfile = LockFile::load(profile.path())
+            .await
+            .with_context(|| format!("Failed to load lockfile of profile `{}`", profile.name()))?;
+        // Outdated versions are only kept to roll back to
+        let locked = locked_files(&lockfile).filter(|(_, lm)| !lockfile.outdated.iter().any(|o| std::ptr::eq(o, *lm)));
+        for (cached, lm) in locked {
+            if let Ok(rel) = cached.strip_prefix(&*CACHE_DIR) {
+                needed.insert(rel.to_owned(), Needed {
+                    sha1: Some(lm.sha1.clone()),
+                    locked: Some(format!("`{}` of profile `{}`", lm.file.display(), profile.name())),
+                });
+            }
+            for id in [Some(&lm.id), lm.substitute.as_ref()].into_iter().flatten() {
+                let metadata = [project_file(&id.project), version_file(&id.version)];
+                for file in metadata.into_iter().flatten() {
+                    if let Ok(rel) = meta.join(file).strip_prefix(&*CACHE_DIR) {
+                        needed.entry(rel.to_owned()).or_insert(Needed { sha1: None, locked: None });
+                    }
+                }
+            }
+        }
+    }
+    Ok(needed)
+}
+
+/// Write `files`, relative to `cache`, to a new zip archive at `path`
+fn write_archive(path: &Path, cache: &Path, files: &[PathBuf]) -> Result<()> {
+    let mut zip = ZipWriter::new(File::create(path)?);
+    // Mods and modpacks are already compressed
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    for rel in files {
+        let name = rel.components().map(|c| c.as_os_str().to_string_lossy()).join("/");
+        zip.start_file(name, options).context("Failed to write cache archive")?;
+        io::copy(&mut File::open(cache.join(rel))?, &mut zip)?;
+    }
+    zip.finish().context("Failed to write cache archive")?;
+    Ok(())
+}
+
+/// Extract the `needed` files of the zip archive at `path` into `cache`,
+/// verifying the files that have a locked sha1. Returns the imported files,
+/// and why any others were skipped
+fn extract_archive(
+    path: &Path,
+    cache: &Path,
+    needed: &HashMap<PathBuf, Option<String>>,
+    force: bool,
+) -> Result<(Vec<PathBuf>, Vec<crate::Error>)> {
+    let mut zip = ZipArchive::new(File::open(path)?).context("Invalid cache archive")?;
+    let mut imported = vec![];
+    let mut skipped = vec![];
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).context("Invalid cache archive")?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(rel) = entry.enclosed_name() else {
+            skipped.push(anyhow!("Skipped cache archive entry with unsafe path `{}`", entry.name()).into());
+            continue;
+        };
+        let Some(sha1) = needed.get(&rel) else {
+            skipped.push(anyhow!("Skipped `{}`, it isn't needed by any profile", rel.display()).into());
+            continue;
+        };
+        let dest = cache.join(&rel);
+        if !force && dest.exists() {
+            continue;
+        }
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Never leave a partial or unverified file in the cache
+        let mut temp = dest.clone();
+        temp.as_mut_os_string().push(".import");
+        let written = io::copy(&mut entry, &mut File::create(&temp)?).with_context(|| format!("Failed to import `{}`", rel.display()));
+        let verified = written.map(|_| sha1.as_ref().is_none_or(|sha1| verify_sha1_sync(sha1, &temp).unwrap_or(false)));
+        match verified {
+            Ok(true) => {},
+            Ok(false) => {
+                let _ = std::fs::remove_file(&temp);
+                skipped.push(anyhow!("Skipped `{}`, it doesn't match its locked sha1", rel.display()).into());
+                continue;
+            },
+            Err(e) => {
+                let _ = std::fs::remove_file(&temp);
+                return Err(e.into());
+            },
+        }
+        std::fs::rename(&temp, &dest)
+            .inspect_err(|_| {
+                let _ = std::fs::remove_file(&temp);
+            })
+            .with_context(|| format!("Failed to import `{}`", rel.display()))?;
+        imported.push(rel);
+    }
+    Ok((imported, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use sha1::{Digest, Sha1};
+
+    use super::*;
+
+    #[test]
+    fn import_verified() {
+        let dir = tempfile::tempdir().unwrap();
+        let (archive, cache) = (dir.path().join("cache.zip"), dir.path().join("cache"));
+        let mut zip = ZipWriter::new(File::create(&archive).unwrap());
+        for (name, data) in [
+            ("mods/valid.jar", "valid"),
+            ("mods/tampered.jar", "tampered"),
+            ("meta/versions/modrinth/abc.json", "{}"),
+            ("mods/unlocked.jar", "unlocked"),
+        ] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(data.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let sha1 = |data: &str| Some(format!("{:x}", Sha1::digest(data)));
+        let needed = HashMap::from([
+            (PathBuf::from("mods/valid.jar"), sha1("valid")),
+            (PathBuf::from("mods/tampered.jar"), sha1("original")),
+            (PathBuf::from("meta/versions/modrinth/abc.json"), None),
+        ]);
+        let (imported, skipped) = extract_archive(&archive, &cache, &needed, false).unwrap();
+        assert_eq!(imported, [
+            PathBuf::from("mods/valid.jar"),
+            PathBuf::from("meta/versions/modrinth/abc.json")
+        ]);
+        assert_eq!(skipped.len(), 2);
+        assert!(!cache.join("mods/tampered.jar").exists());
+        assert!(!cache.join("mods/tampered.jar.import").exists());
+        assert!(!cache.join("mods/unlocked.jar").exists());
+
+        // Cached files are kept unless forced
+        let (imported, _) = extract_archive(&archive, &cache, &needed, false).unwrap();
+        assert!(imported.is_empty());
+        let (imported, _) = extract_archive(&archive, &cache, &needed, true).unwrap();
+        assert_eq!(imported.len(), 2);
+    }
+}
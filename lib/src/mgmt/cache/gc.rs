@@ -0,0 +1,217 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Context;
+
+use super::{CACHE_DIR, locked_files, versioned_files};
+use crate::{
+    Result,
+    config::Profile,
+    mgmt::{ProfileManager, events::EventSouce, lockfile::LockFile},
+};
+
+/// Options for [`collect_garbage`](ProfileManager::collect_garbage). The
+/// default removes every cached version that isn't locked by a profile
+#[derive(Debug, Clone, Default)]
+pub struct GcOptions {
+    /// The number of unreferenced versions to keep for each project, most
+    /// recently used first, so they can still be rolled back to
+    pub keep: usize,
+    /// Only remove unreferenced versions that haven't been used for at least
+    /// this long
+    pub older_than: Option<Duration>,
+    /// Remove the least recently used unreferenced versions until the cache is
+    /// no larger than this many bytes, including any kept by the other options
+    pub max_size: Option<u64>,
+    /// Only find what would be removed without deleting anything
+    pub dry_run: bool,
+}
+
+/// A file removed from the cache by
+/// [`collect_garbage`](ProfileManager::collect_garbage)
+#[derive(Debug, Clone)]
+pub struct CachedFile {
+    #[allow(missing_docs)]
+    pub path: PathBuf,
+    /// The size of the file in bytes
+    pub size: u64,
+    /// The last time the file was downloaded or accessed
+    pub used: SystemTime,
+    /// `true` if the file was only removed to enforce the
+    /// [size cap](GcOptions::max_size)
+    pub evicted: bool,
+}
+
+impl ProfileManager {
+    /// Remove cached versions that aren't locked by any of `profiles`, keeping
+    /// some according to `options`. Returns the removed files, or the files
+    /// that would be removed for a [dry run](GcOptions::dry_run)
+    ///
+    /// Every profile that uses the cache should be included, otherwise files
+    /// still needed to reinstall, or linked into, the missing profiles may be
+    /// removed
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the lockfile of any profile fails to load, or
+    /// the cache can't be read. Files that fail to delete are sent to the
+    /// [`channel`](Self::with_channel)
+    pub async fn collect_garbage(&self, profiles: &[&Profile], options: &GcOptions) -> Result<Vec<CachedFile>> {
+        let mut referenced = HashSet::new();
+        for profile in profiles {
+            let lockfile = LockFile::load(profile.path())
+                .await
+                .with_context(|| format!("Failed to load lockfile of profile `{}`", profile.name()))?;
+            referenced.extend(locked_files(&lockfile).map(|(path, _)| path));
+        }
+
+        let files = versioned_files().await?;
+
+        let mut removed = select_garbage(files, &referenced, options, SystemTime::now());
+        if !options.dry_run {
+            removed.retain(|file| match std::fs::remove_file(&file.path) {
+                Ok(()) => {
+                    remove_empty_parents(&file.path);
+                    true
+                },
+                Err(e) => {
+                    self.send_err(
+                        anyhow::Error::new(e)
+                            .context(format!("Failed to delete cached file `{}`", file.path.display()))
+                            .into(),
+                    );
+                    false
+                },
+            });
+        }
+        Ok(removed)
+    }
+}
+
+/// Choose which of the cached `files` to remove
+fn select_garbage(files: Vec<CachedFile>, referenced: &HashSet<PathBuf>, options: &GcOptions, now: SystemTime) -> Vec<CachedFile> {
+    let mut total = files.iter().map(|f| f.size).sum::<u64>();
+    let (_, unreferenced): (Vec<_>, Vec<_>) = files.into_iter().partition(|f| referenced.contains(&f.path));
+
+    // Files are stored as `<project>/<version>/<file>`
+    let mut projects = HashMap::<_, Vec<_>>::new();
+    for file in unreferenced {
+        let version = file.path.parent().map(Path::to_owned);
+        let project = version.as_deref().and_then(Path::parent).map(Path::to_owned);
+        projects.entry(project).or_default().push((version, file));
+    }
+
+    let mut removed = vec![];
+    let mut kept = vec![];
+    for versions in projects.into_values() {
+        let mut latest = HashMap::new();
+        for (version, file) in &versions {
+            let used = latest.entry(version.clone()).or_insert(file.used);
+            *used = (*used).max(file.used);
+        }
+        let mut latest = latest.into_iter().collect::<Vec<_>>();
+        latest.sort_unstable_by_key(|&(_, used)| std::cmp::Reverse(used));
+        let keep = latest.into_iter().take(options.keep).map(|(v, _)| v).collect::<HashSet<_>>();
+
+        for (version, file) in versions {
+            let recent = options
+                .older_than
+                .is_some_and(|age| now.duration_since(file.used).unwrap_or_default() < age);
+            if keep.contains(&version) || recent {
+                kept.push(file);
+            } else {
+                total -= file.size;
+                removed.push(file);
+            }
+        }
+    }
+
+    if let Some(max_size) = options.max_size {
+        kept.sort_unstable_by_key(|f| f.used);
+        for mut file in kept {
+            if total <= max_size {
+                break;
+            }
+            total -= file.size;
+            file.evicted = true;
+            removed.push(file);
+        }
+    }
+    removed.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+    removed
+}
+
+/// Remove the parent directories of a deleted file until one isn't empty
+fn remove_empty_parents(file: &Path) {
+    for dir in file.ancestors().skip(1) {
+        if dir == &**CACHE_DIR || std::fs::remove_dir(dir).is_err() {
+            break;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, size: u64, age_days: u64) -> CachedFile {
+        CachedFile {
+            path: path.into(),
+            size,
+            used: SystemTime::UNIX_EPOCH + Duration::from_secs((100 - age_days) * 86400),
+            evicted: false,
+        }
+    }
+
+    fn select(options: &GcOptions) -> Vec<(PathBuf, bool)> {
+        let files = vec![
+            file("/c/mods/a/1/a.jar", 10, 30),
+            file("/c/mods/a/2/a.jar", 10, 20),
+            file("/c/mods/a/3/a.jar", 10, 10),
+            file("/c/mods/a/4/a.jar", 10, 0),
+            file("/c/mods/b/1/b.jar", 50, 5),
+        ];
+        let referenced = HashSet::from(["/c/mods/a/4/a.jar".into()]);
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * 86400);
+        select_garbage(files, &referenced, options, now)
+            .into_iter()
+            .map(|f| (f.path, f.evicted))
+            .collect()
+    }
+
+    #[test]
+    fn unreferenced() {
+        let removed = select(&GcOptions::default());
+        assert_eq!(removed.len(), 4);
+        assert!(removed.iter().all(|(p, evicted)| p != Path::new("/c/mods/a/4/a.jar") && !evicted));
+    }
+
+    #[test]
+    fn keep_and_age() {
+        let removed = select(&GcOptions {
+            keep: 1,
+            older_than: Some(Duration::from_secs(15 * 86400)),
+            ..Default::default()
+        });
+        assert_eq!(removed, [("/c/mods/a/1/a.jar".into(), false), ("/c/mods/a/2/a.jar".into(), false)]);
+    }
+
+    #[test]
+    fn size_cap() {
+        let removed = select(&GcOptions {
+            keep: 2,
+            max_size: Some(60),
+            ..Default::default()
+        });
+        // Least recently used versions are evicted first, regardless of size
+        assert_eq!(removed, [
+            ("/c/mods/a/1/a.jar".into(), false),
+            ("/c/mods/a/2/a.jar".into(), true),
+            ("/c/mods/a/3/a.jar".into(), true),
+        ]);
+    }
+}
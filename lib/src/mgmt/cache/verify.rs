@@ -0,0 +1,442 @@
+use std::{
+    collections::{HashMap, HashSet},
+    convert::identity,
+    path::{Component, Path, PathBuf},
+};
+
+use ::github::models::AssetId;
+use anyhow::{Context, anyhow};
+use async_scoped::TokioScope;
+
+use super::{CACHE_DIR, QUARANTINE, locked_files, versioned_files};
+use crate::{
+    Client, Result,
+    checked_types::PathScoped,
+    client::{
+        github,
+        schema::{ProjectId, Version, VersionId},
+    },
+    config::{Profile, profile::ProfileData},
+    hash::{sha1_file, verify_sha1},
+    mgmt::{
+        ProfileManager,
+        events::{EventSouce, ProgressEvent},
+        lockfile::LockFile,
+        version::VersionSet,
+    },
+};
+
+/// What [`verify_cache`](ProfileManager::verify_cache) does with corrupted
+/// files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CorruptAction {
+    /// Leave them in place
+    #[default]
+    Report,
+    /// Move them to a `quarantine` directory in the cache
+    Quarantine,
+    /// Delete them
+    Delete,
+}
+
+/// Options for [`verify_cache`](ProfileManager::verify_cache)
+#[derive(Debug, Clone, Default)]
+pub struct VerifyOptions {
+    #[allow(missing_docs)]
+    pub action: CorruptAction,
+    /// Download corrupted files again when their version is known
+    pub redownload: bool,
+}
+
+/// A cached file checked by [`verify_cache`](ProfileManager::verify_cache)
+#[derive(Debug, Clone)]
+pub struct VerifiedFile {
+    #[allow(missing_docs)]
+    pub path: PathBuf,
+    #[allow(missing_docs)]
+    pub status: CacheStatus,
+}
+
+/// The result of verifying a cached file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheStatus {
+    /// The file matches its expected sha1
+    Valid,
+    /// The expected sha1 of the file isn't known
+    Unknown,
+    /// The file doesn't match its expected sha1. `actual` is [`None`] if the
+    /// file couldn't be read
+    #[allow(missing_docs)]
+    Corrupt { expected: String, actual: Option<String> },
+    /// The file was corrupt and has been downloaded again
+    Repaired,
+}
+
+/// The expected contents of a cached file, and the version it can be
+/// downloaded again from if known
+#[derive(Default)]
+struct Expected {
+    sha1: Option<String>,
+    version: Option<Version>,
+    locked: Option<(ProjectId, VersionId)>,
+}
+
+impl ProfileManager {
+    /// Hash every versioned file in the [cache](CACHE_DIR) and compare it
+    /// against the sha1 locked by any of `profiles`, or the sha1 from the
+    /// provider of its version. Corrupted files are handled according to
+    /// `options`. Profile files installed from a corrupted file are installed
+    /// again once it's repaired, and reported otherwise
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the lockfile of any profile fails to load, or
+    /// the cache can't be read. Any other errors will be sent to the
+    /// [`channel`](Self::with_channel)
+    pub async fn verify_cache(&self, client: &Client, profiles: &[&Profile], options: &VerifyOptions) -> Result<Vec<VerifiedFile>> {
+        let files = versioned_files()
+            .await?
+            .into_iter()
+            .map(|f| f.path)
+            // Unfinished downloads
+            .filter(|p| p.extension().is_none_or(|ext| ext != "part"))
+            .collect::<Vec<_>>();
+
+        self.send(ProgressEvent::Status("Loading lockfiles...".to_owned()));
+        let mut expected = HashMap::<_, Expected>::new();
+        let mut copies = HashMap::<_, Vec<_>>::new();
+        for &profile in profiles {
+            let lockfile = LockFile::load(profile.path())
+                .await
+                .with_context(|| format!("Failed to load lockfile of profile `{}`", profile.name()))?;
+            for (path, lm) in locked_files(&lockfile) {
+                let id = lm.substitute.as_ref().unwrap_or(&lm.id);
+                copies
+                    .entry(path.clone())
+                    .or_default()
+                    .push((profile, lm.file.clone(), lm.sha1.clone()));
+                expected.entry(path).or_insert_with(|| Expected {
+                    sha1: Some(lm.sha1.clone()),
+                    version: None,
+                    locked: Some((id.project.clone(), id.version.clone())),
+                });
+            }
+        }
+
+        self.send(ProgressEvent::Status("Fetching version details...".to_owned()));
+        self.fetch_expected(client, &files, &mut expected).await;
+
+        self.send(ProgressEvent::Status(format!("Hashing {} cached files...", files.len())));
+        let ((), hashes) = TokioScope::scope_and_block(|scope| {
+            for path in &files {
+                scope.spawn(async move {
+                    let _permit = self.hash_jobs().acquire().await;
+                    sha1_file(path).await
+                });
+            }
+        });
+
+        let mut verified = Vec::with_capacity(files.len());
+        for (path, hash) in files.into_iter().zip(hashes) {
+            let actual = match hash {
+                Ok(Ok(sha1)) => Some(sha1),
+                Ok(Err(e)) => {
+                    self.send_err(e);
+                    None
+                },
+                Err(e) => {
+                    self.send_err(anyhow!(e).into());
+                    None
+                },
+            };
+            let status = match expected.get(&path).and_then(|e| e.sha1.as_ref()) {
+                None => CacheStatus::Unknown,
+                Some(sha1) if actual.as_ref().is_some_and(|a| a.eq_ignore_ascii_case(sha1)) => CacheStatus::Valid,
+                Some(sha1) => CacheStatus::Corrupt {
+                    expected: sha1.clone(),
+                    actual,
+                },
+            };
+            verified.push(VerifiedFile { path, status });
+        }
+
+        for file in &mut verified {
+            if !matches!(file.status, CacheStatus::Corrupt { .. }) {
+                continue;
+            }
+            if let Err(e) = remove_corrupt(&file.path, options.action).await {
+                self.send_err(e);
+                continue;
+            }
+            if options.redownload {
+                let expected = expected.remove(&file.path).unwrap_or_default();
+                if self.redownload(client, &file.path, expected).await {
+                    file.status = CacheStatus::Repaired;
+                }
+            }
+            let copies = copies.remove(&file.path).unwrap_or_default();
+            self.fix_copies(&file.path, copies, file.status == CacheStatus::Repaired).await;
+        }
+        Ok(verified)
+    }
+
+    /// Install the profile `copies` of the corrupted cached file at `path`
+    /// again if it was `repaired`, or report them otherwise. Copies linked to
+    /// the cache still point to the corrupted contents after it's removed
+    async fn fix_copies(&self, path: &Path, copies: Vec<(&Profile, PathScoped, String)>, repaired: bool) {
+        for (profile, file, sha1) in copies {
+            let dest = profile.path().join(&file);
+            if dest.symlink_metadata().is_err() || verify_sha1(&sha1, &dest).await.is_ok_and(identity) {
+                continue;
+            }
+            if repaired {
+                let strategy = { profile.data().await.ok() }
+                    .and_then(|data| data.install)
+                    .unwrap_or(self.install_strategy);
+                match super::install_file(strategy, path, &dest).await {
+                    Ok(_) => {
+                        self.send(ProgressEvent::Status(format!(
+                            "Installed `{}` in profile `{}` again",
+                            file.display(),
+                            profile.name()
+                        )));
+                        continue;
+                    },
+                    Err(e) => self.send_err(anyhow!(e).context(format!("Failed to install `{}` again", file.display())).into()),
+                }
+            }
+            self.send_err(
+                anyhow!(
+                    "`{}` in profile `{}` was installed from a corrupted cached file. Apply the profile again to replace it",
+                    file.display(),
+                    profile.name()
+                )
+                .into(),
+            );
+        }
+    }
+
+    /// Fill in the expected sha1 of `files` from the versions that can be
+    /// identified by their path in the cache
+    async fn fetch_expected(&self, client: &Client, files: &[PathBuf], expected: &mut HashMap<PathBuf, Expected>) {
+        let mut versions = HashMap::<_, Vec<_>>::new();
+        let mut assets = vec![];
+        for path in files {
+            let entry = expected.entry(path.clone()).or_default();
+            if entry.sha1.is_some() {
+                continue;
+            }
+            match identify(path) {
+                Identity::Sha1(sha1) => entry.sha1 = Some(sha1),
+                Identity::Versions(candidates) => {
+                    for (project, version) in candidates {
+                        if let (ProjectId::Github((owner, repo)), VersionId::Github(asset)) = (&project, &version) {
+                            assets.push(((owner.clone(), repo.clone()), *asset));
+                        }
+                        versions.entry(version).or_default().push(path.clone());
+                    }
+                },
+                Identity::Unknown => {},
+            }
+        }
+        if versions.is_empty() {
+            return;
+        }
+
+        let ids = versions.keys().map(|v| v as _).collect::<Vec<_>>();
+        let mut found = match client.get_versions(&ids).await {
+            Ok(found) => found,
+            Err(e) => {
+                self.send_err(e);
+                vec![]
+            },
+        };
+        // Github assets can only be fetched by id alone with GraphQL
+        if let Some(gh) = client.as_github() {
+            let missing = assets
+                .iter()
+                .filter(|(_, asset)| !found.iter().any(|v| v.id == VersionId::Github(*asset)))
+                .map(|((owner, repo), asset)| ((owner.as_str(), repo.as_str()), *asset))
+                .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                found.extend(github::fetch_assets(gh, &missing));
+            }
+        }
+
+        for v in found {
+            let Some(paths) = versions.get(&v.id) else {
+                continue;
+            };
+            for path in paths {
+                // Versions may contain several files, such as modpacks
+                if path.file_name() == v.filename.file_name() {
+                    let entry = expected.entry(path.clone()).or_default();
+                    entry.sha1.clone_from(&v.sha1);
+                    entry.version = Some(v.clone());
+                }
+            }
+        }
+    }
+
+    /// Download the file at `path` again. Returns `true` if successful
+    async fn redownload(&self, client: &Client, path: &Path, expected: Expected) -> bool {
+        let mut version = match (expected.version, expected.locked) {
+            (Some(v), _) => v,
+            (None, Some((project, version))) => {
+                let mut pending = VersionSet::new();
+                let failed = self
+                    .fetch_versions(
+                        client,
+                        &ProfileData::default(),
+                        HashSet::new(),
+                        HashMap::from([(project.into(), version.into())]),
+                        &mut pending,
+                    )
+                    .await;
+                for e in failed {
+                    self.send_err(e);
+                }
+                match pending.into_iter().next() {
+                    Some(v) => v.into_inner(),
+                    None => return false,
+                }
+            },
+            (None, None) => {
+                self.send_err(
+                    anyhow!(
+                        "Unknown version of corrupted file `{}`, it can't be downloaded again",
+                        path.display()
+                    )
+                    .into(),
+                );
+                return false;
+            },
+        };
+        client.restrict_local(&mut version);
+        self.download(&version, path).await.is_some()
+    }
+}
+
+/// What is known about a cached file from its path alone
+enum Identity {
+    Sha1(String),
+    /// Every version the file could belong to, since numeric ids are valid
+    /// for both CurseForge and Modrinth
+    Versions(Vec<(ProjectId, VersionId)>),
+    Unknown,
+}
+
+/// Identify a cached file from its path, stored as
+/// `<sub>/<project>/<version>/<file>`
+fn identify(path: &Path) -> Identity {
+    let Ok(rel) = path.strip_prefix(&*CACHE_DIR) else {
+        return Identity::Unknown;
+    };
+    let parts = rel
+        .components()
+        .map(|c| match c {
+            Component::Normal(p) => p.to_str(),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_default();
+    match parts[..] {
+        // Url versions are named by their sha1 when known
+        [_, "url", _, sha1, _] if sha1.len() == 40 && sha1.bytes().all(|b| b.is_ascii_hexdigit()) => Identity::Sha1(sha1.to_owned()),
+        [_, "url" | "local" | "maven", ..] => Identity::Unknown,
+        [_, project, version, _] => {
+            let modrinth = (ProjectId::Modrinth(project.to_owned()), VersionId::Modrinth(version.to_owned()));
+            match (project.parse(), version.parse()) {
+                (Ok(project), Ok(version)) => Identity::Versions(vec![(ProjectId::Forge(project), VersionId::Forge(version)), modrinth]),
+                _ => Identity::Versions(vec![modrinth]),
+            }
+        },
+        [_, owner, repo, asset, _] => match asset.parse::<u64>() {
+            Ok(asset) => Identity::Versions(vec![(
+                ProjectId::Github((owner.to_owned(), repo.to_owned())),
+                VersionId::Github(AssetId::from(asset)),
+            )]),
+            Err(_) => Identity::Unknown,
+        },
+        _ => Identity::Unknown,
+    }
+}
+
+/// Quarantine or delete the corrupt file at `path` according to `action`
+async fn remove_corrupt(path: &Path, action: CorruptAction) -> Result<()> {
+    match action {
+        CorruptAction::Report => {},
+        CorruptAction::Quarantine => {
+            let rel = path.strip_prefix(&*CACHE_DIR).unwrap_or(path);
+            let dest = CACHE_DIR.join(QUARANTINE).join(rel);
+            if let Some(parent) = dest.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::rename(path, &dest)
+                .await
+                .with_context(|| format!("Failed to quarantine corrupted file `{}`", path.display()))?;
+        },
+        CorruptAction::Delete => {
+            tokio::fs::remove_file(path)
+                .await
+                .with_context(|| format!("Failed to delete corrupted file `{}`", path.display()))?;
+        },
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn identify_paths() {
+        let id = |p: &str| match identify(&CACHE_DIR.join(p)) {
+            Identity::Sha1(sha1) => format!("sha1 {sha1}"),
+            Identity::Versions(versions) => versions.iter().map(|(p, v)| format!("{p:?} {v:?}")).join(", "),
+            Identity::Unknown => "unknown".to_owned(),
+        };
+        assert_eq!(id("mods/AANobbMI/abc123/sodium.jar"), r#"Modrinth("AANobbMI") Modrinth("abc123")"#);
+        assert_eq!(
+            id("mods/238222/4712345/jei.jar"),
+            r#"Forge(238222) Forge(4712345), Modrinth("238222") Modrinth("4712345")"#
+        );
+        assert_eq!(id("mods/12345678/87654321/mod.jar").matches("Modrinth").count(), 2);
+        assert_eq!(id("mods/owner/repo/42/mod.jar"), r#"Github(("owner", "repo")) Github(AssetId(42))"#);
+        let sha1 = "e73229d4c2b37acabea9583198b4642b6ea49c1f";
+        assert_eq!(id(&format!("mods/url/0123/{sha1}/mod.jar")), format!("sha1 {sha1}"));
+        assert_eq!(id("mods/url/0123/any/mod.jar"), "unknown");
+        assert_eq!(id("mods/local/0123/mods/cool.jar/cool.jar"), "unknown");
+    }
+
+    #[tokio::test]
+    async fn reinstall_copies() {
+        use sha1::{Digest, Sha1};
+
+        let dir = tempfile::tempdir().unwrap();
+        let cached = dir.path().join("cached.jar");
+        std::fs::write(&cached, "repaired").unwrap();
+        let profile = Profile::with_data(
+            "test".to_owned(),
+            crate::checked_types::PathAbsolute::new(dir.path().join("profile")).unwrap(),
+            ProfileData::default(),
+        );
+        std::fs::create_dir_all(profile.path().join("mods")).unwrap();
+        std::fs::write(profile.path().join("mods/corrupt.jar"), "corrupt").unwrap();
+
+        let sha1 = format!("{:x}", Sha1::digest("repaired"));
+        let copies = ["mods/corrupt.jar", "mods/missing.jar"]
+            .map(|f| (&profile, PathScoped::new(f).unwrap(), sha1.clone()))
+            .into();
+        ProfileManager::new().fix_copies(&cached, copies, true).await;
+        assert_eq!(
+            std::fs::read_to_string(profile.path().join("mods/corrupt.jar")).unwrap(),
+            "repaired"
+        );
+        // Copies that were never installed are left alone
+        assert!(!profile.path().join("mods/missing.jar").exists());
+    }
+}
@@ -1,14 +1,27 @@
-use std::{borrow::Cow, convert::identity, path::Path};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    convert::identity,
+    path::Path,
+    sync::{Arc, LazyLock, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
 
 use anyhow::{Context, anyhow};
+use reqwest::{StatusCode, header};
 use sha1::{Digest, Sha1};
-use tokio::{fs::File, io::AsyncWriteExt};
+use tokio::{
+    fs::{File, OpenOptions},
+    io::{AsyncReadExt, AsyncWriteExt},
+    sync::Semaphore,
+};
 use url::Url;
 
 use crate::{
     ErrorKind, Result,
-    client::schema::Version,
-    hash::{hex_decode, verify_sha1},
+    client::schema::{Version, VersionId},
+    config::DownloadSettings,
+    hash::{hex_decode, sha1_file, verify_sha1},
     mgmt::{
         ProfileManager,
         events::{DownloadId, DownloadProgress, EventSouce},
@@ -19,18 +32,53 @@ pub trait Downloadable: Sync {
     /// A unique id for identifying this download
     fn id(&self) -> DownloadId;
     fn download_url(&self) -> Option<&Url>;
+    /// Other urls the same file can be downloaded from, in order of preference
+    fn mirrors(&self) -> &[Url] {
+        &[]
+    }
+    /// Every url this can be downloaded from, in order of preference
+    fn download_urls(&self) -> Vec<&Url> {
+        self.download_url().into_iter().chain(self.mirrors()).collect()
+    }
     fn title(&self) -> Cow<'_, str>;
     fn length(&self) -> u64;
     fn sha1(&self) -> Option<&str>;
+    /// The exact size the downloaded file must be, if it should be checked
+    fn expected_length(&self) -> Option<u64> {
+        None
+    }
+    /// Whether this is a file from the [local](crate::client::LocalClient)
+    /// root. Only these are read from `file` urls, which are ignored for
+    /// anything else
+    fn is_local(&self) -> bool {
+        false
+    }
 }
 
 
 impl ProfileManager {
+    /// Use `settings` for every download made by this manager and any clones
+    /// made from it afterwards, sending every request with `http`. This should
+    /// be made by [`DownloadSettings::http_client`] so the proxy and CA
+    /// certificates are used, and can be shared with the
+    /// [clients](crate::client) that download files, such as a
+    /// [`MavenClient`](crate::client::MavenClient)
+    pub fn configure_downloads(&mut self, settings: &DownloadSettings, http: reqwest::Client) {
+        self.transfers = Transfers::new(settings, http);
+    }
+
+    /// Limits how many files are hashed at once
+    pub(in crate::mgmt) fn hash_jobs(&self) -> &Semaphore {
+        &self.transfers.hashing
+    }
+
+    /// The http client shared by every download
+    pub(in crate::mgmt) fn http(&self) -> &reqwest::Client {
+        &self.transfers.client
+    }
+
     pub(in crate::mgmt) async fn download(&self, dl: &dyn Downloadable, save_path: &Path) -> Option<String> {
-        // Limit number of concurrent downloads.
-        // Should this be configurable? Maybe via an environment variable
-        static PERMITS: tokio::sync::Semaphore = tokio::sync::Semaphore::const_new(10);
-        let _permit = PERMITS.acquire().await.unwrap();
+        let _permit = self.transfers.permits.acquire().await;
 
         let id = dl.id();
         let title = dl.title();
@@ -47,29 +95,78 @@ impl ProfileManager {
             self.send(DownloadProgress::Success(id).into());
             return sha1.map(Into::into);
         }
+        // Local files are still available offline
+        if self.offline && !dl.is_local() {
+            // Files with a known hash were already checked above unless forced
+            let existing = match sha1 {
+                None if save_path.is_file() => sha1_file(save_path).await.ok(),
+                Some(sha1) if self.force && verify_sha1(sha1, save_path).await.is_ok_and(identity) => Some(sha1.to_owned()),
+                _ => None,
+            };
+            match existing {
+                Some(_) => self.send(DownloadProgress::Success(id).into()),
+                None => self.send(DownloadProgress::Fail(id, ErrorKind::Offline.into()).into()),
+            }
+            return existing;
+        }
 
-        if let Some(url) = dl.download_url() {
+        let urls = self.candidate_urls(dl);
+        if urls.is_empty() {
+            self.send(DownloadProgress::Fail(id, ErrorKind::DistributionDenied(title.into_owned()).into()).into());
+            return None;
+        }
+        let mut error = None;
+        for url in urls {
+            if error.is_some() {
+                self.send(DownloadProgress::Retry(id).into());
+            }
             match self
-                .dl_verified(id, save_path, sha1, url.clone())
+                .dl_verified(id, save_path, sha1, dl.expected_length(), url.clone())
                 .await
                 .with_context(|| ErrorKind::DownloadFailed(url.clone()))
             {
                 Ok(sha1) => {
+                    self.hosts.succeeded(&url);
                     self.send(DownloadProgress::Success(id).into());
-                    Some(sha1)
+                    return Some(sha1);
                 },
                 Err(e) => {
-                    self.send(DownloadProgress::Fail(id, e.into()).into());
-                    None
+                    self.hosts.failed(&url);
+                    error = Some(e);
                 },
             }
-        } else {
-            self.send(DownloadProgress::Fail(id, ErrorKind::DistributionDenied(title.into_owned()).into()).into());
-            None
         }
+        if let Some(e) = error {
+            self.send(DownloadProgress::Fail(id, e.into()).into());
+        }
+        None
     }
 
-    async fn dl_verified(&self, dlid: DownloadId, out_path: &Path, sha1: Option<&str>, url: Url) -> Result<String> {
+    /// The urls to try downloading `dl` from in order. Urls rewritten by the
+    /// configured [mirrors](Self::mirrors) come before the original, and hosts
+    /// that recently failed are tried last
+    fn candidate_urls(&self, dl: &dyn Downloadable) -> Vec<Url> {
+        if dl.is_local() {
+            return dl
+                .download_url()
+                .filter(|url| url.scheme() == "file")
+                .cloned()
+                .into_iter()
+                .collect();
+        }
+        let mut urls = Vec::<Url>::new();
+        for url in dl.download_urls() {
+            for url in self.mirrors.iter().filter_map(|m| m.rewrite(url)).chain([url.clone()]) {
+                if url.scheme() != "file" && !urls.contains(&url) {
+                    urls.push(url);
+                }
+            }
+        }
+        self.hosts.sort(&mut urls);
+        urls
+    }
+
+    async fn dl_verified(&self, dlid: DownloadId, out_path: &Path, sha1: Option<&str>, length: Option<u64>, url: Url) -> Result<String> {
         if let Some(parent) = out_path.parent() {
             tokio::fs::create_dir_all(parent).await?;
         }
@@ -79,15 +176,49 @@ impl ProfileManager {
             tmp.as_mut_os_string().push(".part");
             tmp
         };
-        let mut resp = reqwest::get(url).await?;
-        let mut file = File::create(&temp_path).await?;
         let mut hasher = Sha1::new();
-        while let Some(chunk) = resp.chunk().await? {
-            file.write_all(&chunk).await?;
+        let mut size = 0;
+        let (mut file, resp) = match self.resume(dlid, &temp_path, length, &url, &mut hasher).await? {
+            Resume::Partial(file, resp) => {
+                size = file.metadata().await?.len();
+                (file, Some(resp))
+            },
+            Resume::Full(resp) => (File::create(&temp_path).await?, resp),
+        };
+        let mut write = async |chunk: &[u8]| -> Result<()> {
+            file.write_all(chunk).await?;
+            size += chunk.len() as u64;
             self.send(DownloadProgress::Progress(dlid, chunk.len() as _).into());
-            hasher.update(&chunk);
+            hasher.update(chunk);
+            Ok(())
+        };
+        if url.scheme() == "file" {
+            let path = url.to_file_path().map_err(|()| ErrorKind::InvalidUrl(url.to_string()))?;
+            let mut src = File::open(path).await?;
+            let mut buf = vec![0; 64 * 1024];
+            loop {
+                let read = src.read(&mut buf).await?;
+                if read == 0 {
+                    break;
+                }
+                write(&buf[..read]).await?;
+            }
+        } else {
+            let mut resp = match resp {
+                Some(resp) => resp,
+                None => self.http().get(url).send().await?.error_for_status()?,
+            };
+            while let Some(chunk) = resp.chunk().await? {
+                self.transfers.throttle(chunk.len() as u64).await;
+                write(&chunk).await?;
+            }
         }
         file.flush().await?;
+        if let Some(length) = length.filter(|&l| l != size) {
+            // Don't resume from a bad file
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(anyhow!("Incorrect size for downloaded file:\n\tExpected: {length}\n\t  Actual: {size}").into());
+        }
 
         let computed = hasher.finalize();
         let sha_bytes = sha1.and_then(|s| hex_decode(s).ok());
@@ -95,6 +226,7 @@ impl ProfileManager {
             tokio::fs::rename(temp_path, out_path).await?;
             Ok(format!("{computed:x}"))
         } else {
+            let _ = tokio::fs::remove_file(&temp_path).await;
             Err(anyhow!(
                 "Incorrect hash for downloaded file:\n\tExpected: {}\n\t  Actual: {computed:x}",
                 sha1.expect("sha1 should not be none")
@@ -104,6 +236,168 @@ impl ProfileManager {
     }
 }
 
+/// The http client and limits shared by every download and hashed file of a
+/// [`ProfileManager`] and its clones
+#[derive(Debug, Clone)]
+pub(super) struct Transfers {
+    client: reqwest::Client,
+    permits: Arc<Semaphore>,
+    hashing: Arc<Semaphore>,
+    bandwidth: Option<Arc<Bandwidth>>,
+}
+
+static DEFAULT_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .user_agent(concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .unwrap_or_default()
+});
+
+impl Default for Transfers {
+    fn default() -> Self {
+        Self {
+            client: DEFAULT_CLIENT.clone(),
+            permits: Arc::new(Semaphore::new(DownloadSettings::DEFAULT_CONCURRENT)),
+            hashing: Arc::new(Semaphore::new(DownloadSettings::DEFAULT_HASHING)),
+            bandwidth: None,
+        }
+    }
+}
+
+impl Transfers {
+    fn new(settings: &DownloadSettings, client: reqwest::Client) -> Self {
+        Self {
+            client,
+            permits: Arc::new(Semaphore::new(settings.max_concurrent.max(1))),
+            hashing: Arc::new(Semaphore::new(settings.max_hashing.max(1))),
+            bandwidth: settings
+                .max_speed
+                .filter(|&speed| speed > 0)
+                .map(|speed| Arc::new(Bandwidth::new(speed))),
+        }
+    }
+
+    /// Wait until `bytes` more can be downloaded without going over the
+    /// [speed limit](DownloadSettings::max_speed)
+    async fn throttle(&self, bytes: u64) {
+        if let Some(bandwidth) = &self.bandwidth {
+            let wait = bandwidth.reserve(bytes, Instant::now());
+            if !wait.is_zero() {
+                tokio::time::sleep(wait).await;
+            }
+        }
+    }
+}
+
+/// Spaces out the chunks of every download so their combined speed stays under
+/// a limit
+#[derive(Debug)]
+struct Bandwidth {
+    bytes_per_sec: u64,
+    /// When the bandwidth reserved so far has been used up
+    next: Mutex<Instant>,
+}
+
+impl Bandwidth {
+    fn new(bytes_per_sec: u64) -> Self {
+        Self {
+            bytes_per_sec,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Reserve the time needed to download `bytes` after everything reserved
+    /// before it, returning how long to wait from `now` until it starts
+    fn reserve(&self, bytes: u64, now: Instant) -> Duration {
+        let mut next = self.next.lock().unwrap_or_else(PoisonError::into_inner);
+        let start = (*next).max(now);
+        let nanos = u128::from(bytes) * 1_000_000_000 / u128::from(self.bytes_per_sec);
+        *next = start + Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX));
+        start - now
+    }
+}
+
+/// The number of consecutive failed downloads from each host, shared by every
+/// clone of a [`ProfileManager`]
+#[derive(Debug, Clone, Default)]
+pub(super) struct HostHealth(Arc<Mutex<HashMap<String, u32>>>);
+
+impl HostHealth {
+    fn host(url: &Url) -> String {
+        url.host_str().unwrap_or_default().to_owned()
+    }
+
+    fn failures(&self) -> std::sync::MutexGuard<'_, HashMap<String, u32>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn succeeded(&self, url: &Url) {
+        self.failures().remove(&Self::host(url));
+    }
+
+    fn failed(&self, url: &Url) {
+        *self.failures().entry(Self::host(url)).or_default() += 1;
+    }
+
+    /// Stable sort of `urls` with the hosts that failed the least first
+    fn sort(&self, urls: &mut [Url]) {
+        let failures = self.failures();
+        urls.sort_by_key(|url| failures.get(&Self::host(url)).copied().unwrap_or_default());
+    }
+}
+
+/// How a download should continue from an existing `.part` file
+enum Resume {
+    /// The rest of the file is being sent in the response
+    Partial(File, reqwest::Response),
+    /// The full file must be downloaded, possibly with a response already
+    /// sent for it
+    Full(Option<reqwest::Response>),
+}
+
+impl ProfileManager {
+    /// Request the rest of a previous partial download of `url` saved at
+    /// `part`, hashing the part already downloaded into `hasher`. Falls back to
+    /// a full download when there is nothing to resume or the server ignores
+    /// the requested range
+    async fn resume(&self, dlid: DownloadId, part: &Path, length: Option<u64>, url: &Url, hasher: &mut Sha1) -> Result<Resume> {
+        let existing = tokio::fs::metadata(part).await.map_or(0, |m| m.len());
+        if url.scheme() == "file" || existing == 0 || length.is_some_and(|l| existing >= l) {
+            return Ok(Resume::Full(None));
+        }
+
+        let resp = self
+            .http()
+            .get(url.clone())
+            .header(header::RANGE, format!("bytes={existing}-"))
+            .send()
+            .await?;
+        let start = resp
+            .headers()
+            .get(header::CONTENT_RANGE)
+            .and_then(|range| range.to_str().ok())
+            .and_then(|range| range.strip_prefix("bytes "))
+            .and_then(|range| range.split_once('-'))
+            .and_then(|(start, _)| start.parse::<u64>().ok());
+        if resp.status() != StatusCode::PARTIAL_CONTENT || start != Some(existing) {
+            // Range was ignored, so the response is the full file
+            return Ok(Resume::Full(resp.status().is_success().then_some(resp)));
+        }
+
+        let mut file = OpenOptions::new().read(true).append(true).open(part).await?;
+        let mut buf = vec![0; 64 * 1024];
+        loop {
+            let read = file.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buf[..read]);
+        }
+        self.send(DownloadProgress::Progress(dlid, existing as _).into());
+        Ok(Resume::Partial(file, resp))
+    }
+}
+
 impl Downloadable for Version {
     fn id(&self) -> DownloadId {
         (&self.project_id).into()
@@ -113,6 +407,10 @@ impl Downloadable for Version {
         self.download_url.as_ref()
     }
 
+    fn mirrors(&self) -> &[Url] {
+        &self.mirrors
+    }
+
     fn title(&self) -> Cow<'_, str> {
         self.title.as_str().into()
     }
@@ -124,4 +422,53 @@ impl Downloadable for Version {
     fn sha1(&self) -> Option<&str> {
         self.sha1.as_deref()
     }
+
+    fn expected_length(&self) -> Option<u64> {
+        match &self.id {
+            VersionId::Url(expected) => expected.size,
+            _ => None,
+        }
+    }
+
+    fn is_local(&self) -> bool {
+        matches!(self.id, VersionId::Local(_))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bandwidth_reserve() {
+        let now = Instant::now();
+        let bandwidth = Bandwidth {
+            bytes_per_sec: 1000,
+            next: Mutex::new(now),
+        };
+        assert_eq!(bandwidth.reserve(500, now), Duration::ZERO);
+        assert_eq!(bandwidth.reserve(1000, now), Duration::from_millis(500));
+        assert_eq!(bandwidth.reserve(100, now + Duration::from_secs(1)), Duration::from_millis(500));
+        // Unused bandwidth isn't saved up for later
+        assert_eq!(bandwidth.reserve(100, now + Duration::from_secs(5)), Duration::ZERO);
+    }
+
+    #[test]
+    fn hashing_limit_per_manager() {
+        let configure = |max_hashing| {
+            let mut manager = ProfileManager::new();
+            let settings = DownloadSettings {
+                max_hashing,
+                ..Default::default()
+            };
+            manager.configure_downloads(&settings, reqwest::Client::new());
+            manager
+        };
+        let first = configure(2);
+        let second = configure(5);
+        assert_eq!(first.hash_jobs().available_permits(), 2);
+        assert_eq!(second.hash_jobs().available_permits(), 5);
+        assert_eq!(first.clone().hash_jobs().available_permits(), 2);
+    }
 }
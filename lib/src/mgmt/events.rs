@@ -13,22 +13,39 @@ pub(super) trait EventSouce {
 pub enum ProgressEvent {
     Status(String),
     Download(DownloadProgress),
-    Installed { file: PathScoped, is_new: bool, typ: InstallType },
+    Installed {
+        file: PathScoped,
+        is_new: bool,
+        typ: InstallType,
+    },
     Deleted(PathScoped),
+    /// A version made for other game versions than the profile's was chosen
+    /// because of the profile's [policy](crate::config::GameVersionPolicy)
+    RelaxedMatch {
+        title: String,
+        game_versions: Vec<String>,
+    },
     Error(crate::Error),
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum InstallType {
     Mod,
+    Dependency,
     Override,
     Other,
 }
 
 #[derive(Debug)]
 pub enum DownloadProgress {
-    Start { project: DownloadId, title: String, length: u64 },
+    Start {
+        project: DownloadId,
+        title: String,
+        length: u64,
+    },
     Progress(DownloadId, u64),
+    /// The download failed and is starting over from the next url
+    Retry(DownloadId),
     Success(DownloadId),
     Fail(DownloadId, crate::Error),
 }
@@ -5,19 +5,21 @@ use std::{
 
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
+use url::Url;
 
 use crate::{
     Result, StdResult,
     checked_types::PathScoped,
-    client::schema::{self, ProjectId, VersionId},
+    client::schema::{self, ProjectId, ReleaseChannel, VersionId},
     config::{ModLoader, Profile, ProjectWithVersion, VersionedProject, profile},
     fs_util::{FsUtil, FsUtils},
+    mgmt::events::InstallType,
 };
 
 crate::cow::cow!(LockedMod);
 
 pub type PathHashes = BTreeMap<PathScoped, String>;
-const FILENAME: &str = concat!(profile::consts!(FILENAME), ".lock");
+pub(super) const FILENAME: &str = concat!(profile::consts!(FILENAME), ".lock");
 
 fn cmp_files(a: &LockedMod, b: &LockedMod) -> std::cmp::Ordering {
     a.file.cmp(&b.file)
@@ -95,6 +97,60 @@ pub struct LockedMod {
     pub id: LockedId,
     pub file: PathScoped,
     pub sha1: String,
+
+    /// The size of the file in bytes, or 0 if unknown
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub size: u64,
+
+    /// Every url the file can be downloaded from, in order of preference
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub urls: Vec<Url>,
+
+    /// The [release channel](ReleaseChannel) of the locked version
+    #[serde(default, skip_serializing_if = "ReleaseChannel::is_release")]
+    pub channel: ReleaseChannel,
+
+    #[serde(default, skip_serializing_if = "ModOrigin::is_direct")]
+    pub origin: ModOrigin,
+
+    /// The identical version from another service that was installed instead,
+    /// because this one can't be downloaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub substitute: Option<LockedId>,
+
+    /// Required dependencies of this version. [`None`] if not yet known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requires: Option<Vec<ProjectWithVersion>>,
+}
+
+/// Why a [`LockedMod`] is installed
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModOrigin {
+    /// Added to the profile directly
+    #[default]
+    Direct,
+    /// Installed by the profile's modpack
+    Pack,
+    /// Installed to satisfy the requirements of another mod
+    Dependency,
+}
+
+impl ModOrigin {
+    #[inline]
+    #[allow(clippy::trivially_copy_pass_by_ref)] // Needed for serde
+    pub fn is_direct(&self) -> bool {
+        matches!(self, Self::Direct)
+    }
+}
+
+impl From<ModOrigin> for InstallType {
+    fn from(origin: ModOrigin) -> Self {
+        match origin {
+            ModOrigin::Direct | ModOrigin::Pack => Self::Mod,
+            ModOrigin::Dependency => Self::Dependency,
+        }
+    }
 }
 
 impl VersionedProject for LockedMod {
@@ -108,18 +164,68 @@ impl VersionedProject for LockedMod {
 }
 
 impl From<schema::Version> for LockedMod {
-    fn from(v: schema::Version) -> Self {
+    fn from(mut v: schema::Version) -> Self {
+        // Lock to the downloaded contents if they weren't already known
+        if let VersionId::Url(expected) = &mut v.id {
+            if expected.sha1.is_none() {
+                expected.sha1.clone_from(&v.sha1);
+                expected.size = Some(v.length).filter(|&len| len > 0);
+            }
+        }
         LockedMod {
+            requires: Some(v.required_deps().cloned().map(Into::into).collect()),
             id: LockedId {
                 project: v.project_id,
                 version: v.id,
             },
             sha1: v.sha1.unwrap_or_default(),
+            size: v.length,
+            urls: v.download_url.into_iter().chain(v.mirrors).collect(),
             file: v.filename,
+            channel: v.channel,
+            origin: ModOrigin::Direct,
+            substitute: None,
+        }
+    }
+}
+
+impl From<&LockedMod> for schema::Version {
+    /// The version that was downloaded for `lm`, which is its
+    /// [substitute](LockedMod::substitute) if it has one
+    fn from(lm: &LockedMod) -> Self {
+        let id = lm.substitute.as_ref().unwrap_or(&lm.id);
+        let mut urls = lm.urls.iter().cloned();
+        Self {
+            id: id.version.clone(),
+            project_id: id.project.clone(),
+            title: lm.file.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+            number: None,
+            download_url: urls.next(),
+            mirrors: urls.collect(),
+            filename: lm.file.clone(),
+            length: lm.size,
+            date: String::new(),
+            channel: lm.channel,
+            sha1: Some(lm.sha1.clone()).filter(|sha1| !sha1.is_empty()),
+            deps: { lm.requires.iter().flatten() }
+                .map(|p| schema::Dependency {
+                    project_id: p.project.clone(),
+                    id: p.version.clone(),
+                    dep_type: schema::DependencyType::Required,
+                })
+                .collect(),
+            game_versions: vec![],
+            loaders: vec![],
         }
     }
 }
 
+#[inline]
+#[allow(clippy::trivially_copy_pass_by_ref)] // Needed for serde
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(try_from = "ProjectWithVersion", into = "ProjectWithVersion")]
 pub struct LockedId {
@@ -265,3 +371,31 @@ where
 
     de.deserialize_seq(ModSetVisitor)
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locked_version_roundtrip() {
+        let lm: LockedMod = serde_json::from_value(serde_json::json!({
+            "modrinth": "AANobbMI",
+            "version": "abc123",
+            "file": "mods/sodium.jar",
+            "sha1": "e73229d4c2b37acabea9583198b4642b6ea49c1f",
+            "size": 1024,
+            "urls": ["https://cdn.example.com/sodium.jar", "https://mirror.example.com/sodium.jar"],
+            "origin": "pack",
+            "requires": [{ "modrinth": "P7dR8mSH" }]
+        }))
+        .unwrap();
+        let v = schema::Version::from(&lm);
+        assert_eq!(v.download_url.as_ref().map(Url::as_str), Some("https://cdn.example.com/sodium.jar"));
+        assert_eq!(v.mirrors.len(), 1);
+
+        let mut relocked = LockedMod::from(v);
+        relocked.origin = lm.origin;
+        assert_eq!(serde_json::to_value(&relocked).unwrap(), serde_json::to_value(&lm).unwrap(),);
+    }
+}
@@ -2,7 +2,11 @@ pub mod forge;
 pub mod modrinth;
 mod version;
 
-use std::{cell::LazyCell, collections::HashMap, path::Path};
+use std::{
+    cell::LazyCell,
+    collections::{HashMap, HashSet},
+    path::Path,
+};
 
 use ::modrinth::{
     apis::version_files_api::VersionsFromHashesParams,
@@ -35,13 +39,14 @@ impl ProfileManager {
         data: &ProfileData,
     ) -> Result<(Version, ModpackData)> {
         let mut pack_version = if let Some(vid) = pack.version() {
-            client.get_version(vid).await?
+            client.get_project_version(pack.project(), vid).await?
         } else {
             client
-                .get_latest(pack.project(), Some(&data.game_version), data.loader.known())
+                .get_latest(pack.project(), Some(&data.game_version), data.loader.known(), data.channel)
                 .await
                 .with_context(|| ErrorKind::MissingVersion(pack.project().clone()))?
         };
+        client.restrict_local(&mut pack_version);
         let cache_path = cache::version_path(&pack_version, PathScopedRef::new("modpacks").ok());
         let Some(sha1) = self.download(&pack_version, &cache_path).await else {
             return Err(anyhow!("Modpack download failed").into());
@@ -138,10 +143,13 @@ impl ProfileManager {
                             id: vid,
                             project_id: pid,
                             title: format!("{pack_name} - {}", path.display()),
+                            number: None,
                             download_url: f.downloads.first().cloned(),
+                            mirrors: f.downloads.iter().skip(1).cloned().collect(),
                             filename: path.to_owned(),
                             length: f.file_size,
                             date: Default::default(),
+                            channel: Default::default(),
                             sha1: Some(f.hashes.sha1.clone()),
                             deps: Default::default(),
                             game_versions: game_version.iter().cloned().collect(),
@@ -150,16 +158,22 @@ impl ProfileManager {
                         .into(),
                     );
                 }
-                let fetched = client
-                    .ok_or(anyhow!("Modrinth modpack found, but no Modrinth client available"))?
-                    .version_files()
-                    .versions_from_hashes(&VersionsFromHashesParams {
-                        hash_list: Some(&HashList {
-                            hashes: pending.iter().map(|f| f.hashes.sha1.clone()).collect(),
-                            algorithm: Algorithm::Sha1,
-                        }),
-                    })
-                    .await?;
+                let fetched = if pending.is_empty() || self.frozen || (self.offline && client.is_none()) {
+                    // Files that can't be identified offline are installed from the index instead,
+                    // and frozen installs identify them from the lockfile
+                    HashMap::new()
+                } else {
+                    client
+                        .ok_or(anyhow!("Modrinth modpack found, but no Modrinth client available"))?
+                        .version_files()
+                        .versions_from_hashes(&VersionsFromHashesParams {
+                            hash_list: Some(&HashList {
+                                hashes: pending.iter().map(|f| f.hashes.sha1.clone()).collect(),
+                                algorithm: Algorithm::Sha1,
+                            }),
+                        })
+                        .await?
+                };
                 let unknown = pending
                     .into_iter()
                     .filter(|f| !fetched.contains_key(&f.hashes.sha1))
@@ -199,6 +213,14 @@ pub struct ModpackData {
 }
 
 impl ModpackData {
+    /// The ids of all known projects included in the pack
+    pub fn project_ids(&self) -> HashSet<ProjectId> {
+        match &self.mods {
+            PackMods::Modrinth { known, .. } => known.iter().map(|v| v.project_id.clone()).collect(),
+            PackMods::Forge(mods) => mods.keys().cloned().collect(),
+        }
+    }
+
     pub fn visit_overrides(&mut self, mut cb: impl FnMut(&PathScopedRef, ZipFile<'_, std::fs::File>)) {
         let zip = &mut self.archive;
         for idx in 0..zip.len() {
@@ -113,6 +113,10 @@ impl Downloadable for IndexFile {
         self.downloads.first()
     }
 
+    fn mirrors(&self) -> &[Url] {
+        self.downloads.get(1..).unwrap_or_default()
+    }
+
     fn title(&self) -> Cow<'_, str> {
         self.path
             .file_name()
@@ -1,3 +1,10 @@
+pub(super) mod changelog;
+mod compat;
+mod denied;
+pub(super) mod deps;
+mod frozen;
 pub(super) mod install;
+pub(super) mod plan;
 pub(super) mod scan;
+mod transaction;
 pub(super) mod update;
@@ -0,0 +1,67 @@
+use super::update::UpdateInfo;
+use crate::{Client, ProfileManager, Result, client::schema::Version, config::Profile, mgmt::events::EventSouce};
+
+/// The changelog of a single [version](Version)
+#[derive(Debug, Clone)]
+pub struct Changelog {
+    /// The version the changelog is for
+    pub version: Version,
+    /// The changelog text as written by the author, typically markdown
+    pub text: String,
+}
+
+impl ProfileManager {
+    /// Get the changelogs of every version after the installed version of
+    /// `update` up to and including the version it updates to, newest first.
+    /// Intermediate versions must be compatible with the `profile` to be
+    /// included. Versions without a changelog are skipped, and a failure to
+    /// fetch the changelogs is sent as an event
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if loading the profile data or listing the project
+    /// versions fails
+    pub async fn changelogs(&self, client: &Client, profile: &Profile, update: &UpdateInfo) -> Result<Vec<Changelog>> {
+        let data = profile.data().await?;
+        let versions = client.get_project_versions(&update.project, None, data.loader.known()).await?;
+        let Some(to) = versions.iter().find(|v| v.id == update.to.0) else {
+            return Ok(vec![]);
+        };
+
+        let (channel, compat) = (data.channel_for(&update.project), data.compat_for(&update.project));
+        let mut pending = match versions.iter().find(|v| v.id == update.from.0) {
+            Some(from) => versions
+                .iter()
+                .filter(|v| {
+                    v.date > from.date
+                        && v.date <= to.date
+                        && v.in_channel(channel)
+                        && (v.game_versions.is_empty() || v.game_versions.iter().any(|gv| compat.accepts(&data.game_version, gv)))
+                })
+                .collect(),
+            // Installed version is unknown, so there's nothing to compare against
+            None => vec![to],
+        };
+        if !pending.iter().any(|v| v.id == to.id) {
+            pending.push(to);
+        }
+
+        let mut texts = match client.get_changelogs(&pending).await {
+            Ok(texts) => texts,
+            Err(e) => {
+                self.send_err(e);
+                return Ok(vec![]);
+            },
+        };
+        let mut changelogs = pending
+            .into_iter()
+            .filter_map(|v| {
+                let text = texts.remove(&v.id)?;
+                Some(Changelog { version: v.clone(), text })
+            })
+            .filter(|c| !c.text.trim().is_empty())
+            .collect::<Vec<_>>();
+        changelogs.sort_by(|a, b| b.version.date.cmp(&a.version.date));
+        Ok(changelogs)
+    }
+}
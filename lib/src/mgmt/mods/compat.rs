@@ -0,0 +1,124 @@
+use crate::{
+    Client, ErrorKind, Result,
+    client::schema::{ProjectId, Version},
+    config::profile::ProfileData,
+    mgmt::{
+        ProfileManager,
+        events::{EventSouce, ProgressEvent},
+    },
+};
+
+impl ProfileManager {
+    /// Get the latest version of the project with `id` that can be installed to
+    /// the profile with `data`. When there isn't one for the exact game
+    /// version, the newest version for any game version allowed by its
+    /// [policy](ProfileData::compat_for) is used instead, and reported to the
+    /// [`channel`](Self::with_channel). Only versions satisfying its
+    /// [constraint](ProfileData::constraint_for) are considered
+    ///
+    /// # Errors
+    ///
+    /// Will return [`Unsatisfied`](ErrorKind::Unsatisfied) if the project has
+    /// a constraint that no compatible version satisfies
+    pub(super) async fn latest_compatible(&self, client: &Client, id: &ProjectId, data: &ProfileData) -> Result<Version> {
+        let (channel, compat, constraint) = (data.channel_for(id), data.compat_for(id), data.constraint_for(id));
+        let err = match constraint {
+            Some(c) => ErrorKind::Unsatisfied(id.clone().into(), c.clone()).into(),
+            None => match client.get_latest(id, Some(&data.game_version), data.loader.known(), channel).await {
+                Err(e) if !compat.is_exact() && matches!(e.kind(), ErrorKind::DoesNotExist | ErrorKind::MissingVersion(_)) => e,
+                res => return res,
+            },
+        };
+
+        // Constrained versions are chosen from every version, so like
+        // `get_latest`, versions for the exact game version are preferred and
+        // versions without any, like local files, can be installed for every
+        // version. Otherwise only versions for other game versions are left
+        let exact = |v: &Version| constraint.is_some() && (v.game_versions.is_empty() || v.game_versions.contains(&data.game_version));
+        // Only other game versions need every version fetched
+        let game_version = Some(data.game_version.as_str()).filter(|_| compat.is_exact());
+        let mut latest = client
+            .get_project_versions(id, game_version, data.loader.known())
+            .await?
+            .into_iter()
+            .filter(|v| exact(v) || v.game_versions.iter().any(|gv| compat.accepts(&data.game_version, gv)))
+            .filter(|v| v.in_channel(channel))
+            .filter(|v| constraint.is_none_or(|c| c.accepts(v)))
+            .max_by(|a, b| (exact(a), &a.date).cmp(&(exact(b), &b.date)))
+            .ok_or(err)?;
+        if !exact(&latest) {
+            latest.game_versions.retain(|gv| compat.accepts(&data.game_version, gv));
+            self.send(ProgressEvent::RelaxedMatch {
+                title: latest.title.clone(),
+                game_versions: latest.game_versions.clone(),
+            });
+        }
+        Ok(latest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        collections::{HashMap, HashSet},
+        sync::mpsc,
+    };
+
+    use super::*;
+    use crate::{
+        config::{Mod, ModLoader},
+        mgmt::version::VersionSet,
+    };
+
+    fn profile(id: &ProjectId, constraint: &str) -> ProfileData {
+        ProfileData {
+            game_version: "1.21.1".to_owned(),
+            loader: ModLoader::Fabric,
+            mods: vec![Mod {
+                constraint: Some(constraint.parse().unwrap()),
+                ..Mod::stub(id.clone(), "a")
+            }],
+            ..ProfileData::default()
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn constrained_latest() {
+        let dir = tempfile::tempdir().unwrap();
+        let (old, new) = (
+            Version {
+                number: Some("v1.0.0".to_owned()),
+                date: "2024-01-01T00:00:00Z".to_owned(),
+                ..Version::stub("a", "a1")
+            },
+            Version {
+                number: Some("v2.0.0".to_owned()),
+                date: "2024-06-01T00:00:00Z".to_owned(),
+                ..Version::stub("a", "a2")
+            },
+        );
+        let client = Client::offline_stub(dir.path(), &[old.clone(), new]).await;
+        let (sender, receiver) = mpsc::channel();
+        let manager = ProfileManager::with_channel(sender);
+        let id = old.project_id.clone();
+
+        let latest = manager.latest_compatible(&client, &id, &profile(&id, "^1.0")).await.unwrap();
+        assert_eq!(latest.id, old.id);
+        // Versions for the exact game version aren't reported as relaxed matches
+        assert!(receiver.try_iter().next().is_none());
+
+        // Unsatisfied constraints fail resolving the mod
+        let data = profile(&id, "^3");
+        let failed = manager
+            .fetch_versions(
+                &client,
+                &data,
+                HashSet::from([Cow::Borrowed(&id)]),
+                HashMap::new(),
+                &mut VersionSet::new(),
+            )
+            .await;
+        assert!(matches!(failed.as_slice(), [e] if matches!(e.kind(), ErrorKind::Unsatisfied(..))));
+    }
+}
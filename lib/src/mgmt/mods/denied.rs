@@ -0,0 +1,239 @@
+use std::{collections::HashMap, convert::identity, mem::replace, path::Path};
+
+use tokio::sync::Semaphore;
+
+use super::install::install_path;
+use crate::{
+    Client, ErrorKind,
+    checked_types::PathAbsolute,
+    client::{
+        modrinth,
+        schema::{ProjectId, Version},
+    },
+    hash::{sha1_file, verify_sha1},
+    mgmt::{
+        ProfileManager,
+        events::{EventSouce, ProgressEvent},
+        lockfile::LockedMod,
+        version::VersionSet,
+    },
+};
+
+const FORGE_PROJECT_URL: &str = "https://www.curseforge.com/projects/";
+
+/// Versions that can't be downloaded, by the project id of the identical
+/// version installed in their place
+pub(super) type Substitutes = HashMap<ProjectId, Version>;
+
+impl ProfileManager {
+    /// Replace any `pending` versions that can't be downloaded with an
+    /// identical file from another service, found by its sha1. Versions
+    /// without a replacement are removed, and returned as installed if the
+    /// file was already added to the profile manually, identified by its sha1
+    /// or its CurseForge fingerprint when the sha1 isn't known. Otherwise,
+    /// instructions for downloading it manually are sent to the
+    /// [`channel`](Self::with_channel)
+    pub(super) async fn substitute_denied(
+        &self,
+        client: &Client,
+        pending: &mut VersionSet,
+        profile_path: &PathAbsolute,
+    ) -> (Substitutes, Vec<LockedMod>) {
+        let denied = pending
+            .iter()
+            .filter(|v| v.download_url.is_none())
+            .map(|v| (**v).clone())
+            .collect::<Vec<_>>();
+        if denied.is_empty() {
+            return Default::default();
+        }
+        for v in &denied {
+            pending.remove(&v.project_id);
+        }
+
+        self.send(ProgressEvent::Status("Searching for files that can't be downloaded...".into()));
+        let hashes = denied.iter().filter_map(|v| v.sha1.clone()).collect::<Vec<_>>();
+        let found = match client.as_modrinth() {
+            Some(mr) if !hashes.is_empty() => modrinth::versions_from_sha1(mr, hashes).await.unwrap_or_else(|e| {
+                self.send_err(e);
+                HashMap::new()
+            }),
+            _ => HashMap::new(),
+        };
+        self.substitute_found(client, denied, found, pending, profile_path).await
+    }
+
+    /// Replace each `denied` version with the version in `found` by its sha1,
+    /// or check for a manually downloaded file
+    async fn substitute_found(
+        &self,
+        client: &Client,
+        denied: Vec<Version>,
+        mut found: HashMap<String, Version>,
+        pending: &mut VersionSet,
+        profile_path: &PathAbsolute,
+    ) -> (Substitutes, Vec<LockedMod>) {
+        let mut substitutes = Substitutes::new();
+        let mut manual = vec![];
+        for v in denied {
+            let sub = v
+                .sha1
+                .as_ref()
+                .and_then(|sha1| found.remove(sha1))
+                .filter(|sub| !pending.contains(&sub.project_id));
+            if let Some(sub) = sub {
+                self.send(ProgressEvent::Status(format!(
+                    "Installing `{}` from Modrinth instead of CurseForge",
+                    v.title
+                )));
+                substitutes.insert(sub.project_id.clone(), v);
+                pending.insert(sub.into());
+                continue;
+            }
+
+            let file = install_path(v.filename.clone());
+            let path = profile_path.join(&file);
+            let sha1 = match &v.sha1 {
+                Some(sha1) => verify_sha1(sha1, &path).await.is_ok_and(identity).then(|| sha1.clone()),
+                None => fingerprint_match(client, self.hash_jobs(), &v, &path).await,
+            };
+            if let Some(sha1) = sha1 {
+                manual.push(LockedMod { file, sha1, ..v.into() });
+                continue;
+            }
+            self.send_err(
+                match &v.project_id {
+                    ProjectId::Forge(id) => ErrorKind::ManualDownload {
+                        page: format!("{FORGE_PROJECT_URL}{id}"),
+                        title: v.title,
+                        path: path.into(),
+                    },
+                    _ => ErrorKind::DistributionDenied(v.title),
+                }
+                .into(),
+            );
+        }
+        (substitutes, manual)
+    }
+}
+
+/// Look up the file at `path` by its CurseForge fingerprint. Returns its sha1
+/// if it is the file of `version`
+async fn fingerprint_match(client: &Client, jobs: &Semaphore, version: &Version, path: &Path) -> Option<String> {
+    let forge = client.as_forge().filter(|_| path.is_file())?;
+    let mut found = HashMap::new();
+    let errors = Client::from(forge.clone()).lookup(&[path], jobs, &mut found).await.ok()?;
+    if !errors.is_empty() || found.remove(path).is_none_or(|v| v.id != version.id) {
+        return None;
+    }
+    sha1_file(path).await.ok()
+}
+
+/// Lock `lm` as the version it was installed in place of, if any
+pub(super) fn as_substitute(mut lm: LockedMod, substitutes: &mut Substitutes) -> LockedMod {
+    if let Some(original) = substitutes.remove(&lm.id.project) {
+        let original = LockedMod::from(original);
+        lm.substitute = Some(replace(&mut lm.id, original.id));
+        lm.requires = original.requires;
+    }
+    lm
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use url::Url;
+
+    use super::*;
+    use crate::client::{DirectClient, schema::VersionId};
+
+    fn denied(project: u64, sha1: Option<&str>) -> Version {
+        Version {
+            id: VersionId::Forge(project * 10),
+            project_id: ProjectId::Forge(project),
+            sha1: sha1.map(str::to_owned),
+            ..Version::stub("", &format!("cf{project}"))
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn substitute_by_sha1() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile_path = PathAbsolute::new(dir.path()).unwrap();
+        let sub = Version {
+            download_url: Some(Url::parse("https://cdn.modrinth.com/data/AANobbMI/versions/abc/abc.jar").unwrap()),
+            ..Version::stub("AANobbMI", "abc")
+        };
+        let found = HashMap::from([("a".repeat(40), sub.clone())]);
+
+        let mut pending = VersionSet::new();
+        let (mut substitutes, manual) = ProfileManager::new()
+            .substitute_found(
+                &DirectClient.into(),
+                vec![denied(1, Some(&"a".repeat(40)))],
+                found,
+                &mut pending,
+                &profile_path,
+            )
+            .await;
+
+        assert!(manual.is_empty());
+        assert!(pending.contains(&sub.project_id));
+        assert_eq!(substitutes[&sub.project_id].project_id, ProjectId::Forge(1));
+
+        // Locked as the denied version
+        let lm = as_substitute(sub.into(), &mut substitutes);
+        assert_eq!(lm.id.project, ProjectId::Forge(1));
+        assert_eq!(lm.id.version, VersionId::Forge(10));
+        assert_eq!(lm.substitute.unwrap().project, ProjectId::Modrinth("AANobbMI".to_owned()));
+        assert!(substitutes.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn manual_downloads() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile_path = PathAbsolute::new(dir.path()).unwrap();
+        std::fs::create_dir(dir.path().join("mods")).unwrap();
+        std::fs::write(dir.path().join("mods/cf1.jar"), b"manual").unwrap();
+        let sha1 = sha1_file(&dir.path().join("mods/cf1.jar")).await.unwrap();
+
+        let downloadable = Version {
+            download_url: Some(Url::parse("https://cdn.modrinth.com/data/AANobbMI/versions/abc/abc.jar").unwrap()),
+            ..Version::stub("AANobbMI", "abc")
+        };
+        let mut pending = VersionSet::new();
+        pending.insert(downloadable.clone().into());
+        pending.insert(denied(1, Some(&sha1)).into());
+        pending.insert(denied(2, Some(&"a".repeat(40))).into());
+        // Without a sha1 or a CurseForge client, the file can't be identified
+        std::fs::write(dir.path().join("mods/cf3.jar"), b"unknown").unwrap();
+        pending.insert(denied(3, None).into());
+
+        let (tx, rx) = mpsc::channel();
+        let (substitutes, manual) = ProfileManager::with_channel(tx)
+            .substitute_denied(&DirectClient.into(), &mut pending, &profile_path)
+            .await;
+
+        assert!(substitutes.is_empty());
+        assert_eq!(pending.len(), 1);
+        assert!(pending.contains(&downloadable.project_id));
+        assert_eq!(manual.len(), 1);
+        assert_eq!(manual[0].id.project, ProjectId::Forge(1));
+        assert_eq!(manual[0].sha1, sha1);
+
+        let mut pages = rx
+            .try_iter()
+            .filter_map(|e| match e {
+                ProgressEvent::Error(e) => match e.kind() {
+                    ErrorKind::ManualDownload { page, .. } => Some(page.clone()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        pages.sort();
+        assert_eq!(pages, [format!("{FORGE_PROJECT_URL}2"), format!("{FORGE_PROJECT_URL}3")]);
+    }
+}
@@ -0,0 +1,374 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashMap, HashSet},
+    convert::identity,
+};
+
+use anyhow::anyhow;
+use async_scoped::TokioScope;
+
+use super::install::ResolvedMods;
+use crate::{
+    Client, ProfileManager, Result,
+    checked_types::{PathAbsolute, PathScoped},
+    client::schema::{DependencyType, Project, ProjectId, Version, VersionId},
+    config::{Mod, VersionedProject, profile::ProfileData},
+    hash::verify_sha1,
+    mgmt::{events::EventSouce, version::VersionSet},
+};
+
+/// The direct dependencies of a set of mods that are not already part of a
+/// profile
+#[derive(Debug, Clone, Default)]
+pub struct Dependencies {
+    /// Dependencies that will be installed automatically
+    pub required: Vec<Project>,
+    /// Dependencies that can be added to the profile if wanted
+    pub optional: Vec<Project>,
+}
+
+impl ProfileManager {
+    /// Get the [dependencies](Dependencies) of the versions of `mods` that
+    /// would be installed to the profile with `data`. Projects already in the
+    /// profile, including excluded mods, are ignored
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if fetching the dependency projects fails. Failures
+    /// getting the version of any individual mod are ignored
+    pub async fn dependencies(&self, client: &Client, data: &ProfileData, mods: &[Mod]) -> Result<Dependencies> {
+        let ((), versions) = TokioScope::scope_and_block(|scope| {
+            for m in mods {
+                scope.spawn(async move {
+                    match m.version() {
+                        Some(vid) => client.get_project_version(m.project(), vid).await,
+                        None => {
+                            client
+                                .get_latest(
+                                    m.project(),
+                                    Some(&data.game_version),
+                                    data.loader.known(),
+                                    m.channel.or(data.channel),
+                                )
+                                .await
+                        },
+                    }
+                });
+            }
+        });
+
+        let mut types = HashMap::new();
+        for v in versions.into_iter().filter_map(|v| v.ok().and_then(Result::ok)) {
+            for dep in v.deps {
+                if mods.iter().chain(&data.mods).any(|m| m.project() == &dep.project_id) {
+                    continue;
+                }
+                let typ = types.entry(dep.project_id).or_insert(dep.dep_type);
+                // Required takes priority if listed as both
+                if matches!(dep.dep_type, DependencyType::Required) {
+                    *typ = dep.dep_type;
+                }
+            }
+        }
+        if types.is_empty() {
+            return Ok(Dependencies::default());
+        }
+
+        let ids = types.keys().map(|id| id as _).collect::<Vec<_>>();
+        let mut deps = Dependencies::default();
+        for proj in client.get_projects(&ids).await? {
+            match types.get(&proj.id) {
+                Some(DependencyType::Required) => deps.required.push(proj),
+                Some(DependencyType::Optional) => deps.optional.push(proj),
+                _ => {},
+            }
+        }
+        Ok(deps)
+    }
+}
+
+// Internal helpers
+impl ProfileManager {
+    /// Transitively resolve the required dependencies of all profile mods and
+    /// add any missing ones to the pending or installed mods of `resolved`.
+    /// Projects in the profile, including excluded mods, and any in `pack_ids`
+    /// are considered to already satisfy a dependency. Any previously installed
+    /// dependencies that are no longer needed will be marked for deletion
+    ///
+    /// Returns the ids of all projects being installed as a dependency
+    pub(super) async fn resolve_deps(
+        &self,
+        client: &Client,
+        data: &ProfileData,
+        pack_ids: &HashSet<ProjectId>,
+        resolved: &mut ResolvedMods<'_>,
+        profile_path: &PathAbsolute,
+        delete: &mut BTreeSet<PathScoped>,
+    ) -> HashSet<ProjectId> {
+        let ResolvedMods {
+            installed,
+            pending,
+            locked_deps,
+            failed,
+            ..
+        } = resolved;
+        let profile_ids = data.mods.iter().map(Mod::project).collect::<HashSet<_>>();
+
+        let mut satisfied = profile_ids
+            .iter()
+            .copied()
+            .chain(pack_ids)
+            .chain(pending.iter().map(|v| &v.project_id))
+            .chain(installed.iter().map(|lm| lm.project()))
+            .cloned()
+            .collect::<HashSet<_>>();
+
+        // Dependencies of the mods added directly to the profile
+        let mut required = pending
+            .iter()
+            .filter(|v| profile_ids.contains(&v.project_id))
+            .flat_map(|v| v.required_deps())
+            .cloned()
+            .collect::<Vec<_>>();
+        self.installed_deps(client, installed, &profile_ids, &mut required).await;
+
+        let mut locked_deps = locked_deps.drain(..).map(|lm| (lm.project(), lm)).collect::<HashMap<_, _>>();
+        let mut deps = HashSet::new();
+        loop {
+            let mut next = vec![];
+            let mut unversioned = HashSet::new();
+            let mut versioned = HashMap::new();
+            for pid in required {
+                if !satisfied.insert(pid.clone()) {
+                    continue;
+                }
+                deps.insert(pid.clone());
+
+                // Keep any previously installed version that's still valid
+                if let Some(lm) = locked_deps.remove(&pid) {
+                    let path = profile_path.join(&lm.file);
+                    if !self.force && path.exists() && verify_sha1(&lm.sha1, &path).await.is_ok_and(identity) {
+                        next.extend(lm.requires.iter().flatten().map(|p| p.project().clone()));
+                        installed.push(Cow::Borrowed(lm));
+                    } else {
+                        versioned.insert(Cow::Owned(pid), Cow::Borrowed(&lm.id.version));
+                    }
+                } else {
+                    unversioned.insert(Cow::Owned(pid));
+                }
+            }
+            if unversioned.is_empty() && versioned.is_empty() && next.is_empty() {
+                break;
+            }
+
+            let mut fetched = VersionSet::new();
+            failed.extend(self.fetch_versions(client, data, unversioned, versioned, &mut fetched).await);
+            for v in fetched {
+                next.extend(v.required_deps().cloned());
+                pending.replace(v);
+            }
+            required = next;
+        }
+
+        // Anything left is no longer required by any mod
+        delete.extend(locked_deps.into_values().map(|lm| lm.file.clone()));
+
+        deps
+    }
+
+    /// Add the required dependencies of all `installed` profile mods to
+    /// `out_required`. Mods installed before their dependencies were tracked
+    /// will have their version fetched to find them
+    async fn installed_deps(
+        &self,
+        client: &Client,
+        installed: &mut [Cow<'_, crate::mgmt::LockedMod>],
+        profile_ids: &HashSet<&ProjectId>,
+        out_required: &mut Vec<ProjectId>,
+    ) {
+        let mut unknown = vec![];
+        for (i, lm) in installed.iter().enumerate().filter(|(_, lm)| profile_ids.contains(lm.project())) {
+            match &lm.requires {
+                Some(requires) => out_required.extend(requires.iter().map(|p| p.project().clone())),
+                None => unknown.push(i),
+            }
+        }
+        // Github releases, direct downloads, and maven artifacts have no dependency
+        // info
+        unknown.retain(|&i| {
+            let none = matches!(
+                installed[i].id.version,
+                VersionId::Github(_) | VersionId::Url(_) | VersionId::Maven(_) | VersionId::Local(_)
+            );
+            if none {
+                installed[i].to_mut().requires = Some(vec![]);
+            }
+            !none
+        });
+        if unknown.is_empty() {
+            return;
+        }
+
+        let ids = unknown.iter().map(|&i| &installed[i].id.version as _).collect::<Vec<_>>();
+        let versions = match client.get_versions(&ids).await {
+            Ok(versions) => versions
+                .into_iter()
+                .map(|v| (v.id.clone(), v))
+                .collect::<HashMap<VersionId, Version>>(),
+            Err(e) => {
+                self.send_err(anyhow!(e).context("Failed to check dependencies of installed mods").into());
+                return;
+            },
+        };
+        for i in unknown {
+            let lm = &mut installed[i];
+            if let Some(v) = versions.get(&lm.id.version) {
+                out_required.extend(v.required_deps().cloned());
+                lm.to_mut().requires = Some(v.required_deps().cloned().map(Into::into).collect());
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        client::schema::Dependency,
+        config::ModLoader,
+        mgmt::lockfile::{LockedMod, ModOrigin},
+    };
+
+    fn version(project: &str, id: &str, deps: &[(&str, DependencyType)]) -> Version {
+        Version {
+            deps: deps
+                .iter()
+                .map(|&(p, dep_type)| Dependency {
+                    project_id: ProjectId::Modrinth(p.to_owned()),
+                    id: None,
+                    dep_type,
+                })
+                .collect(),
+            ..Version::stub(project, id)
+        }
+    }
+
+    fn profile(mods: &[&str]) -> ProfileData {
+        ProfileData {
+            game_version: "1.21.1".to_owned(),
+            loader: ModLoader::Fabric,
+            mods: mods.iter().map(|&id| Mod::stub(ProjectId::Modrinth(id.to_owned()), id)).collect(),
+            ..ProfileData::default()
+        }
+    }
+
+    fn resolved<'a>(pending: &[&Version], locked_deps: Vec<&'a LockedMod>) -> ResolvedMods<'a> {
+        ResolvedMods {
+            versioned: HashMap::new(),
+            unversioned: HashSet::new(),
+            installed: vec![],
+            pending: pending.iter().map(|&v| v.clone().into()).collect(),
+            locked_deps,
+            failed: vec![],
+        }
+    }
+
+    fn ids(ids: &[&str]) -> HashSet<ProjectId> {
+        ids.iter().map(|&id| ProjectId::Modrinth(id.to_owned())).collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn only_required_deps() {
+        use DependencyType::{Optional, Other, Required};
+        let dir = tempfile::tempdir().unwrap();
+        let a = version("a", "a1", &[("b", Required), ("c", Optional), ("d", Other)]);
+        let versions = [
+            a.clone(),
+            version("b", "b1", &[("e", Required)]),
+            version("c", "c1", &[]),
+            version("d", "d1", &[]),
+            version("e", "e1", &[]),
+        ];
+        let client = Client::offline_stub(dir.path(), &versions).await;
+        let profile_path = PathAbsolute::new(dir.path()).unwrap();
+
+        let mut resolved = resolved(&[&a], vec![]);
+        let mut delete = BTreeSet::new();
+        let deps = ProfileManager::new()
+            .resolve_deps(
+                &client,
+                &profile(&["a"]),
+                &HashSet::new(),
+                &mut resolved,
+                &profile_path,
+                &mut delete,
+            )
+            .await;
+
+        // Transitive requirements are installed, optional and other dependency types
+        // aren't
+        assert_eq!(deps, ids(&["b", "e"]));
+        let pending = resolved.pending.iter().map(|v| v.project_id.clone()).collect::<HashSet<_>>();
+        assert_eq!(pending, ids(&["a", "b", "e"]));
+        assert!(delete.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn locked_and_unneeded_deps() {
+        use DependencyType::Required;
+        let dir = tempfile::tempdir().unwrap();
+        let a = version("a", "a1", &[("b", Required), ("pack", Required)]);
+        let (b0, b1, f) = (version("b", "b0", &[]), version("b", "b1", &[]), version("f", "f1", &[]));
+        let client = Client::offline_stub(dir.path(), &[a.clone(), b0.clone(), b1, f.clone()]).await;
+        let profile_path = PathAbsolute::new(dir.path()).unwrap();
+
+        let locked = [b0, f].map(|v| LockedMod {
+            origin: ModOrigin::Dependency,
+            ..v.into()
+        });
+        let mut resolved = resolved(&[&a], locked.iter().collect());
+        let mut delete = BTreeSet::new();
+        let deps = ProfileManager::new()
+            .resolve_deps(
+                &client,
+                &profile(&["a"]),
+                &ids(&["pack"]),
+                &mut resolved,
+                &profile_path,
+                &mut delete,
+            )
+            .await;
+
+        // Modpack mods satisfy deps, and missing locked deps are installed as their
+        // locked version
+        assert_eq!(deps, ids(&["b"]));
+        let b = resolved.pending.get(&ProjectId::Modrinth("b".into())).unwrap();
+        assert_eq!(b.id, VersionId::Modrinth("b0".into()));
+        // No longer required by anything
+        assert_eq!(delete, BTreeSet::from([PathScoped::new("f1.jar").unwrap()]));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn deps_of_installed_mods() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = version("a", "a1", &[("b", DependencyType::Required), ("c", DependencyType::Optional)]);
+        let client = Client::offline_stub(dir.path(), std::slice::from_ref(&a)).await;
+
+        // Installed before dependencies were tracked
+        let lm = LockedMod {
+            requires: None,
+            ..a.into()
+        };
+        let mut installed = vec![Cow::Borrowed(&lm)];
+        let mut required = vec![];
+        ProfileManager::new()
+            .installed_deps(&client, &mut installed, &HashSet::from([lm.project()]), &mut required)
+            .await;
+
+        let b = ProjectId::Modrinth("b".into());
+        assert_eq!(required, std::slice::from_ref(&b));
+        let requires = installed[0].requires.as_ref().unwrap();
+        assert_eq!(requires.iter().map(VersionedProject::project).collect::<Vec<_>>(), [&b]);
+    }
+}
@@ -0,0 +1,287 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    convert::identity,
+    mem::take,
+};
+
+use anyhow::anyhow;
+
+use super::{
+    denied::Substitutes,
+    install::MODS_PATH,
+    plan::{InstallPlan, locked_versions},
+};
+use crate::{
+    Client, ErrorKind, Result,
+    checked_types::{PathAbsolute, PathScopedRef},
+    client::schema::Version,
+    config::{VersionedProject, profile::ProfileData},
+    hash::verify_sha1,
+    mgmt::{
+        ProfileManager, cache,
+        events::{EventSouce, ProgressEvent},
+        lockfile::{LockFile, LockedPack, ModOrigin},
+        modpack::{ModpackData, PackMods},
+        version::VersionSet,
+    },
+};
+
+impl ProfileManager {
+    /// Plan installing exactly the contents of `lockfile`, downloading from
+    /// the locked urls without resolving anything through the apis
+    pub(super) async fn plan_frozen(
+        &self,
+        client: &Client,
+        profile_path: &PathAbsolute,
+        data: &ProfileData,
+        mut lockfile: LockFile,
+        lockfile_sha1: Option<String>,
+    ) -> Result<InstallPlan> {
+        let mut mismatched = mismatches(data, &lockfile);
+        if !mismatched.is_empty() {
+            return Err(ErrorKind::LockMismatch(mismatched).into());
+        }
+        let previous = locked_versions(&lockfile);
+        let delete = take(&mut lockfile.outdated).into_iter().map(|lm| lm.file).collect();
+
+        let pack = match &lockfile.pack {
+            Some(lp) => {
+                self.send(ProgressEvent::Status("Read locked modpack...".to_string()));
+                let mut pack = self.locked_pack(client, lp, data.is_server).await?;
+                mismatched = pack_mismatches(&lockfile, &mut pack);
+                Some(pack)
+            },
+            None => None,
+        };
+        if !mismatched.is_empty() {
+            return Err(ErrorKind::LockMismatch(mismatched).into());
+        }
+
+        self.send(ProgressEvent::Status("Checking locked mods...".to_string()));
+        let mut installed = vec![];
+        let mut pending = VersionSet::new();
+        let mut substitutes = Substitutes::new();
+        for lm in &lockfile.mods {
+            if !self.force && verify_sha1(&lm.sha1, &profile_path.join(&lm.file)).await.is_ok_and(identity) {
+                installed.push(lm.clone());
+                continue;
+            }
+            let v = Version::from(lm);
+            // Only files that have to be downloaded need a url
+            if lm.urls.is_empty() && !self.locked_cached(&v).await {
+                mismatched.push(format!("`{}` has no locked download url", lm.file.display()));
+                continue;
+            }
+            if let Some(sub) = &lm.substitute {
+                // Locked as the version it replaced
+                let original = Version {
+                    id: lm.id.version.clone(),
+                    project_id: lm.id.project.clone(),
+                    ..v.clone()
+                };
+                substitutes.insert(sub.project.clone(), original);
+            }
+            pending.replace(v.into());
+        }
+        if !mismatched.is_empty() {
+            return Err(ErrorKind::LockMismatch(mismatched).into());
+        }
+        let origins = |origin| {
+            { lockfile.mods.iter() }
+                .filter(|lm| lm.origin == origin)
+                .map(|lm| lm.project().clone())
+                .collect::<HashSet<_>>()
+        };
+
+        let plan = InstallPlan {
+            download: vec![],
+            copy: vec![],
+            keep: BTreeSet::new(),
+            delete: BTreeSet::new(),
+            extract: BTreeSet::new(),
+            backup: BTreeSet::new(),
+            changes: vec![],
+            profile_path: profile_path.clone(),
+            lockfile_sha1,
+            game_version: data.game_version.clone(),
+            loader: data.loader,
+            deps: origins(ModOrigin::Dependency),
+            pack_mods: origins(ModOrigin::Pack),
+            lockfile,
+            pack,
+            installed,
+            manual: vec![],
+            pending,
+            substitutes,
+            remove: delete,
+            install_overrides: data.modpack.as_ref().is_some_and(|mp| mp.install_overrides),
+            strategy: data.install.unwrap_or(self.install_strategy),
+        };
+        self.finish_plan(client, plan, previous).await
+    }
+
+    /// Read the locked modpack from the cache, or download it from its locked
+    /// urls
+    async fn locked_pack(&self, client: &Client, lp: &LockedPack, server: bool) -> Result<ModpackData> {
+        let cached = cache::versioned_path(
+            &lp.id.project,
+            &lp.id.version,
+            lp.file.as_os_str(),
+            PathScopedRef::new("modpacks").ok(),
+        );
+        if self.force || !verify_sha1(&lp.sha1, &cached).await.is_ok_and(identity) {
+            if lp.urls.is_empty() {
+                return Err(ErrorKind::LockMismatch(vec![format!("`{}` has no locked download url", lp.file.display())]).into());
+            }
+            let mut version = Version::from(&**lp);
+            client.restrict_local(&mut version);
+            self.download(&version, &cached)
+                .await
+                .ok_or_else(|| anyhow!("Modpack download failed"))?;
+        }
+        self.read_pack(client, &cached, server).await
+    }
+
+    /// Whether the locked file of `v` will be copied from the cache instead of
+    /// downloaded
+    async fn locked_cached(&self, v: &Version) -> bool {
+        let sub = v.filename.parent().and_then(|p| p.file_name_path()).unwrap_or(&MODS_PATH);
+        let sha1 = v.sha1.as_deref().unwrap_or_default();
+        !self.force && !self.no_cache && verify_sha1(sha1, &cache::version_path(v, Some(sub))).await.is_ok_and(identity)
+    }
+}
+
+/// Everything in `data` that doesn't match `lockfile`, or can't be installed
+/// from it alone
+fn mismatches(data: &ProfileData, lockfile: &LockFile) -> Vec<String> {
+    let mut mismatched = vec![];
+    if lockfile.game_version != data.game_version {
+        mismatched.push(format!(
+            "Minecraft version `{}` is locked as `{}`",
+            data.game_version, lockfile.game_version
+        ));
+    }
+    if lockfile.loader != data.loader {
+        mismatched.push(format!("Mod loader `{}` is locked as `{}`", data.loader, lockfile.loader));
+    }
+    match (&data.modpack, &lockfile.pack) {
+        (None, None) => {},
+        (Some(pack), None) => mismatched.push(format!("Modpack `{}` isn't locked", pack.project())),
+        (None, Some(lp)) => mismatched.push(format!("Modpack `{}` is locked but not in the profile", lp.project())),
+        (Some(pack), Some(lp)) => {
+            if pack.project() != lp.project() || pack.version().is_some_and(|v| v != &lp.id.version) {
+                mismatched.push(format!("Modpack `{}` doesn't match the locked version", pack.project()));
+            }
+        },
+    }
+
+    for m in &data.mods {
+        let locked = lockfile.mods.iter().find(|lm| lm.project() == m.project());
+        match locked {
+            Some(lm) if m.exclude && lm.origin != ModOrigin::Dependency => {
+                mismatched.push(format!("`{}` is excluded but locked", m.name));
+            },
+            None if !m.exclude => mismatched.push(format!("`{}` isn't locked", m.name)),
+            Some(lm) if m.version().is_some_and(|v| v != &lm.id.version) => {
+                mismatched.push(format!("`{}` is locked to a different version", m.name));
+            },
+            _ => {},
+        }
+    }
+    for lm in &lockfile.mods {
+        let missing = match lm.origin {
+            ModOrigin::Direct => !data.mods.iter().any(|m| m.project() == lm.project() && !m.exclude),
+            ModOrigin::Pack => data.modpack.is_none(),
+            ModOrigin::Dependency => false,
+        };
+        if missing {
+            mismatched.push(format!("`{}` is locked but not in the profile", lm.file.display()));
+        }
+    }
+    for lm in lockfile.mods.iter().chain(lockfile.pack.as_deref()) {
+        if lm.sha1.is_empty() {
+            mismatched.push(format!("`{}` has no locked sha1", lm.file.display()));
+        }
+    }
+    mismatched
+}
+
+/// Reduce the files of `pack` to the ones locked in `lockfile`, and return any
+/// locked files the pack doesn't have
+fn pack_mismatches(lockfile: &LockFile, pack: &mut ModpackData) -> Vec<String> {
+    match &mut pack.mods {
+        // Identified files are locked as mods
+        PackMods::Forge(mods) => mods.clear(),
+        PackMods::Modrinth { known, unknown } => {
+            known.clear();
+            unknown.retain(|f| {
+                f.path_scoped().is_ok_and(|path| {
+                    lockfile
+                        .other
+                        .get(path)
+                        .is_some_and(|sha1| sha1.eq_ignore_ascii_case(&f.hashes.sha1))
+                })
+            });
+            return { lockfile.other.keys() }
+                .filter(|path| !unknown.iter().any(|f| f.path_scoped().is_ok_and(|p| p == path.as_ref())))
+                .map(|path| format!("Modpack file `{}` isn't in the locked modpack", path.display()))
+                .collect();
+        },
+    }
+    vec![]
+}
+
+#[cfg(test)]
+mod tests {
+    use sha1::{Digest, Sha1};
+
+    use super::*;
+    use crate::{
+        client::DirectClient,
+        config::{Mod, ModLoader},
+        mgmt::lockfile::LockedMod,
+    };
+
+    #[tokio::test]
+    async fn manual_files_without_urls() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile_path = PathAbsolute::new(dir.path()).unwrap();
+        // Files that can only be downloaded manually have no url
+        let version = Version {
+            sha1: Some(format!("{:x}", Sha1::digest("manual"))),
+            filename: "mods/manual.jar".try_into().unwrap(),
+            ..Version::stub("manual", "manual1")
+        };
+        let data = ProfileData {
+            game_version: "1.21.1".to_owned(),
+            loader: ModLoader::Fabric,
+            mods: vec![Mod::stub(version.project_id.clone(), "manual")],
+            ..ProfileData::default()
+        };
+        let lockfile = LockFile {
+            game_version: "1.21.1".to_owned(),
+            loader: ModLoader::Fabric,
+            mods: vec![LockedMod::from(version)],
+            ..LockFile::default()
+        };
+        let client = Client::from(DirectClient);
+        let manager = ProfileManager {
+            frozen: true,
+            no_cache: true,
+            ..ProfileManager::new()
+        };
+
+        let err = { manager.plan_frozen(&client, &profile_path, &data, lockfile.clone(), None) }
+            .await
+            .unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::LockMismatch(m) if m.len() == 1));
+
+        std::fs::create_dir(dir.path().join("mods")).unwrap();
+        std::fs::write(dir.path().join("mods/manual.jar"), "manual").unwrap();
+        let plan = { manager.plan_frozen(&client, &profile_path, &data, lockfile, None) }
+            .await
+            .unwrap();
+        assert_eq!(plan.installed.len(), 1);
+        assert!(plan.pending.is_empty());
+    }
+}
@@ -15,21 +15,29 @@ use itertools::Itertools;
 use crate::{
     Client, ErrorKind, Result, StdResult,
     checked_types::{PathAbsolute, PathScoped, PathScopedRef},
-    client::schema::{ProjectId, Version, VersionId},
-    config::{Mod, Profile, VersionedProject, profile::ProfileData},
+    client::{
+        direct, github,
+        schema::{ProjectId, Version, VersionId},
+    },
+    config::{InstallStrategy, Mod, Profile, VersionedProject, profile::ProfileData},
     hash::{Sha1Writer, verify_sha1, verify_sha1_sync},
     mgmt::{
         ProfileManager, cache,
         events::{EventSouce, InstallType, ProgressEvent},
-        lockfile::{LockFile, LockedMod, LockedPack, PathHashes},
-        modpack::{ModpackData, modrinth::IndexFile},
+        lockfile::{self, LockFile, LockedMod, LockedPack, ModOrigin, PathHashes},
+        modpack::{ModpackData, PackMods, modrinth::IndexFile},
+        mods::{
+            denied::{Substitutes, as_substitute},
+            plan::{InstallPlan, lockfile_sha1},
+            transaction::Transaction,
+        },
         version::VersionSet,
     },
 };
 
 type Downloads = Vec<StdResult<Option<(Version, PathAbsolute)>, tokio::task::JoinError>>;
 
-static MODS_PATH: LazyLock<&PathScopedRef> = LazyLock::new(|| PathScopedRef::new("mods").unwrap());
+pub(super) static MODS_PATH: LazyLock<&PathScopedRef> = LazyLock::new(|| PathScopedRef::new("mods").unwrap());
 
 
 // Public interface
@@ -45,75 +53,139 @@ impl ProfileManager {
     ///
     /// # Errors
     ///
-    /// This function will only return an error if a lock file exists and fails
-    /// to parse. Otherwise, any other individual errors will be sent to the
-    /// [`channel`]
+    /// Will return an error if [`plan`](Self::plan) or
+    /// [`apply_plan`](Self::apply_plan) fails. Otherwise, any other individual
+    /// errors will be sent to the [`channel`]
     ///
     /// [`channel`]: Self::with_channel
     pub async fn apply(&self, client: &Client, profile: &Profile) -> Result<()> {
-        let data = profile.data().await?;
-        let profile_path = profile.path();
-
-        self.send(ProgressEvent::Status("Loading lockfile...".to_string()));
-        let mut lockfile = LockFile::load(profile_path).await?;
+        self.recover(profile.path()).await?;
+        let plan = self.plan(client, profile).await?;
+        self.apply_plan(profile, plan).await
+    }
 
-        self.install(client, profile_path, data, &mut lockfile).await?;
+    /// Install a `plan` previously made by [`plan`](Self::plan). Files are
+    /// checked again as they are installed, so anything changed in the profile
+    /// since the plan was made is still handled
+    ///
+    /// New files are staged and only moved into the profile once every file
+    /// was installed successfully. If anything fails, or a previous apply was
+    /// interrupted, the profile is restored to how it was before. Set
+    /// [`allow_partial`](Self::allow_partial) to install whatever succeeds
+    /// directly to the profile instead
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if `plan` was made for a different profile, the
+    /// lockfile changed since the plan was made, or any file failed to install
+    /// and the profile was restored. Otherwise, any individual errors will be
+    /// sent to the [`channel`](Self::with_channel)
+    pub async fn apply_plan(&self, profile: &Profile, plan: InstallPlan) -> Result<()> {
+        let profile_path = profile.path();
+        if plan.profile_path() != profile_path {
+            return Err(anyhow!("Install plan was made for a different profile: {}", plan.profile_path().display()).into());
+        }
+        self.recover(profile_path).await?;
+        if lockfile_sha1(profile_path).await != plan.lockfile_sha1 {
+            return Err(anyhow!("The lockfile changed since the install plan was made, plan again").into());
+        }
 
-        lockfile.game_version.clone_from(&data.game_version);
-        lockfile.loader = data.loader;
+        let mut tx = Transaction::new(profile_path, !self.allow_partial);
+        let mut lockfile = self.install(profile_path, plan, &mut tx).await;
         lockfile.sort();
-        if let Err(e) = lockfile.save(profile_path).await {
-            self.send_err(e);
+        if !tx.is_staged() {
+            if let Err(e) = lockfile.save(profile_path).await {
+                self.send_err(e);
+            }
+            return Ok(());
         }
 
-        Ok(())
+        let res = match tx.failed() {
+            0 => self.commit(&mut tx, &lockfile).await,
+            failed => Err(ErrorKind::InstallFailed(failed).into()),
+        };
+        if res.is_ok() {
+            tx.finish().await;
+        } else {
+            self.send(ProgressEvent::Status("Restoring previous profile files...".to_owned()));
+            tx.rollback().await?;
+        }
+        res
     }
 }
 
 // Internal helpers
 impl ProfileManager {
-    async fn install(&self, client: &Client, profile_path: &PathAbsolute, data: &ProfileData, lockfile: &mut LockFile) -> Result<()> {
-        let mut delete = take(&mut lockfile.outdated).into_iter().map(|lm| lm.file).collect();
+    /// Restore the files of an interrupted apply to `profile_path`
+    async fn recover(&self, profile_path: &PathAbsolute) -> Result<()> {
+        if Transaction::recover(profile_path).await? {
+            self.send(ProgressEvent::Status("Restored profile files from an interrupted apply".to_owned()));
+        }
+        Ok(())
+    }
 
-        let mut pack = self.load_pack(client, profile_path, data, lockfile, &mut delete).await?;
+    /// Save `lockfile` with the staged files and move them all into the profile
+    async fn commit(&self, tx: &mut Transaction, lockfile: &LockFile) -> Result<()> {
+        lockfile.save(tx.root()).await?;
+        tx.stage(PathScoped::new(lockfile::FILENAME).map_err(anyhow::Error::new)?, false);
 
-        self.send(ProgressEvent::Status("Resolving mod versions...".to_string()));
-        let reset = lockfile.game_version != data.game_version || lockfile.loader != data.loader;
-        let ResolvedMods {
-            versioned,
-            unversioned,
+        self.send(ProgressEvent::Status("Moving installed files into profile...".to_owned()));
+        for file in tx.commit().await? {
+            self.send(ProgressEvent::Deleted(file));
+        }
+        Ok(())
+    }
+
+    async fn install(&self, profile_path: &PathAbsolute, plan: InstallPlan, tx: &mut Transaction) -> LockFile {
+        let InstallPlan {
+            game_version,
+            loader,
+            mut lockfile,
+            pack,
             installed,
-            mut pending,
-        } = merge_sources(
-            &data.mods,
-            &lockfile.mods,
-            pack.as_mut(),
-            profile_path,
-            &mut delete,
-            reset,
-            self.force,
-        )
-        .await;
-
-        self.fetch_versions(client, data, unversioned, versioned, &mut pending).await;
-        let downloads = self.download_files(pending, profile_path);
+            manual,
+            pending,
+            substitutes,
+            deps,
+            pack_mods,
+            remove: mut delete,
+            install_overrides,
+            strategy,
+            ..
+        } = plan;
+        let downloads = self.download_files(pending, tx);
 
         self.send(ProgressEvent::Status("Installing...".to_string()));
         lockfile.mods = installed
             .into_iter()
+            .map(|m| with_origin(m, &deps, &pack_mods))
             .inspect(|m| {
                 self.send(ProgressEvent::Installed {
                     file: m.file.clone(),
                     is_new: false,
-                    typ: InstallType::Mod,
+                    typ: m.origin.into(),
                 });
             })
-            .map(Cow::into_owned)
             .collect();
-        lockfile.mods.extend(self.install_downloaded(downloads, profile_path).await);
+        lockfile
+            .mods
+            .extend(manual.into_iter().map(|m| with_origin(m, &deps, &pack_mods)).inspect(|m| {
+                self.send(ProgressEvent::Installed {
+                    file: m.file.clone(),
+                    is_new: true,
+                    typ: m.origin.into(),
+                });
+            }));
+        lockfile.mods.extend(
+            self.install_downloaded(downloads, tx, strategy, &deps, &pack_mods, substitutes)
+                .await,
+        );
 
         if let Some(pack) = pack {
-            delete.extend(self.install_pack(pack, lockfile, profile_path, data).into_keys());
+            delete.extend(
+                self.install_pack(pack, &mut lockfile, profile_path, tx, install_overrides)
+                    .into_keys(),
+            );
             // Don't delete extracted overrides
             for p in lockfile.pack.as_ref().unwrap().overrides.keys() {
                 delete.remove(p);
@@ -124,24 +196,35 @@ impl ProfileManager {
         for p in lockfile.mods.iter().map(|m| &m.file).chain(lockfile.other.keys()) {
             delete.remove(p);
         }
-        if self.delete_files(delete.iter(), profile_path).await.is_err() {
+        if tx.is_staged() {
+            tx.delete(delete);
+        } else if self.delete_files(delete.iter(), profile_path).await.is_err() {
             let files = delete.iter().map(|p| p.display()).join("\n\t");
             self.send_err(anyhow!("Unexpected error deleting old files. The following may need deleted manually:\n\t{files}").into());
         }
 
-        Ok(())
+        lockfile.game_version = game_version;
+        lockfile.loader = loader;
+        lockfile
     }
 
-    fn install_pack(&self, pack: ModpackData, lockfile: &mut LockFile, profile_path: &PathAbsolute, data: &ProfileData) -> PathHashes {
+    fn install_pack(
+        &self,
+        pack: ModpackData,
+        lockfile: &mut LockFile,
+        profile_path: &PathAbsolute,
+        tx: &mut Transaction,
+        install_overrides: bool,
+    ) -> PathHashes {
         use crate::mgmt::modpack::PackMods::Modrinth;
 
         let mut delete = PathHashes::new();
         if let Modrinth { ref unknown, .. } = pack.mods {
-            delete.extend(self.install_modrinth_unknown(&mut lockfile.other, profile_path, unknown));
+            delete.extend(self.install_modrinth_unknown(&mut lockfile.other, profile_path, tx, unknown));
         }
-        if data.modpack.as_ref().is_some_and(|mp| mp.install_overrides) {
+        if install_overrides {
             let locked_pack = lockfile.pack.as_mut().expect("Should have lockfile pack if we have pack data");
-            let mut removed = self.extract_overrides(&mut locked_pack.overrides, pack, profile_path);
+            let mut removed = self.extract_overrides(&mut locked_pack.overrides, pack, profile_path, tx);
             // Don't delete any overrides that have been modified
             removed.retain(|path, sha1| {
                 let unchanged = verify_sha1_sync(sha1, &profile_path.join(path)).unwrap_or(true);
@@ -159,7 +242,7 @@ impl ProfileManager {
     }
 
     /// Fetches and loads the modpack data from the pack index.
-    async fn load_pack(
+    pub(super) async fn load_pack(
         &self,
         client: &Client,
         profile_path: &PathAbsolute,
@@ -169,10 +252,15 @@ impl ProfileManager {
     ) -> Result<Option<ModpackData>> {
         macro_rules! fetch_replace {
             ($pack:expr) => {
-                self.fetch_pack(client, $pack.deref(), data).await.map(|(data, lm)| {
-                    lockfile.pack.replace(LockedPack::new(lm));
-                    Some(data)
-                })
+                if self.offline {
+                    // A new pack version can't be resolved from the lockfile
+                    Err(ErrorKind::NotLocked(vec![$pack.project().clone()]).into())
+                } else {
+                    self.fetch_pack(client, $pack.deref(), data).await.map(|(data, lm)| {
+                        lockfile.pack.replace(LockedPack::new(lm));
+                        Some(data)
+                    })
+                }
             };
         }
         // Marks unchanged overrides for deletion
@@ -214,8 +302,14 @@ impl ProfileManager {
                         lp.file.as_os_str(),
                         PathScopedRef::new("modpacks").ok(),
                     );
-                    if !self.force && cached.exists() && verify_sha1(&lp.sha1, &cached).await.is_ok_and(identity) {
-                        self.read_pack(client, &cached, data.is_server).await
+                    if (!self.force || self.offline) && cached.exists() && verify_sha1(&lp.sha1, &cached).await.is_ok_and(identity) {
+                        let mut pack = self.read_pack(client, &cached, data.is_server).await;
+                        if let (true, Ok(pack)) = (self.offline, &mut pack) {
+                            self.identify_locked(client, &lockfile.mods, pack).await;
+                        }
+                        pack
+                    } else if self.offline {
+                        Err(ErrorKind::NotCached(vec![format!("Modpack file `{}`", cached.display())]).into())
                     } else {
                         self.fetch_pack(client, lp, data).await.map(|(data, _)| data)
                     }
@@ -226,23 +320,48 @@ impl ProfileManager {
         }
     }
 
+    /// Identify the modrinth pack files that can't be looked up offline from
+    /// the versions previously locked with the same sha1
+    async fn identify_locked(&self, client: &Client, locked: &[LockedMod], pack: &mut ModpackData) {
+        let PackMods::Modrinth { known, unknown } = &mut pack.mods else {
+            return;
+        };
+        let ids = unknown
+            .iter()
+            .filter_map(|f| locked.iter().find(|lm| lm.sha1.eq_ignore_ascii_case(&f.hashes.sha1)))
+            .map(|lm| &lm.id.version as _)
+            .collect::<Vec<_>>();
+        if ids.is_empty() {
+            return;
+        }
+        match client.get_versions(&ids).await {
+            Ok(versions) => {
+                for v in versions {
+                    unknown.retain(|f| v.sha1.as_ref().is_none_or(|sha1| !sha1.eq_ignore_ascii_case(&f.hashes.sha1)));
+                    known.replace(v.into());
+                }
+            },
+            Err(e) => self.send_err(e),
+        }
+    }
+
     async fn fetch_pack(&self, client: &Client, pack: &impl VersionedProject, data: &ProfileData) -> Result<(ModpackData, LockedMod)> {
         let (v, data) = self.load_modpack(client, pack, data).await?;
         Ok((data, v.into()))
     }
 
-    /// Fetch the [version](Version) details of all mods that will be installed
+    /// Fetch the [version](Version) details of all mods that will be installed.
+    /// Returns why any project couldn't be resolved
     ///
     /// [version]: crate::client::schema::Version
-    async fn fetch_versions(
+    pub(in crate::mgmt) async fn fetch_versions(
         &self,
         client: &Client,
         data: &ProfileData,
         unversioned: HashSet<Cow<'_, ProjectId>>,
         versioned: HashMap<Cow<'_, ProjectId>, Cow<'_, VersionId>>,
         out_pending: &mut VersionSet,
-    ) {
-        self.send(ProgressEvent::Status("Fetch version details...".to_string()));
+    ) -> Vec<crate::Error> {
         // Get the latest version of all unversioned projects
         let ((), pending) = TokioScope::scope_and_block(|scope| {
             let semaphore = Arc::new(tokio::sync::Semaphore::const_new(10));
@@ -250,22 +369,44 @@ impl ProfileManager {
                 let semaphore = semaphore.clone();
                 scope.spawn(async move {
                     let _permit = semaphore.acquire().await.unwrap();
-                    client.get_latest(id.as_ref(), Some(&data.game_version), data.loader.known()).await
+                    self.latest_compatible(client, id, data).await
                 });
             }
         });
+        let mut failed = vec![];
         for res in pending {
             match res {
                 Ok(Ok(v)) => {
                     out_pending.replace(v.into());
                 },
-                Ok(Err(e)) => self.send_err(e),
-                Err(e) => self.send_err(anyhow!(e).into()),
+                Ok(Err(e)) => failed.push(e),
+                Err(e) => failed.push(anyhow!(e).into()),
             }
         }
 
-        // Fetch version details for all versioned projects
-        match client
+        let err = self.fetch_versioned(client, &versioned, out_pending).await;
+
+        // Report any unknown projects
+        let mut unknown = versioned.into_keys().collect::<HashSet<_>>();
+        for v in out_pending.iter() {
+            unknown.remove(&v.project_id);
+        }
+        if let Some(e) = err.filter(|_| !unknown.is_empty()) {
+            failed.push(e);
+        }
+        failed.extend(unknown.into_iter().map(|id| ErrorKind::MissingVersion(id.into_owned()).into()));
+        failed
+    }
+
+    /// Fetch the details of every `versioned` project's version. Returns the
+    /// error of the batch lookup if it failed
+    async fn fetch_versioned(
+        &self,
+        client: &Client,
+        versioned: &HashMap<Cow<'_, ProjectId>, Cow<'_, VersionId>>,
+        out_pending: &mut VersionSet,
+    ) -> Option<crate::Error> {
+        let err = match client
             .get_versions(&versioned.values().map(|v| v.as_ref() as _).collect::<Vec<_>>())
             .await
         {
@@ -273,21 +414,85 @@ impl ProfileManager {
                 for v in versions {
                     out_pending.replace(v.into());
                 }
+                None
             },
-            Err(e) => self.send_err(e),
+            // Offline batches fail if any version isn't cached, so find the rest
+            Err(e) if matches!(e.kind(), ErrorKind::DoesNotExist) => {
+                for vid in versioned.values() {
+                    if let Ok(v) = client.get_version(vid.as_ref()).await {
+                        out_pending.replace(v.into());
+                    }
+                }
+                Some(e)
+            },
+            Err(e) => Some(e),
+        };
+
+        // Url versions are known from their id alone
+        for (pid, vid) in versioned {
+            if let (ProjectId::Url(url), VersionId::Url(expected)) = (pid.as_ref(), vid.as_ref()) {
+                match direct::url_version(url.clone(), expected.clone()) {
+                    Ok(v) => {
+                        out_pending.replace(v.into());
+                    },
+                    Err(e) => self.send_err(e),
+                }
+            }
         }
 
-        // Notify of any unknown projects
-        let mut unversioned = versioned.into_keys().chain(unversioned).collect::<HashSet<_>>();
-        for v in out_pending.iter() {
-            unversioned.remove(&v.project_id);
+        // Maven versions need their artifact to be located
+        if let Some(mvn) = client.as_maven() {
+            for (pid, vid) in versioned {
+                if let (ProjectId::Maven(id), VersionId::Maven(version)) = (pid.as_ref(), vid.as_ref()) {
+                    match mvn.fetch_version(id, version).await {
+                        Ok(v) => {
+                            out_pending.replace(v.into());
+                        },
+                        Err(e) => self.send_err(e),
+                    }
+                }
+            }
+        }
+
+        // Local versions need the pattern they matched
+        if let Some(local) = client.as_local() {
+            for (pid, vid) in versioned {
+                if let (ProjectId::Local(pattern), VersionId::Local(file)) = (pid.as_ref(), vid.as_ref()) {
+                    match local.fetch_version(pattern, file).await {
+                        Ok(v) => {
+                            out_pending.replace(v.into());
+                        },
+                        Err(e) => self.send_err(e),
+                    }
+                }
+            }
         }
-        for id in unversioned {
-            self.send_err(ErrorKind::MissingVersion(id.into_owned()).into());
+
+        // Github assets can only be fetched by id alone with GraphQL, so get any
+        // still missing from their repo instead
+        if let Some(gh) = client.as_github() {
+            let missing = versioned
+                .iter()
+                .filter_map(|(pid, vid)| match (pid.as_ref(), vid.as_ref()) {
+                    (ProjectId::Github((owner, repo)), VersionId::Github(asset))
+                        if !out_pending.iter().any(|v| v.project_id == *pid.as_ref()) =>
+                    {
+                        Some(((owner.as_str(), repo.as_str()), *asset))
+                    },
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                for v in github::fetch_assets(gh, &missing) {
+                    out_pending.replace(v.into());
+                }
+            }
         }
+
+        err
     }
 
-    fn download_files(&self, pending: VersionSet, profile_path: &PathAbsolute) -> Downloads {
+    fn download_files(&self, pending: VersionSet, tx: &Transaction) -> Downloads {
         if !pending.is_empty() {
             self.send(ProgressEvent::Status("Downloading...".to_string()));
         }
@@ -295,7 +500,7 @@ impl ProfileManager {
             for v in pending {
                 let sub = v.filename.parent().and_then(|p| p.file_name_path()).unwrap_or(&MODS_PATH);
                 let save_path = if self.no_cache {
-                    profile_path.join(sub)
+                    tx.target(sub)
                 } else {
                     cache::version_path(&v, Some(sub))
                 };
@@ -316,7 +521,8 @@ impl ProfileManager {
         let delete = delete
             .filter_map(|p| {
                 let file = profile_path.join(p);
-                file.exists().then_some((p.to_owned(), file))
+                // Checks the link itself so broken links are still deleted
+                file.symlink_metadata().is_ok().then_some((p.to_owned(), file))
             })
             .collect::<Vec<_>>();
         if !delete.is_empty() {
@@ -333,23 +539,48 @@ impl ProfileManager {
         })
     }
 
-    async fn install_downloaded(&self, downloads: Downloads, profile_path: &PathAbsolute) -> Vec<LockedMod> {
+    async fn install_downloaded(
+        &self,
+        downloads: Downloads,
+        tx: &mut Transaction,
+        strategy: InstallStrategy,
+        deps: &HashSet<ProjectId>,
+        pack_mods: &HashSet<ProjectId>,
+        mut substitutes: Substitutes,
+    ) -> Vec<LockedMod> {
         let mut installed = Vec::with_capacity(downloads.len());
+        let mut fell_back = false;
         for dl in downloads {
             match dl {
-                Ok(Some((v, file_path))) => match install_version(profile_path, v, &file_path).await {
-                    Ok(lm) => {
+                Ok(Some((v, file_path))) => match install_version(tx, strategy, v, &file_path).await {
+                    Ok((lm, used)) => {
+                        if used != strategy && !fell_back {
+                            fell_back = true;
+                            self.send(ProgressEvent::Status(format!(
+                                "Install strategy `{strategy}` is not supported here, copying files instead"
+                            )));
+                        }
+                        let lm = with_origin(as_substitute(lm, &mut substitutes), deps, pack_mods);
                         self.send(ProgressEvent::Installed {
                             file: lm.file.clone(),
                             is_new: true,
-                            typ: InstallType::Mod,
+                            typ: lm.origin.into(),
                         });
                         installed.push(lm);
                     },
-                    Err(e) => self.send_err(e),
+                    Err(e) => {
+                        tx.fail();
+                        self.send_err(e);
+                    },
+                },
+                Ok(None) => {
+                    // Download failed. Error already sent to channel
+                    tx.fail();
+                },
+                Err(e) => {
+                    tx.fail();
+                    self.send_err(anyhow!(e).into());
                 },
-                Ok(None) => { /* Download failed. Error already sent to channel */ },
-                Err(e) => self.send_err(anyhow!(e).into()),
             }
         }
         installed
@@ -360,7 +591,13 @@ impl ProfileManager {
     ///
     /// Returns any previously installed files that are no longer present in the
     /// pack
-    fn install_modrinth_unknown(&self, lock: &mut PathHashes, profile_path: &PathAbsolute, unknown: &[IndexFile]) -> PathHashes {
+    fn install_modrinth_unknown(
+        &self,
+        lock: &mut PathHashes,
+        profile_path: &PathAbsolute,
+        tx: &mut Transaction,
+        unknown: &[IndexFile],
+    ) -> PathHashes {
         let mut to_delete = take(lock);
         if unknown.is_empty() {
             return to_delete;
@@ -373,13 +610,14 @@ impl ProfileManager {
                     continue;
                 };
                 to_delete.remove(path);
+                let target = tx.target(path);
                 scope.spawn(async move {
-                    let target = &profile_path.join(path);
+                    let installed = &profile_path.join(path);
                     let path = path.to_owned();
-                    if !self.force && verify_sha1(&file.hashes.sha1, target).await.unwrap_or(false) {
+                    if !self.force && verify_sha1(&file.hashes.sha1, installed).await.unwrap_or(false) {
                         Some((file.hashes.sha1.clone(), path, false))
                     } else {
-                        self.download(file, target).await.map(|sha1| (sha1, path, true))
+                        self.download(file, &target).await.map(|sha1| (sha1, path, true))
                     }
                 });
             }
@@ -387,6 +625,9 @@ impl ProfileManager {
         for dl in downloads {
             match dl {
                 Ok(Some((sha1, file, is_new))) => {
+                    if is_new {
+                        tx.stage(file.clone(), false);
+                    }
                     lock.insert(file.clone(), sha1);
                     self.send(ProgressEvent::Installed {
                         file,
@@ -394,8 +635,14 @@ impl ProfileManager {
                         typ: InstallType::Other,
                     });
                 },
-                Ok(None) => { /* Download failed. Error already sent to channel */ },
-                Err(e) => self.send_err(anyhow!(e).into()),
+                Ok(None) => {
+                    // Download failed. Error already sent to channel
+                    tx.fail();
+                },
+                Err(e) => {
+                    tx.fail();
+                    self.send_err(anyhow!(e).into());
+                },
             }
         }
         to_delete
@@ -406,26 +653,37 @@ impl ProfileManager {
     ///
     /// Returns any previously installed override files that are no longer
     /// present in the pack
-    fn extract_overrides(&self, overrides: &mut PathHashes, mut pack: ModpackData, profile_path: &PathAbsolute) -> PathHashes {
+    fn extract_overrides(
+        &self,
+        overrides: &mut PathHashes,
+        mut pack: ModpackData,
+        profile_path: &PathAbsolute,
+        tx: &mut Transaction,
+    ) -> PathHashes {
         self.send(ProgressEvent::Status("Extracting Overrides...".to_string()));
         let mut to_delete = take(overrides);
         pack.visit_overrides(|path, mut file| {
             use std::{fs, io};
 
-            let target = &profile_path.join(path);
+            let installed = &profile_path.join(path);
+            let target = &tx.target(path);
+            let mut backup = false;
             if let Some(sha1) = to_delete.remove(path) {
-                if let Ok(false) = verify_sha1_sync(&sha1, target) {
+                if let Ok(false) = verify_sha1_sync(&sha1, installed) {
+                    backup = true;
                     let bak = {
-                        let mut bak = target.to_path_buf();
+                        let mut bak = installed.to_path_buf();
                         bak.as_mut_os_string().push(".bak");
                         bak
                     };
-                    if fs::rename(target, &bak).is_ok() {
+                    // Staged backups are made when the new file is moved into the profile
+                    if tx.is_staged() || fs::rename(installed, &bak).is_ok() {
                         self.send(ProgressEvent::Status(format!(
                             "Created backup of modified override file: {}",
                             bak.display()
                         )));
                     } else {
+                        tx.fail();
                         self.send_err(
                             anyhow!(
                                 "Failed to create backup of modified override file. It will not be extracted\n\t{}",
@@ -439,6 +697,9 @@ impl ProfileManager {
             }
 
             target.parent().map(fs::create_dir_all);
+            // Replace rather than overwrite, so a file linked to the cache is never
+            // written through. Hard links can't be detected on every platform
+            let _ = fs::remove_file(target);
             let sha1 = fs::File::create(target)
                 .map(io::BufWriter::new)
                 .map(Sha1Writer::new)
@@ -448,6 +709,7 @@ impl ProfileManager {
                 });
             match sha1 {
                 Ok(sha1) => {
+                    tx.stage(path.to_owned(), backup);
                     overrides.insert(path.to_owned(), sha1);
                     self.send(ProgressEvent::Installed {
                         file: path.to_owned(),
@@ -455,14 +717,17 @@ impl ProfileManager {
                         typ: InstallType::Override,
                     });
                 },
-                Err(e) => self.send_err(e.into()),
+                Err(e) => {
+                    tx.fail();
+                    self.send_err(e.into());
+                },
             }
         });
         to_delete
     }
 }
 
-async fn merge_sources<'a>(
+pub(super) async fn merge_sources<'a>(
     profile: &'a Vec<Mod>,
     locked: &'a Vec<LockedMod>,
     pack: Option<&'a mut ModpackData>,
@@ -480,6 +745,8 @@ async fn merge_sources<'a>(
         unversioned,
         installed,
         pending,
+        locked_deps,
+        ..
     } = &mut resolved;
 
     // Modpack first as base set of mods
@@ -518,6 +785,17 @@ async fn merge_sources<'a>(
         let pid = m.project();
         let vid = &m.id.version;
 
+        // Dependencies not requested directly are handled during dependency resolution
+        if !reset
+            && m.origin == ModOrigin::Dependency
+            && !unversioned.contains(pid)
+            && !versioned.contains_key(pid)
+            && !pending.contains(pid)
+        {
+            locked_deps.push(m);
+            continue;
+        }
+
         // If locked mod isn't in profile or it has a different version, delete it
         {
             let ver = versioned.get(pid);
@@ -543,33 +821,63 @@ async fn merge_sources<'a>(
     resolved
 }
 
-async fn install_version(profile_path: &PathAbsolute, v: Version, cached: &Path) -> Result<LockedMod> {
+/// Set the origin of `lm` based on whether it was installed as one of `deps`,
+/// or one of the `pack_mods` of the modpack
+fn with_origin(mut lm: LockedMod, deps: &HashSet<ProjectId>, pack_mods: &HashSet<ProjectId>) -> LockedMod {
+    lm.origin = if deps.contains(lm.project()) {
+        ModOrigin::Dependency
+    } else if pack_mods.contains(lm.project()) {
+        ModOrigin::Pack
+    } else {
+        ModOrigin::Direct
+    };
+    lm
+}
+
+/// The path `file` is installed to within a profile
+pub(super) fn install_path(file: PathScoped) -> PathScoped {
+    // put in mods subdir if not specified
+    match file.parent() {
+        Some(p) if !p.as_os_str().is_empty() => file,
+        _ => MODS_PATH.join(file),
+    }
+}
+
+/// Install the downloaded version `v` from `cached`. Returns the locked mod and
+/// the [strategy](InstallStrategy) actually used to install it
+async fn install_version(
+    tx: &mut Transaction,
+    strategy: InstallStrategy,
+    v: Version,
+    cached: &Path,
+) -> Result<(LockedMod, InstallStrategy)> {
     let lm = {
         let mut lm: LockedMod = v.into();
-        // put in mods subdir if not specified
-        lm.file = match lm.file.parent() {
-            Some(p) if !p.as_os_str().is_empty() => lm.file,
-            _ => MODS_PATH.join(lm.file),
-        };
-
+        lm.file = install_path(lm.file);
         lm
     };
 
-    let dest = profile_path.join(&lm.file);
+    let dest = tx.target(&lm.file);
+    let mut used = strategy;
     if cached != &*dest {
         let _ = tokio::fs::create_dir_all(dest.parent().expect("dest directory should always be valid")).await;
-        tokio::fs::copy(cached, dest)
+        used = cache::install_file(strategy, cached, &dest)
             .await
-            .with_context(|| format!("Failed to copy downloaded mod into profile: {}", lm.file.display()))?;
+            .with_context(|| format!("Failed to install downloaded mod into profile: {}", lm.file.display()))?;
     }
-    Ok(lm)
+    tx.stage(lm.file.clone(), false);
+    Ok((lm, used))
 }
 
 
 #[derive(Debug, Default)]
-struct ResolvedMods<'a> {
-    versioned: HashMap<Cow<'a, ProjectId>, Cow<'a, VersionId>>,
-    unversioned: HashSet<Cow<'a, ProjectId>>,
-    installed: Vec<Cow<'a, LockedMod>>,
-    pending: VersionSet,
+pub(super) struct ResolvedMods<'a> {
+    pub versioned: HashMap<Cow<'a, ProjectId>, Cow<'a, VersionId>>,
+    pub unversioned: HashSet<Cow<'a, ProjectId>>,
+    pub installed: Vec<Cow<'a, LockedMod>>,
+    pub pending: VersionSet,
+    /// Previously installed dependencies that were not requested directly
+    pub locked_deps: Vec<&'a LockedMod>,
+    /// Why any project couldn't be resolved
+    pub failed: Vec<crate::Error>,
 }
@@ -0,0 +1,679 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashMap, HashSet},
+    convert::identity,
+    mem::take,
+    path::Path,
+};
+
+use anyhow::anyhow;
+
+use super::{
+    denied::Substitutes,
+    install::{MODS_PATH, ResolvedMods, install_path, merge_sources},
+};
+use crate::{
+    Client, ErrorKind, Result,
+    checked_types::{PathAbsolute, PathScoped},
+    client::{
+        CacheOptions,
+        schema::{ProjectId, Version, VersionId},
+    },
+    config::{InstallStrategy, ModLoader, Profile, ProjectWithVersion, VersionedProject, profile::ProfileData},
+    hash::{self, verify_sha1, verify_sha1_sync},
+    mgmt::{
+        ProfileManager, cache,
+        events::{EventSouce, ProgressEvent},
+        lockfile::{LockFile, LockedMod},
+        modpack::{ModpackData, PackMods},
+        version::VersionSet,
+    },
+};
+
+/// Everything [`apply`](ProfileManager::apply) will change in a profile,
+/// resolved ahead of time by [`plan`](ProfileManager::plan) so it can be
+/// inspected before being installed with
+/// [`apply_plan`](ProfileManager::apply_plan)
+///
+/// The public fields only describe the plan. Changing them does not change what
+/// will be installed
+#[derive(Debug)]
+pub struct InstallPlan {
+    /// Files that will be downloaded and installed
+    pub download: Vec<PlannedFile>,
+    /// Files that are already downloaded to the cache and will be copied or
+    /// linked into the profile
+    pub copy: Vec<PlannedFile>,
+    /// Installed files that are already up to date
+    pub keep: BTreeSet<PathScoped>,
+    /// Installed files that will be deleted
+    pub delete: BTreeSet<PathScoped>,
+    /// Modpack override files that will be extracted
+    pub extract: BTreeSet<PathScoped>,
+    /// Modified modpack override files that will be renamed with a `.bak`
+    /// extension before being replaced
+    pub backup: BTreeSet<PathScoped>,
+    /// Projects that will be added, removed, or changed in the lockfile
+    pub changes: Vec<LockChange>,
+
+    pub(super) profile_path: PathAbsolute,
+    /// The sha1 of the lockfile the plan was made from, if there was one
+    pub(super) lockfile_sha1: Option<String>,
+    pub(super) game_version: String,
+    pub(super) loader: ModLoader,
+    pub(super) lockfile: LockFile,
+    pub(super) pack: Option<ModpackData>,
+    pub(super) installed: Vec<LockedMod>,
+    pub(super) manual: Vec<LockedMod>,
+    pub(super) pending: VersionSet,
+    pub(super) substitutes: Substitutes,
+    pub(super) deps: HashSet<ProjectId>,
+    /// Projects installed by the modpack and not the profile
+    pub(super) pack_mods: HashSet<ProjectId>,
+    /// The files that will actually be deleted, along with any found while
+    /// installing
+    pub(super) remove: BTreeSet<PathScoped>,
+    pub(super) install_overrides: bool,
+    pub(super) strategy: InstallStrategy,
+}
+
+/// A file that will be installed to a profile
+#[derive(Debug, Clone)]
+pub struct PlannedFile {
+    /// The path the file will be installed to in the profile
+    pub file: PathScoped,
+    /// The project and version the file belongs to, if known
+    pub id: Option<ProjectWithVersion>,
+    #[allow(missing_docs)]
+    pub title: String,
+    /// The size of the file in bytes, or 0 if unknown
+    pub length: u64,
+}
+
+/// The change in the locked version of a project. A version of [`None`] means
+/// the project is not installed
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(missing_docs)] // fields are self explanatory
+pub struct LockChange {
+    pub project: ProjectId,
+    pub from: Option<VersionId>,
+    pub to: Option<VersionId>,
+}
+
+impl InstallPlan {
+    /// Returns `true` if installing the plan would not change anything
+    pub fn is_empty(&self) -> bool {
+        self.download.is_empty()
+            && self.copy.is_empty()
+            && self.delete.is_empty()
+            && self.extract.is_empty()
+            && self.backup.is_empty()
+            && self.changes.is_empty()
+    }
+
+    /// The path of the profile this plan was made for
+    pub fn profile_path(&self) -> &PathAbsolute {
+        &self.profile_path
+    }
+}
+
+impl ProfileManager {
+    /// Resolve everything that [`apply`](Self::apply) would install to
+    /// `profile` without changing any files in the profile. Version details are
+    /// still fetched, and the modpack is downloaded to the cache to read its
+    /// contents. The files of an interrupted apply are only restored once the
+    /// plan is installed
+    ///
+    /// Progress and most errors will be sent to [`channel`](Self::with_channel)
+    ///
+    /// When [offline](Self::offline), everything is resolved from the lockfile
+    /// and cache alone
+    ///
+    /// # Errors
+    ///
+    /// This function will only return an error if a lock file exists and fails
+    /// to parse, the modpack can't be loaded, or any mod can't be resolved
+    /// without [`allow_partial`](Self::allow_partial). When offline, it will
+    /// also return an error if any mod isn't locked, or any locked version
+    /// or file isn't cached. When [frozen](Self::frozen), it will return an
+    /// error if the profile doesn't match the lockfile
+    pub async fn plan(&self, client: &Client, profile: &Profile) -> Result<InstallPlan> {
+        let data = profile.data().await?;
+        let profile_path = profile.path();
+        let offline_client;
+        let client = if self.offline && !client.is_offline() {
+            offline_client = client.clone().cached(CacheOptions {
+                offline: true,
+                ..Default::default()
+            });
+            &offline_client
+        } else {
+            client
+        };
+
+        self.send(ProgressEvent::Status("Loading lockfile...".to_string()));
+        let lockfile_sha1 = lockfile_sha1(profile_path).await;
+        let lockfile = LockFile::load(profile_path).await?;
+        if self.frozen {
+            self.plan_frozen(client, profile_path, data, lockfile, lockfile_sha1).await
+        } else {
+            self.plan_resolved(client, profile_path, data, lockfile, lockfile_sha1).await
+        }
+    }
+
+    /// Plan installing `data`, resolving what changed since `lockfile` through
+    /// the apis
+    async fn plan_resolved(
+        &self,
+        client: &Client,
+        profile_path: &PathAbsolute,
+        data: &ProfileData,
+        mut lockfile: LockFile,
+        lockfile_sha1: Option<String>,
+    ) -> Result<InstallPlan> {
+        let reset = lockfile.game_version != data.game_version || lockfile.loader != data.loader;
+        if self.offline && reset {
+            return Err(
+                anyhow!("Can't apply offline, the profile hasn't been applied online with its current game version and loader").into(),
+            );
+        }
+        let previous = locked_versions(&lockfile);
+        let mut delete = take(&mut lockfile.outdated).into_iter().map(|lm| lm.file).collect();
+
+        let mut pack = self.load_pack(client, profile_path, data, &mut lockfile, &mut delete).await?;
+        if let (false, Some(lp)) = (self.offline, lockfile.pack.as_mut()) {
+            self.lock_urls(client, data, std::slice::from_mut(&mut **lp)).await;
+        }
+
+        self.send(ProgressEvent::Status("Resolving mod versions...".to_string()));
+        let pack_ids = pack.as_ref().map(ModpackData::project_ids).unwrap_or_default();
+        let pack_mods = { pack_ids.iter() }
+            .filter(|&p| !data.mods.iter().any(|m| m.project() == p))
+            .cloned()
+            .collect();
+        let (installed, mut pending, deps) = {
+            let mut resolved = merge_sources(
+                &data.mods,
+                &lockfile.mods,
+                pack.as_mut(),
+                profile_path,
+                &mut delete,
+                reset,
+                self.force,
+            )
+            .await;
+            if self.offline && !resolved.unversioned.is_empty() {
+                return Err(ErrorKind::NotLocked(resolved.unversioned.iter().map(|p| p.as_ref().clone()).collect()).into());
+            }
+            let mut expected = resolved.versioned.keys().map(|p| p.as_ref().clone()).collect::<Vec<_>>();
+            if self.offline {
+                locked_maven(&lockfile, &mut resolved);
+            }
+
+            self.send(ProgressEvent::Status("Fetch version details...".to_string()));
+            let failed = self
+                .fetch_versions(
+                    client,
+                    data,
+                    take(&mut resolved.unversioned),
+                    take(&mut resolved.versioned),
+                    &mut resolved.pending,
+                )
+                .await;
+            resolved.failed.extend(failed);
+
+            self.send(ProgressEvent::Status("Resolving dependencies...".to_string()));
+            let deps = self
+                .resolve_deps(client, data, &pack_ids, &mut resolved, profile_path, &mut delete)
+                .await;
+
+            if self.offline {
+                expected.extend(deps.iter().cloned());
+                self.check_resolved(&lockfile, &resolved, expected)?;
+                pin_locked_sha1(&lockfile, &mut resolved.pending);
+            }
+            self.check_failed(take(&mut resolved.failed))?;
+
+            let ResolvedMods { installed, pending, .. } = resolved;
+            (installed.into_iter().map(Cow::into_owned).collect::<Vec<_>>(), pending, deps)
+        };
+        let mut installed = installed;
+        if !self.offline {
+            self.lock_urls(client, data, &mut installed).await;
+        }
+        let (substitutes, manual) = self.substitute_denied(client, &mut pending, profile_path).await;
+
+        let plan = InstallPlan {
+            download: vec![],
+            copy: vec![],
+            keep: BTreeSet::new(),
+            delete: BTreeSet::new(),
+            extract: BTreeSet::new(),
+            backup: BTreeSet::new(),
+            changes: vec![],
+            profile_path: profile_path.clone(),
+            lockfile_sha1,
+            game_version: data.game_version.clone(),
+            loader: data.loader,
+            lockfile,
+            pack,
+            installed,
+            manual,
+            pending,
+            substitutes,
+            deps,
+            pack_mods,
+            remove: delete,
+            install_overrides: data.modpack.as_ref().is_some_and(|mp| mp.install_overrides),
+            strategy: data.install.unwrap_or(self.install_strategy),
+        };
+        self.finish_plan(client, plan, previous).await
+    }
+
+    /// Describe the files changed by `plan`, and the changes from the
+    /// `previous` locked versions. Only local versions are allowed to be read
+    /// from disk
+    pub(super) async fn finish_plan(
+        &self,
+        client: &Client,
+        mut plan: InstallPlan,
+        previous: HashMap<ProjectId, VersionId>,
+    ) -> Result<InstallPlan> {
+        plan.pending = { take(&mut plan.pending).into_iter() }
+            .map(|v| {
+                let mut v = v.into_inner();
+                client.restrict_local(&mut v);
+                v.into()
+            })
+            .collect();
+        let missing = self.describe(&mut plan).await;
+        if !missing.is_empty() {
+            return Err(ErrorKind::NotCached(missing).into());
+        }
+        plan.changes = lock_changes(previous, &plan);
+        Ok(plan)
+    }
+
+    /// Record the download urls of `installed` mods that were locked before
+    /// urls and sizes were, so they can be installed [frozen](Self::frozen).
+    /// Files that can only be downloaded manually are locked with a size but
+    /// no urls, so they aren't fetched again
+    async fn lock_urls(&self, client: &Client, data: &ProfileData, installed: &mut [LockedMod]) {
+        let mut missing = { installed.iter_mut() }
+            .filter(|lm| lm.urls.is_empty() && lm.size == 0)
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            return;
+        }
+        let versioned = { missing.iter() }
+            .map(|lm| {
+                let id = lm.substitute.as_ref().unwrap_or(&lm.id);
+                (Cow::Owned(id.project.clone()), Cow::Owned(id.version.clone()))
+            })
+            .collect();
+        let mut found = VersionSet::new();
+        for e in self.fetch_versions(client, data, HashSet::new(), versioned, &mut found).await {
+            self.send_err(e);
+        }
+        for lm in &mut missing {
+            let id = lm.substitute.as_ref().unwrap_or(&lm.id);
+            if let Some(v) = found.get(&id.project).filter(|v| v.id == id.version) {
+                lm.urls = v.download_url.iter().chain(&v.mirrors).cloned().collect();
+                if lm.size == 0 {
+                    lm.size = v.length;
+                }
+            }
+        }
+    }
+
+    /// Make sure every `expected` project was resolved without the network.
+    /// Projects that were never locked must be applied online first
+    fn check_resolved(&self, lockfile: &LockFile, resolved: &ResolvedMods<'_>, expected: Vec<ProjectId>) -> Result<()> {
+        let (locked, unlocked): (Vec<_>, Vec<_>) = expected
+            .into_iter()
+            .filter(|p| !resolved.pending.contains(p) && !resolved.installed.iter().any(|lm| lm.project() == p))
+            .partition(|p| lockfile.mods.iter().any(|lm| lm.project() == p));
+        if !unlocked.is_empty() {
+            return Err(ErrorKind::NotLocked(unlocked).into());
+        }
+        if !locked.is_empty() {
+            return Err(ErrorKind::NotCached(locked.iter().map(|p| format!("Version details of `{p}`")).collect()).into());
+        }
+        debug_assert!(self.offline);
+        Ok(())
+    }
+
+    /// Fail if any project couldn't be resolved, so the profile is never
+    /// installed without it. With [`allow_partial`](Self::allow_partial), the
+    /// errors are only sent to the [`channel`](Self::with_channel)
+    fn check_failed(&self, failed: Vec<crate::Error>) -> Result<()> {
+        if failed.is_empty() {
+            return Ok(());
+        }
+        if !self.allow_partial {
+            return Err(ErrorKind::Unresolved(failed).into());
+        }
+        for e in failed {
+            self.send_err(e);
+        }
+        Ok(())
+    }
+
+    /// Fill in the public description of the files changed by `plan`. Returns
+    /// the files that can't be installed [offline](Self::offline)
+    async fn describe(&self, plan: &mut InstallPlan) -> Vec<String> {
+        let mut missing = vec![];
+        let profile_path = &plan.profile_path;
+        plan.delete.clone_from(&plan.remove);
+        plan.keep
+            .extend(plan.installed.iter().chain(&plan.manual).map(|lm| lm.file.clone()));
+
+        for v in &plan.pending {
+            let sub = v.filename.parent().and_then(|p| p.file_name_path()).unwrap_or(&MODS_PATH);
+            let cached = cache::version_path(v, Some(sub));
+            let in_cache = !self.no_cache
+                && match &v.sha1 {
+                    Some(sha1) => verify_sha1(sha1, &cached).await.is_ok_and(identity),
+                    None => false,
+                };
+            let is_cached = !self.force && in_cache;
+            // Local files are still available offline
+            if self.offline && !in_cache && !(matches!(v.id, VersionId::Local(_)) && v.download_url.is_some()) {
+                missing.push(format!("`{}` ({})", v.title, cached.display()));
+            }
+            let file = PlannedFile {
+                file: install_path(v.filename.clone()),
+                id: ProjectWithVersion::new(v.project_id.clone(), Some(v.id.clone())).ok(),
+                title: v.title.clone(),
+                length: v.length,
+            };
+            if is_cached {
+                plan.copy.push(file);
+            } else {
+                plan.download.push(file);
+            }
+        }
+
+        if let Some(pack) = plan.pack.as_mut() {
+            if let PackMods::Modrinth { unknown, .. } = &pack.mods {
+                let mut removed = plan.lockfile.other.keys().cloned().collect::<BTreeSet<_>>();
+                for f in unknown {
+                    let Ok(path) = f.path_scoped() else {
+                        continue;
+                    };
+                    removed.remove(path);
+                    if !self.force && verify_sha1(&f.hashes.sha1, &profile_path.join(path)).await.unwrap_or(false) {
+                        plan.keep.insert(path.to_owned());
+                    } else {
+                        if self.offline {
+                            missing.push(format!("Modpack file `{}`", path.display()));
+                        }
+                        plan.download.push(PlannedFile {
+                            file: path.to_owned(),
+                            id: None,
+                            title: path.display().to_string(),
+                            length: f.file_size,
+                        });
+                    }
+                }
+                plan.delete.extend(removed);
+            }
+            if plan.install_overrides {
+                let mut removed = plan.lockfile.pack.as_ref().map(|lp| lp.overrides.clone()).unwrap_or_default();
+                pack.visit_overrides(|path, _| {
+                    if let Some(sha1) = removed.remove(path) {
+                        if let Ok(false) = verify_sha1_sync(&sha1, &profile_path.join(path)) {
+                            plan.backup.insert(path.to_owned());
+                        }
+                    }
+                    plan.extract.insert(path.to_owned());
+                });
+                // Modified overrides are kept
+                plan.delete.extend(
+                    removed
+                        .into_iter()
+                        .filter(|(path, sha1)| verify_sha1_sync(sha1, &profile_path.join(path)).unwrap_or(true))
+                        .map(|(path, _)| path),
+                );
+            }
+        }
+
+        // Nothing being installed is deleted
+        let installing = plan.download.iter().chain(&plan.copy).map(|f| &f.file);
+        for p in installing.chain(&plan.keep).chain(&plan.extract) {
+            plan.delete.remove(p);
+        }
+        // Broken links to the cache are still deleted
+        plan.delete.retain(|p| profile_path.join(p).symlink_metadata().is_ok());
+        missing
+    }
+}
+
+/// The sha1 of the lockfile in `profile_path`, used to tell if it changed
+pub(super) async fn lockfile_sha1(profile_path: &Path) -> Option<String> {
+    hash::sha1_file(&LockFile::file_path(profile_path)).await.ok()
+}
+
+/// The currently installed version of every locked project. Mods marked for
+/// update are still installed as their outdated version
+pub(super) fn locked_versions(lockfile: &LockFile) -> HashMap<ProjectId, VersionId> {
+    lockfile
+        .pack
+        .iter()
+        .map(|lp| &**lp)
+        .chain(&lockfile.mods)
+        .chain(&lockfile.outdated)
+        .map(|lm| (lm.project().clone(), lm.id.version.clone()))
+        .collect()
+}
+
+/// Resolve the locked versions of maven artifacts, which can only be located
+/// online
+fn locked_maven(lockfile: &LockFile, resolved: &mut ResolvedMods<'_>) {
+    let pending = &mut resolved.pending;
+    resolved.versioned.retain(|pid, vid| {
+        let locked = match (pid.as_ref(), vid.as_ref()) {
+            (ProjectId::Maven(_), VersionId::Maven(_)) => {
+                { lockfile.mods.iter() }.find(|lm| lm.id.project == **pid && lm.id.version == **vid)
+            },
+            _ => None,
+        };
+        if let Some(lm) = locked {
+            pending.replace(Version::from(lm).into());
+        }
+        locked.is_none()
+    });
+}
+
+/// Replace the sha1 of every `pending` version with the sha1 it was locked
+/// with, since cached metadata may have come from anywhere. Versions that
+/// aren't locked are left without a sha1, so they are never taken from the
+/// cache
+fn pin_locked_sha1(lockfile: &LockFile, pending: &mut VersionSet) {
+    *pending = { take(pending).into_iter() }
+        .map(|v| {
+            let mut v = v.into_inner();
+            // Substitutes are identical to the version they replace
+            let locked = { lockfile.mods.iter() }.find(|lm| {
+                [Some(&lm.id), lm.substitute.as_ref()]
+                    .into_iter()
+                    .flatten()
+                    .any(|id| id.project == v.project_id && id.version == v.id)
+            });
+            v.sha1 = locked.map(|lm| lm.sha1.clone()).filter(|sha1| !sha1.is_empty());
+            v.into()
+        })
+        .collect();
+}
+
+/// Compare the `previous` locked versions with the versions that will be locked
+/// after installing `plan`
+fn lock_changes(mut previous: HashMap<ProjectId, VersionId>, plan: &InstallPlan) -> Vec<LockChange> {
+    let pending = plan.pending.iter().map(|v| {
+        // Substitutes are locked as the version they replace
+        let v = plan.substitutes.get(&v.project_id).unwrap_or(v);
+        (&v.project_id, &v.id)
+    });
+    let mut changes = plan
+        .lockfile
+        .pack
+        .iter()
+        .map(|lp| &**lp)
+        .chain(&plan.installed)
+        .chain(&plan.manual)
+        .map(|lm| (lm.project(), &lm.id.version))
+        .chain(pending)
+        .filter_map(|(project, version)| {
+            let from = previous.remove(project);
+            (from.as_ref() != Some(version)).then(|| LockChange {
+                project: project.clone(),
+                from,
+                to: Some(version.clone()),
+            })
+        })
+        .collect::<Vec<_>>();
+    changes.extend(previous.into_iter().map(|(project, from)| LockChange {
+        project,
+        from: Some(from),
+        to: None,
+    }));
+    changes.sort_unstable_by(|a, b| a.project.cmp(&b.project));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(profile_path: &PathAbsolute) -> InstallPlan {
+        InstallPlan {
+            download: vec![],
+            copy: vec![],
+            keep: BTreeSet::new(),
+            delete: BTreeSet::new(),
+            extract: BTreeSet::new(),
+            backup: BTreeSet::new(),
+            changes: vec![],
+            profile_path: profile_path.clone(),
+            lockfile_sha1: None,
+            game_version: "1.21.1".to_owned(),
+            loader: ModLoader::Fabric,
+            lockfile: LockFile::default(),
+            pack: None,
+            installed: vec![],
+            manual: vec![],
+            pending: VersionSet::new(),
+            substitutes: Substitutes::new(),
+            deps: HashSet::new(),
+            pack_mods: HashSet::new(),
+            remove: BTreeSet::new(),
+            install_overrides: false,
+            strategy: InstallStrategy::default(),
+        }
+    }
+
+    fn scoped(path: &str) -> PathScoped {
+        PathScoped::new(path).unwrap()
+    }
+
+    #[test]
+    fn changes_from_previous() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut plan = plan(&PathAbsolute::new(dir.path()).unwrap());
+        plan.installed.push(Version::stub("kept", "kept1").into());
+        plan.pending.replace(Version::stub("updated", "updated2").into());
+        plan.pending.replace(Version::stub("added", "added1").into());
+        // Substitutes are locked as the version they replace
+        plan.pending.replace(Version::stub("sub", "sub1").into());
+        plan.substitutes
+            .insert(ProjectId::Modrinth("sub".to_owned()), Version::stub("denied", "denied1"));
+
+        let id = |id: &str| VersionId::Modrinth(id.to_owned());
+        let project = |id: &str| ProjectId::Modrinth(id.to_owned());
+        let previous = HashMap::from([
+            (project("kept"), id("kept1")),
+            (project("updated"), id("updated1")),
+            (project("removed"), id("removed1")),
+            (project("denied"), id("denied1")),
+        ]);
+        let change = |p: &str, from: Option<&str>, to: Option<&str>| LockChange {
+            project: project(p),
+            from: from.map(id),
+            to: to.map(id),
+        };
+        assert_eq!(lock_changes(previous, &plan), [
+            change("added", None, Some("added1")),
+            change("removed", Some("removed1"), None),
+            change("updated", Some("updated1"), Some("updated2")),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn describe_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile_path = PathAbsolute::new(dir.path()).unwrap();
+        std::fs::create_dir(dir.path().join("mods")).unwrap();
+        for file in ["mods/old.jar", "mods/new.jar", "mods/kept.jar"] {
+            std::fs::write(dir.path().join(file), file).unwrap();
+        }
+
+        let mut plan = plan(&profile_path);
+        plan.installed.push(LockedMod {
+            file: scoped("mods/kept.jar"),
+            ..Version::stub("kept", "kept").into()
+        });
+        plan.pending.replace(Version::stub("new", "new").into());
+        plan.remove = ["mods/old.jar", "mods/new.jar", "mods/kept.jar", "mods/missing.jar"]
+            .map(scoped)
+            .into();
+
+        let manager = ProfileManager {
+            no_cache: true,
+            ..ProfileManager::new()
+        };
+        assert!(manager.describe(&mut plan).await.is_empty());
+        assert_eq!(plan.download.iter().map(|f| &f.file).collect::<Vec<_>>(), [&scoped("mods/new.jar")]);
+        assert!(plan.copy.is_empty());
+        assert_eq!(plan.keep, [scoped("mods/kept.jar")].into());
+        // Files being installed or kept, and files already gone, aren't deleted
+        assert_eq!(plan.delete, [scoped("mods/old.jar")].into());
+    }
+
+    #[test]
+    fn offline_trusts_lockfile() {
+        let maven = Version {
+            id: VersionId::Maven("1.0".to_owned()),
+            project_id: ProjectId::Maven("com.example:lib@https://maven.example.com".parse().unwrap()),
+            sha1: Some("aaaa".to_owned()),
+            ..Version::stub("lib", "lib-1.0")
+        };
+        let mut lockfile = LockFile::default();
+        lockfile.mods.push(LockedMod::from(Version {
+            sha1: Some("bbbb".to_owned()),
+            ..Version::stub("mod", "mod1")
+        }));
+        lockfile.mods.push(maven.clone().into());
+
+        let mut resolved = ResolvedMods::default();
+        resolved
+            .versioned
+            .insert(Cow::Owned(maven.project_id.clone()), Cow::Owned(maven.id.clone()));
+        // Cached metadata with a different sha1
+        resolved.pending.replace(
+            Version {
+                sha1: Some("cccc".to_owned()),
+                ..Version::stub("mod", "mod1")
+            }
+            .into(),
+        );
+        resolved.pending.replace(Version::stub("unlocked", "unlocked1").into());
+
+        locked_maven(&lockfile, &mut resolved);
+        assert!(resolved.versioned.is_empty());
+        pin_locked_sha1(&lockfile, &mut resolved.pending);
+        let sha1 = |project: &ProjectId| resolved.pending.get(project).unwrap().sha1.clone();
+        assert_eq!(sha1(&maven.project_id).as_deref(), Some("aaaa"));
+        assert_eq!(sha1(&ProjectId::Modrinth("mod".to_owned())).as_deref(), Some("bbbb"));
+        assert_eq!(sha1(&ProjectId::Modrinth("unlocked".to_owned())), None);
+    }
+}
@@ -42,7 +42,7 @@ impl ProfileManager {
         }
 
         let mut results = HashMap::new();
-        client.lookup(&paths, &mut results).await?;
+        client.lookup(&paths, self.hash_jobs(), &mut results).await?;
         let results = results
             .into_iter()
             .map(|(k, v)| {
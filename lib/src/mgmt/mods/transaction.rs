@@ -0,0 +1,289 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, anyhow};
+use tokio::fs;
+
+use crate::{
+    Result,
+    checked_types::{PathAbsolute, PathScoped},
+    config::profile,
+};
+
+const STAGING_DIR: &str = concat!(profile::consts!(FILENAME), ".staging");
+const JOURNAL: &str = "journal.json";
+
+/// Collects the changes made to a profile while installing so they can all be
+/// moved into place together once everything succeeded. Every rename made in
+/// the profile is recorded in a journal, so the previous files can be restored
+/// if committing fails, or by [`recover`](Self::recover) if the process was
+/// interrupted. Deleting the journal once every rename was made is what
+/// commits the changes, so nothing past that point is ever undone
+///
+/// When not staged, files are installed directly to the profile and nothing can
+/// be undone
+#[derive(Debug)]
+pub(super) struct Transaction {
+    profile_path: PathAbsolute,
+    staging: Option<PathAbsolute>,
+    /// Files to move into the profile, and whether to keep the replaced file
+    /// as a `.bak`
+    staged: Vec<(PathScoped, bool)>,
+    delete: BTreeSet<PathScoped>,
+    /// Renames made in the profile, in order
+    journal: Vec<(PathBuf, PathBuf)>,
+    failed: usize,
+}
+
+impl Transaction {
+    pub fn new(profile_path: &PathAbsolute, staged: bool) -> Self {
+        Self {
+            profile_path: profile_path.clone(),
+            staging: staged.then(|| profile_path.join(STAGING_DIR)),
+            staged: vec![],
+            delete: BTreeSet::new(),
+            journal: vec![],
+            failed: 0,
+        }
+    }
+
+    /// Restore the files of an apply to `profile_path` that was interrupted
+    /// before it was committed, and discard what's left of one that was
+    /// interrupted after. Returns `true` if anything was restored
+    pub async fn recover(profile_path: &PathAbsolute) -> Result<bool> {
+        let staging = profile_path.join(STAGING_DIR);
+        if !staging.exists() {
+            return Ok(false);
+        }
+        let journal = match fs::read(staging.join(JOURNAL)).await {
+            Ok(json) => serde_json::from_slice(&json).context("Invalid apply journal")?,
+            Err(_) => vec![],
+        };
+        let restored = !journal.is_empty();
+        let mut tx = Self::new(profile_path, true);
+        tx.journal = journal;
+        tx.rollback().await?;
+        Ok(restored)
+    }
+
+    #[inline]
+    pub fn is_staged(&self) -> bool {
+        self.staging.is_some()
+    }
+
+    /// The directory new files are written to before they are moved into the
+    /// profile
+    pub fn root(&self) -> PathAbsolute {
+        match &self.staging {
+            Some(staging) => staging.join("new"),
+            None => self.profile_path.clone(),
+        }
+    }
+
+    /// Where `file` should be written to be installed at `file` in the profile
+    pub fn target(&self, file: impl AsRef<Path>) -> PathAbsolute {
+        self.root().join(file)
+    }
+
+    /// Install the [target](Self::target) of `file` to the profile on
+    /// [commit](Self::commit). If `backup`, the replaced file is kept with a
+    /// `.bak` extension
+    pub fn stage(&mut self, file: PathScoped, backup: bool) {
+        // Several projects may install the same file, which is only moved once
+        if self.is_staged() && !self.staged.iter().any(|(f, _)| *f == file) {
+            self.staged.push((file, backup));
+        }
+    }
+
+    /// Delete `files` from the profile on [commit](Self::commit)
+    pub fn delete(&mut self, files: impl IntoIterator<Item = PathScoped>) {
+        self.delete.extend(files);
+    }
+
+    /// Record that a file failed to install
+    pub fn fail(&mut self) {
+        self.failed += 1;
+    }
+
+    /// The number of files that failed to install
+    pub fn failed(&self) -> usize {
+        self.failed
+    }
+
+    /// Move all staged files into the profile and delete the old files, then
+    /// commit by deleting the journal. Returns the files that were deleted
+    pub async fn commit(&mut self) -> Result<Vec<PathScoped>> {
+        let Some(staging) = self.staging.clone() else {
+            return Ok(vec![]);
+        };
+        fs::create_dir_all(&staging).await?;
+        let old = staging.join("old");
+        for (file, backup) in std::mem::take(&mut self.staged) {
+            let dest = self.profile_path.join(&file);
+            if fs::symlink_metadata(&dest).await.is_ok() {
+                let replaced = if backup {
+                    let mut bak = dest.to_path_buf();
+                    bak.as_mut_os_string().push(".bak");
+                    bak
+                } else {
+                    old.join(&file).into()
+                };
+                self.rename(dest.to_path_buf(), replaced).await?;
+            }
+            self.rename(staging.join("new").join(&file).into(), dest.into()).await?;
+        }
+
+        let mut deleted = vec![];
+        for file in std::mem::take(&mut self.delete) {
+            let path = self.profile_path.join(&file);
+            if fs::symlink_metadata(&path).await.is_ok() {
+                self.rename(path.into(), old.join(&file).into())
+                    .await
+                    .with_context(|| format!("Failed to delete file `{}`", file.display()))?;
+                deleted.push(file);
+            }
+        }
+
+        match fs::remove_file(staging.join(JOURNAL)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => self.journal.clear(),
+        }
+        Ok(deleted)
+    }
+
+    /// Undo every change made to the profile and discard the staged files
+    pub async fn rollback(&mut self) -> Result<()> {
+        let Some(staging) = &self.staging else {
+            return Ok(());
+        };
+        while let Some((from, to)) = self.journal.last() {
+            // Not renamed if interrupted right after journaling, or the rename failed
+            let pending = fs::symlink_metadata(to).await.is_err();
+            if !pending {
+                if let Err(e) = fs::rename(to, from).await {
+                    self.save_journal().await?;
+                    return Err(anyhow!(e)
+                        .context(format!(
+                            "Failed to restore `{}`. The profile may need repaired manually",
+                            from.display()
+                        ))
+                        .into());
+                }
+            }
+            self.journal.pop();
+        }
+        fs::remove_dir_all(staging).await?;
+        Ok(())
+    }
+
+    /// Discard the staged files and replaced files once all changes are final
+    pub async fn finish(self) {
+        if let Some(staging) = &self.staging {
+            let _ = fs::remove_dir_all(staging).await;
+        }
+    }
+
+    /// Journaled before renaming so an interrupted rename is still undone
+    async fn rename(&mut self, from: PathBuf, to: PathBuf) -> Result<()> {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        self.journal.push((from, to));
+        self.save_journal().await?;
+        let (from, to) = self.journal.last().expect("entry was just added");
+        if let Err(e) = fs::rename(from, to).await {
+            self.journal.pop();
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Replaced atomically so an interrupted write never loses the journal
+    async fn save_journal(&self) -> Result<()> {
+        if let Some(staging) = &self.staging {
+            let path = staging.join(JOURNAL);
+            let tmp = path.with_extension("tmp");
+            fs::write(&tmp, serde_json::to_vec(&self.journal).map_err(anyhow::Error::new)?).await?;
+            fs::rename(tmp, path).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scoped(path: &str) -> PathScoped {
+        PathScoped::new(path).unwrap()
+    }
+
+    /// A profile with an installed `mods/a.jar`, and a transaction replacing it
+    /// with a new `mods/a.jar` and `mods/b.jar`
+    async fn staged() -> (tempfile::TempDir, PathAbsolute, Transaction) {
+        let dir = tempfile::tempdir().unwrap();
+        let profile_path = PathAbsolute::new(dir.path()).unwrap();
+        std::fs::create_dir(dir.path().join("mods")).unwrap();
+        std::fs::write(dir.path().join("mods/a.jar"), "old").unwrap();
+
+        let mut tx = Transaction::new(&profile_path, true);
+        for file in ["mods/a.jar", "mods/b.jar"] {
+            let target = tx.target(file);
+            fs::create_dir_all(target.parent().unwrap()).await.unwrap();
+            fs::write(target, "new").await.unwrap();
+            tx.stage(scoped(file), false);
+        }
+        (dir, profile_path, tx)
+    }
+
+    fn read(profile_path: &PathAbsolute, file: &str) -> Option<String> {
+        std::fs::read_to_string(profile_path.join(file)).ok()
+    }
+
+    #[tokio::test]
+    async fn failed_staging() {
+        let (_dir, profile_path, mut tx) = staged().await;
+        tx.fail();
+        assert_eq!(tx.failed(), 1);
+
+        tx.rollback().await.unwrap();
+        assert_eq!(read(&profile_path, "mods/a.jar").as_deref(), Some("old"));
+        assert_eq!(read(&profile_path, "mods/b.jar"), None);
+        assert!(!profile_path.join(STAGING_DIR).exists());
+    }
+
+    #[tokio::test]
+    async fn crash_before_commit() {
+        let (_dir, profile_path, mut tx) = staged().await;
+        // Interrupted after replacing `a.jar`, since `c.jar` was never written
+        tx.stage(scoped("mods/c.jar"), false);
+        tx.delete([scoped("mods/a.jar")]);
+        assert!(tx.commit().await.is_err());
+        assert_eq!(read(&profile_path, "mods/a.jar").as_deref(), Some("new"));
+        drop(tx);
+
+        assert!(Transaction::recover(&profile_path).await.unwrap());
+        assert_eq!(read(&profile_path, "mods/a.jar").as_deref(), Some("old"));
+        assert_eq!(read(&profile_path, "mods/b.jar"), None);
+        assert!(!profile_path.join(STAGING_DIR).exists());
+    }
+
+    #[tokio::test]
+    async fn crash_after_commit() {
+        let (_dir, profile_path, mut tx) = staged().await;
+        std::fs::write(profile_path.join("mods/old.jar"), "old").unwrap();
+        tx.delete([scoped("mods/old.jar")]);
+        assert_eq!(tx.commit().await.unwrap(), [scoped("mods/old.jar")]);
+        // Interrupted before the staging directory was removed
+        drop(tx);
+        assert!(profile_path.join(STAGING_DIR).exists());
+
+        assert!(!Transaction::recover(&profile_path).await.unwrap());
+        assert_eq!(read(&profile_path, "mods/a.jar").as_deref(), Some("new"));
+        assert_eq!(read(&profile_path, "mods/b.jar").as_deref(), Some("new"));
+        assert_eq!(read(&profile_path, "mods/old.jar"), None);
+        assert!(!profile_path.join(STAGING_DIR).exists());
+    }
+}
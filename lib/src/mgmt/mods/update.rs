@@ -1,11 +1,20 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use async_scoped::TokioScope;
+use itertools::Itertools;
 
 use crate::{
     Client, ProfileManager, Result,
     checked_types::PathScoped,
-    client::schema::{ProjectId, ProjectIdSvcType, VersionId},
+    client::schema::{ProjectId, ProjectIdSvcType, ReleaseChannel, VersionId},
     config::{Profile, VersionedProject, profile::ProfileData},
-    mgmt::lockfile::{LockFile, LockedMod, LockedPack},
+    mgmt::{
+        events::EventSouce,
+        lockfile::{LockFile, LockedMod, LockedPack, ModOrigin},
+    },
 };
 
 
@@ -23,23 +32,88 @@ pub struct UpdateInfo {
     pub project: ProjectId,
     pub from: (VersionId, PathScoped),
     pub to: (VersionId, PathScoped),
+    /// The [release channel](ReleaseChannel) of the `to` version
+    pub channel: ReleaseChannel,
+}
+
+impl UpdateInfo {
+    fn new(from: &LockedMod, to: &LockedMod) -> Self {
+        Self {
+            project: from.project().clone(),
+            from: (from.id.version.clone(), from.file.clone()),
+            to: (to.id.version.clone(), to.file.clone()),
+            channel: to.channel,
+        }
+    }
 }
 
 impl ProfileManager {
     /// Updates any installed profile mods without an explicit version to their
-    /// latest compatible version
+    /// latest compatible version in the allowed [release
+    /// channel](ProfileData::channel_for), including versions for other game
+    /// versions allowed by their [policy](ProfileData::compat_for). Mods with a
+    /// [constraint](ProfileData::constraint_for) are moved to the latest
+    /// version satisfying it, even if older than the installed one
     pub async fn update(&self, client: &Client, profile: &Profile, ids: &[&dyn ProjectIdSvcType]) -> Result<Vec<UpdateInfo>> {
         let profile_path = profile.path();
         let mut lockfile = LockFile::load(profile_path).await?;
+        let updates = self.find_updates(client, profile.data().await?, &lockfile, ids).await?;
+        if updates.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut updated = Vec::with_capacity(updates.len());
+        lockfile.outdated.reserve(updates.len());
+        for (i, ulm) in updates {
+            let lm = get_mod!(lockfile, i, mut);
+            updated.push(UpdateInfo::new(lm, &ulm));
+            lockfile.outdated.push(core::mem::replace(lm, ulm));
+        }
+
+        lockfile.sort();
+        lockfile.save(profile_path).await?;
+
+        Ok(updated)
+    }
+
+    /// Find the same updates as [`update`](Self::update) without marking
+    /// anything as outdated. The lockfile is left unchanged
+    pub async fn check_updates(&self, client: &Client, profile: &Profile, ids: &[&dyn ProjectIdSvcType]) -> Result<Vec<UpdateInfo>> {
+        let lockfile = LockFile::load(profile.path()).await?;
+        self.check_lockfile(client, profile.data().await?, &lockfile, ids).await
+    }
+
+    async fn check_lockfile(
+        &self,
+        client: &Client,
+        data: &ProfileData,
+        lockfile: &LockFile,
+        ids: &[&dyn ProjectIdSvcType],
+    ) -> Result<Vec<UpdateInfo>> {
+        let updates = self.find_updates(client, data, lockfile, ids).await?;
+        Ok(updates
+            .iter()
+            .map(|(i, ulm)| UpdateInfo::new(get_mod!(lockfile, *i), ulm))
+            .collect())
+    }
+
+    /// The newer versions of the updatable mods in `lockfile`, by their index
+    /// as returned from [`get_updatable`]
+    async fn find_updates(
+        &self,
+        client: &Client,
+        data: &ProfileData,
+        lockfile: &LockFile,
+        ids: &[&dyn ProjectIdSvcType],
+    ) -> Result<Vec<(usize, LockedMod)>> {
         if lockfile.mods.is_empty() && lockfile.pack.is_none() {
             return Ok(vec![]);
         }
 
-        let data = profile.data().await?;
         let pending = {
             let mut pending = get_updatable(data, lockfile.pack.as_ref(), &lockfile.mods);
             if !ids.is_empty() {
-                pending.retain(|&pid, _| ids.iter().any(|id| pid == id));
+                pending.retain(|pid, _| ids.iter().any(|id| pid == id));
             }
             pending
         };
@@ -47,41 +121,61 @@ impl ProfileManager {
             return Ok(vec![]);
         }
 
-        let mut updated = vec![];
-        let updates = client
-            .get_updates(
-                &data.game_version,
-                data.loader,
-                &pending.values().map(|&i| get_mod!(lockfile, i)).collect::<Vec<_>>(),
-            )
-            .await?
-            .into_iter()
-            .map(|lm| (pending[lm.project()], lm))
-            .collect::<Vec<_>>();
-        lockfile.outdated.reserve(updates.len());
+        let mut updates = vec![];
+        let batched = pending.iter().filter(|(id, _)| data.constraint_for(id).is_none());
+        for (channel, mods) in batched.into_group_map_by(|(id, _)| data.channel_for(id)) {
+            let mods = mods.into_iter().map(|(_, &i)| get_mod!(lockfile, i)).collect::<Vec<_>>();
+            updates.extend(
+                client
+                    .get_updates(&data.game_version, data.loader, channel, &mods)
+                    .await?
+                    .into_iter()
+                    .map(|lm| (pending[lm.project()], lm)),
+            );
+        }
+        // Mods allowed other game versions may only have updates for those, and
+        // constrained mods need every version checked
+        let found = updates.iter().map(|(i, _)| *i).collect::<HashSet<_>>();
+        let ((), latest) = TokioScope::scope_and_block(|scope| {
+            let semaphore = Arc::new(tokio::sync::Semaphore::const_new(10));
+            for (id, &i) in pending
+                .iter()
+                .filter(|(id, i)| !found.contains(*i) && (data.constraint_for(id).is_some() || !data.compat_for(id).is_exact()))
+            {
+                let semaphore = semaphore.clone();
+                scope.spawn(async move {
+                    let _permit = semaphore.acquire().await.unwrap();
+                    let constrained = data.constraint_for(id).is_some();
+                    (i, constrained, self.latest_compatible(client, id, data).await)
+                });
+            }
+        });
+        for res in latest {
+            match res {
+                Ok((i, _, Ok(v))) => {
+                    if get_mod!(lockfile, i).version() != Some(&v.id) {
+                        updates.push((i, v.into()));
+                    }
+                },
+                Ok((_, true, Err(e))) => self.send_err(e),
+                Ok(_) => {},
+                Err(e) => self.send_err(anyhow::Error::new(e).into()),
+            }
+        }
 
-        for (i, mut ulm) in updates {
-            let lm = get_mod!(lockfile, i, mut);
+        for (i, ulm) in &mut updates {
+            let lm = get_mod!(lockfile, *i);
+            ulm.origin = lm.origin;
 
             // Keep the current subdir if update doesn't specify
             match ulm.file.parent() {
                 Some(p) if p.as_os_str().is_empty() => {
-                    ulm.file = lm.file.with_file_name(ulm.file);
+                    ulm.file = lm.file.with_file_name(&ulm.file);
                 },
                 _ => {},
             }
-            updated.push(UpdateInfo {
-                project: lm.project().clone(),
-                from: (lm.id.version.clone(), lm.file.clone()),
-                to: (ulm.id.version.clone(), ulm.file.clone()),
-            });
-            lockfile.outdated.push(core::mem::replace(lm, ulm));
         }
-
-        lockfile.sort();
-        lockfile.save(profile_path).await?;
-
-        Ok(updated)
+        Ok(updates)
     }
 
     /// Cancel the update of any outdated mods waiting to be installed
@@ -96,11 +190,7 @@ impl ProfileManager {
         let mods = get_updatable(profile.data().await?, lockfile.pack.as_ref(), &lockfile.mods);
         for prev in lockfile.outdated.drain(..) {
             let lm = get_mod!(lockfile, mods[prev.project()], mut);
-            updated.push(UpdateInfo {
-                project: lm.project().clone(),
-                from: (lm.id.version.clone(), lm.file.clone()),
-                to: (prev.id.version.clone(), prev.file.clone()),
-            });
+            updated.push(UpdateInfo::new(lm, &prev));
             *lm = prev;
         }
 
@@ -111,10 +201,11 @@ impl ProfileManager {
     }
 }
 
-/// Get the unversioned profile project ids that can be updated
+/// Get the unversioned profile project ids and installed dependencies that can
+/// be updated
 ///
 /// [`ProjectId`]`-> mods index` where the pack index = `mods.len()`
-fn get_updatable<'p, 'l>(data: &'p ProfileData, pack: Option<&'l LockedPack>, mods: &'l [LockedMod]) -> HashMap<&'p ProjectId, usize> {
+fn get_updatable(data: &ProfileData, pack: Option<&LockedPack>, mods: &[LockedMod]) -> HashMap<ProjectId, usize> {
     let mut pending = data
         .mods
         .iter()
@@ -123,6 +214,9 @@ fn get_updatable<'p, 'l>(data: &'p ProfileData, pack: Option<&'l LockedPack>, mo
     if let Some(pack) = data.modpack.as_ref().filter(|p| p.version().is_none()) {
         pending.insert(pack.project(), None);
     }
+    for m in mods.iter().filter(|m| m.origin == ModOrigin::Dependency) {
+        pending.entry(m.project()).or_default();
+    }
 
     // Set values to installed projects
     if let Some(pack) = pack {
@@ -137,5 +231,52 @@ fn get_updatable<'p, 'l>(data: &'p ProfileData, pack: Option<&'l LockedPack>, mo
     }
 
     // Remove any projects that aren't installed and unwrap
-    pending.into_iter().filter_map(|(k, v)| v.map(|v| (k, v))).collect()
+    pending.into_iter().filter_map(|(k, v)| v.map(|v| (k.clone(), v))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        PathAbsolute,
+        client::schema::Version,
+        config::{Mod, ModLoader},
+    };
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn check_updates_keeps_lockfile() {
+        let dir = tempfile::tempdir().unwrap();
+        let (old, new) = (Version::stub("a", "a1"), Version {
+            date: "2024-06-01T00:00:00Z".to_owned(),
+            ..Version::stub("a", "a2")
+        });
+        let client = Client::offline_stub(dir.path(), &[old.clone(), new]).await;
+
+        // Constrained mods are checked against every cached version
+        let data = ProfileData {
+            game_version: "1.21.1".to_owned(),
+            loader: ModLoader::Fabric,
+            mods: vec![Mod {
+                constraint: Some("until:2024-12-31".parse().unwrap()),
+                ..Mod::stub(old.project_id.clone(), "a")
+            }],
+            ..ProfileData::default()
+        };
+        let profile = Profile::with_data("a".to_owned(), PathAbsolute::new(dir.path().join("profile")).unwrap(), data);
+        let lockfile = LockFile {
+            game_version: "1.21.1".to_owned(),
+            loader: ModLoader::Fabric,
+            mods: vec![old.into()],
+            ..LockFile::default()
+        };
+        lockfile.save(profile.path()).await.unwrap();
+        let lock_path = LockFile::file_path(profile.path());
+        let saved = std::fs::read(&lock_path).unwrap();
+
+        let updates = ProfileManager::new().check_updates(&client, &profile, &[]).await.unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].to.0, VersionId::Modrinth("a2".to_owned()));
+        assert_eq!(std::fs::read(&lock_path).unwrap(), saved);
+        assert!(LockFile::load(profile.path()).await.unwrap().outdated.is_empty());
+    }
 }
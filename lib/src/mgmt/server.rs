@@ -4,6 +4,7 @@
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, anyhow};
+use url::Url;
 
 use super::{
     ProfileManager, cache,
@@ -49,6 +50,19 @@ impl ProfileManager {
     }
 }
 
+/// Join `path` to the `base` url if set, otherwise to `default`. Any path in
+/// `base` is kept even without a trailing slash
+fn endpoint(base: Option<&Url>, default: &str, path: &str) -> crate::Result<Url> {
+    let mut base = match base {
+        Some(base) => base.clone(),
+        None => default.parse().map_err(anyhow::Error::new)?,
+    };
+    if !base.path().ends_with('/') {
+        base.set_path(&format!("{}/", base.path()));
+    }
+    Ok(base.join(path).map_err(anyhow::Error::new)?)
+}
+
 async fn install_file(
     mngr: &ProfileManager,
     loader: Option<ModLoader>,
@@ -129,3 +143,22 @@ struct InstallArgs<'a> {
     install_dir: &'a Path,
     version: Version<'a>,
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_keeps_base_path() {
+        let base: Url = "http://localhost/releases".parse().unwrap();
+        assert_eq!(
+            endpoint(Some(&base), "https://unused", "a/b.xml").unwrap().as_str(),
+            "http://localhost/releases/a/b.xml"
+        );
+        assert_eq!(
+            endpoint(None, "https://meta.test", "v2/").unwrap().as_str(),
+            "https://meta.test/v2/"
+        );
+    }
+}
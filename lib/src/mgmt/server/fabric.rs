@@ -2,7 +2,6 @@ use std::{
     borrow::Cow,
     hash::{DefaultHasher, Hash, Hasher},
     path::PathBuf,
-    sync::LazyLock,
 };
 
 use anyhow::anyhow;
@@ -19,13 +18,9 @@ use crate::{
 };
 
 
-macro_rules! fabric_url {
-    ($path:literal) => {
-        concat!("https://meta.fabricmc.net/v2/versions/", $path).parse().unwrap()
-    };
-}
-static INSTALLER_URL: LazyLock<Url> = LazyLock::new(|| fabric_url!("installer/"));
-static LOADER_URL: LazyLock<Url> = LazyLock::new(|| fabric_url!("loader/"));
+const DEFAULT_META: &str = "https://meta.fabricmc.net";
+const INSTALLER_PATH: &str = "v2/versions/installer/";
+const LOADER_PATH: &str = "v2/versions/loader/";
 
 
 pub async fn install(
@@ -35,7 +30,7 @@ pub async fn install(
         version,
     }: super::InstallArgs<'_>,
 ) -> crate::Result<PathBuf> {
-    let meta = installer_meta(version).await?;
+    let meta = installer_meta(mngr.http(), mngr.endpoints.fabric_meta.as_ref(), version).await?;
     let file = format!(
         "fabric-server-mc.{}-loader.{}-launcher.{}.jar",
         meta.mc_version, meta.version, meta.installer
@@ -43,15 +38,20 @@ pub async fn install(
     super::install_file(mngr, Some(ModLoader::Fabric), install_dir, &file, &meta).await
 }
 
-async fn installer_meta(version: super::Version<'_>) -> crate::Result<FabricServer<'_>> {
+async fn installer_meta<'a>(client: &Client, base: Option<&Url>, version: super::Version<'a>) -> crate::Result<FabricServer<'a>> {
     use super::Version::*;
-    let client = Client::builder().build()?;
-
-    let LatestVersion(VersionMeta { version: installer, .. }) = client.get(INSTALLER_URL.clone()).send().await?.json().await?;
+    let loader_url = super::endpoint(base, DEFAULT_META, LOADER_PATH)?;
+
+    let LatestVersion(VersionMeta { version: installer, .. }) = client
+        .get(super::endpoint(base, DEFAULT_META, INSTALLER_PATH)?)
+        .send()
+        .await?
+        .json()
+        .await?;
     let (mc_version, version) = match version {
         Latest(mc_version) => (
             mc_version,
-            { client.get(LOADER_URL.clone()).send().await?.json::<LatestVersion>().await?.0 }
+            { client.get(loader_url.clone()).send().await?.json::<LatestVersion>().await?.0 }
                 .version
                 .into(),
         ),
@@ -61,7 +61,7 @@ async fn installer_meta(version: super::Version<'_>) -> crate::Result<FabricServ
             .ok_or_else(|| anyhow!("Invalid exact fabric server version: {v}"))?,
     };
 
-    let url = LOADER_URL
+    let url = loader_url
         .join(&format!("{mc_version}/{version}/{installer}/server/jar"))
         .map_err(anyhow::Error::new)?;
 
@@ -1,6 +1,5 @@
-use std::{path::PathBuf, sync::LazyLock};
+use std::path::PathBuf;
 
-use reqwest::Client;
 use url::Url;
 
 use super::maven;
@@ -8,11 +7,8 @@ use crate::{config::ModLoader, mgmt::download::Downloadable};
 
 
 const NAME: &str = "Forge";
-static META_URL: LazyLock<Url> = LazyLock::new(|| {
-    "https://maven.minecraftforge.net/net/minecraftforge/forge/maven-metadata.xml"
-        .parse()
-        .unwrap()
-});
+const DEFAULT_MAVEN: &str = "https://maven.minecraftforge.net";
+const META_PATH: &str = "net/minecraftforge/forge/maven-metadata.xml";
 
 
 fn version_file(version: &str) -> String {
@@ -27,15 +23,16 @@ pub async fn install(
     }: super::InstallArgs<'_>,
 ) -> crate::Result<PathBuf> {
     use super::Version::*;
-    let client = Client::builder().build()?;
+    let client = mngr.http().clone();
+    let meta_url = super::endpoint(mngr.endpoints.forge_maven.as_ref(), DEFAULT_MAVEN, META_PATH)?;
     let installer = match version {
         Latest(mc_version) => {
-            maven::latest_file(NAME, &client, &META_URL, mc_version, version_file, |v| {
+            maven::latest_file(NAME, &client, &meta_url, mc_version, version_file, |v| {
                 v.split_once('-').is_some_and(|(mc, _)| mc == mc_version)
             })
             .await
         },
-        Exact(v) => maven::exact_file(NAME, &client, &META_URL, v, version_file(v)).await,
+        Exact(v) => maven::exact_file(NAME, &client, &meta_url, v, version_file(v)).await,
     }?;
 
     let file = installer
@@ -2,28 +2,15 @@ use std::hash::{DefaultHasher, Hash, Hasher};
 
 use anyhow::{Context, anyhow};
 use reqwest::Client;
-use serde::Deserialize;
 use url::Url;
 
-use crate::mgmt::{download::Downloadable, events::DownloadId};
+pub use crate::maven::latest_version;
+use crate::{
+    maven::{Artifact, artifact},
+    mgmt::{download::Downloadable, events::DownloadId},
+};
 
 
-const SHA1_EXT: &str = ".sha1";
-
-
-fn meta_versions(xml: impl AsRef<str>) -> crate::Result<Vec<String>> {
-    let meta: Meta = quick_xml::de::from_str(xml.as_ref()).map_err(anyhow::Error::new)?;
-    Ok(meta.versioning.versions.version)
-}
-
-pub async fn latest_version(client: &Client, meta_url: &Url, filter: impl FnMut(&String) -> bool) -> crate::Result<String> {
-    meta_versions(client.get(meta_url.clone()).send().await?.text().await?)?
-        .into_iter()
-        .filter(filter)
-        .max()
-        .ok_or(anyhow!("No matching version").into())
-}
-
 pub async fn latest_file(
     name: &str,
     client: &Client,
@@ -39,17 +26,7 @@ pub async fn latest_file(
 }
 
 pub async fn exact_file(name: &str, client: &Client, meta_url: &Url, exact_version: &str, file_name: String) -> crate::Result<MavenFile> {
-    let mut url = meta_url.clone();
-
-    url.path_segments_mut().unwrap().pop().push(exact_version).push(&file_name);
-    let length = client.head(url.clone()).send().await?.content_length().unwrap_or_default();
-
-    let sha1 = {
-        let mut url = url.clone();
-        url.path_segments_mut().unwrap().pop().extend(Some(file_name + SHA1_EXT));
-        client.get(url).send().await?.text().await.ok()
-    };
-
+    let Artifact { url, length, sha1 } = artifact(client, meta_url, exact_version, &file_name).await?;
     Ok(MavenFile {
         id: {
             let mut hasher = DefaultHasher::new();
@@ -64,21 +41,6 @@ pub async fn exact_file(name: &str, client: &Client, meta_url: &Url, exact_versi
 }
 
 
-#[derive(Debug, Deserialize)]
-struct Meta {
-    versioning: Versioning,
-}
-#[derive(Debug, Deserialize)]
-struct Versioning {
-    versions: Versions,
-}
-#[derive(Debug, Deserialize)]
-struct Versions {
-    #[serde(default)]
-    version: Vec<String>,
-}
-
-
 #[derive(Debug)]
 pub(super) struct MavenFile {
     id: DownloadId,
@@ -1,6 +1,5 @@
-use std::{borrow::Cow, path::PathBuf, sync::LazyLock};
+use std::{borrow::Cow, path::PathBuf};
 
-use reqwest::Client;
 use url::Url;
 
 use super::maven;
@@ -8,11 +7,8 @@ use crate::{config::ModLoader, mgmt::download::Downloadable};
 
 
 const NAME: &str = "NeoForge";
-static META_URL: LazyLock<Url> = LazyLock::new(|| {
-    "https://maven.neoforged.net/releases/net/neoforged/neoforge/maven-metadata.xml"
-        .parse()
-        .unwrap()
-});
+const DEFAULT_MAVEN: &str = "https://maven.neoforged.net/releases";
+const META_PATH: &str = "net/neoforged/neoforge/maven-metadata.xml";
 
 
 fn version_file(version: &str) -> String {
@@ -27,7 +23,8 @@ pub async fn install(
     }: super::InstallArgs<'_>,
 ) -> crate::Result<PathBuf> {
     use super::Version::*;
-    let client = Client::builder().build()?;
+    let client = mngr.http().clone();
+    let meta_url = super::endpoint(mngr.endpoints.neoforge_maven.as_ref(), DEFAULT_MAVEN, META_PATH)?;
     let installer = match version {
         Latest(mc_version) => {
             // https://docs.neoforged.net/docs/gettingstarted/versioning#neoforge
@@ -35,12 +32,12 @@ pub async fn install(
                 .strip_prefix("1.")
                 .map(Cow::from)
                 .map_or_else(|| mc_version.into(), |v| if v.contains('.') { v } else { format!("{v}.0").into() });
-            maven::latest_file(NAME, &client, &META_URL, mc_version, version_file, move |v| {
+            maven::latest_file(NAME, &client, &meta_url, mc_version, version_file, move |v| {
                 v.rsplit_once('.').is_some_and(|(mc, _)| mc == major_minor)
             })
             .await
         },
-        Exact(v) => maven::exact_file(NAME, &client, &META_URL, v, version_file(v)).await,
+        Exact(v) => maven::exact_file(NAME, &client, &meta_url, v, version_file(v)).await,
     }?;
 
     let file = installer
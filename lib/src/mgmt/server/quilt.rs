@@ -1,7 +1,6 @@
-use std::{borrow::Cow, ffi::OsString, path::PathBuf, sync::LazyLock};
+use std::{borrow::Cow, ffi::OsString, path::PathBuf};
 
 use anyhow::anyhow;
-use reqwest::Client;
 use url::Url;
 
 use super::maven;
@@ -9,16 +8,9 @@ use crate::{config::ModLoader, mgmt::download::Downloadable};
 
 
 const NAME: &str = "Quilt";
-static INSTALLER_URL: LazyLock<Url> = LazyLock::new(|| {
-    "https://maven.quiltmc.org/repository/release/org/quiltmc/quilt-installer/maven-metadata.xml"
-        .parse()
-        .unwrap()
-});
-static LOADER_URL: LazyLock<Url> = LazyLock::new(|| {
-    "https://maven.quiltmc.org/repository/release/org/quiltmc/quilt-loader/maven-metadata.xml"
-        .parse()
-        .unwrap()
-});
+const DEFAULT_MAVEN: &str = "https://maven.quiltmc.org/repository/release";
+const INSTALLER_PATH: &str = "org/quiltmc/quilt-installer/maven-metadata.xml";
+const LOADER_PATH: &str = "org/quiltmc/quilt-loader/maven-metadata.xml";
 
 
 pub async fn install(
@@ -29,11 +21,12 @@ pub async fn install(
     }: super::InstallArgs<'_>,
 ) -> crate::Result<PathBuf> {
     use super::Version::*;
-    let client = Client::builder().build()?;
+    let client = mngr.http().clone();
+    let base = mngr.endpoints.quilt_maven.as_ref();
     let (mc_version, version) = match version {
         Latest(mc_version) => (
             mc_version,
-            maven::latest_version(&client, &LOADER_URL, |v| !v.contains('-'))
+            maven::latest_version(&client, &super::endpoint(base, DEFAULT_MAVEN, LOADER_PATH)?, |v| !v.contains('-'))
                 .await
                 .map(Cow::from)?,
         ),
@@ -45,7 +38,7 @@ pub async fn install(
     let installer = maven::latest_file(
         NAME,
         &client,
-        &INSTALLER_URL,
+        &super::endpoint(base, DEFAULT_MAVEN, INSTALLER_PATH)?,
         mc_version,
         |v| format!("quilt-installer-{v}.jar"),
         |_| true,
@@ -1,14 +1,14 @@
-use std::{borrow::Cow, path::PathBuf, sync::LazyLock};
+use std::{borrow::Cow, path::PathBuf};
 
 use anyhow::anyhow;
-use reqwest::Client;
 use serde::Deserialize;
 use url::Url;
 
 use crate::mgmt::download::Downloadable;
 
 
-static MANIFEST_URL: LazyLock<Url> = LazyLock::new(|| "https://launchermeta.mojang.com/mc/game/version_manifest.json".parse().unwrap());
+const DEFAULT_META: &str = "https://launchermeta.mojang.com";
+const MANIFEST_PATH: &str = "mc/game/version_manifest.json";
 
 
 pub async fn install(
@@ -24,9 +24,10 @@ pub async fn install(
         Exact(v) => v,
     };
 
-    let client = Client::builder().build()?;
+    let client = mngr.http().clone();
 
-    let Manifest { versions } = client.get(MANIFEST_URL.clone()).send().await?.json().await?;
+    let manifest_url = super::endpoint(mngr.endpoints.mojang_meta.as_ref(), DEFAULT_META, MANIFEST_PATH)?;
+    let Manifest { versions } = client.get(manifest_url).send().await?.json().await?;
     let details = versions
         .into_iter()
         .find(|v| v.id == version)
@@ -0,0 +1,91 @@
+use std::cmp::Ordering;
+
+
+/// Compare version numbers so that numeric parts are ordered by value instead
+/// of lexically, e.g. `1.10` is newer than `1.9`. A trailing qualifier makes a
+/// version older than the bare release, e.g. `1.0-rc1` is older than `1.0`
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    /// Whether the extra `part` of a longer version starts a qualifier instead
+    /// of more numbers
+    fn is_qualifier(part: &str) -> bool {
+        part.trim_start_matches(|c: char| !c.is_ascii_alphanumeric())
+            .starts_with(|c: char| c.is_ascii_alphabetic())
+    }
+
+    fn parts(mut version: &str) -> impl Iterator<Item = &str> {
+        std::iter::from_fn(move || {
+            let numeric = version.chars().next()?.is_ascii_digit();
+            let end = version.find(|c: char| c.is_ascii_digit() != numeric).unwrap_or(version.len());
+            let (part, rest) = version.split_at(end);
+            version = rest;
+            Some(part)
+        })
+    }
+
+    let (mut a, mut b) = (parts(a), parts(b));
+    loop {
+        let ord = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(b)) if is_qualifier(b) => return Ordering::Greater,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(a), None) if is_qualifier(a) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                _ => { a.bytes().map(|c| c.to_ascii_lowercase()) }.cmp(b.bytes().map(|c| c.to_ascii_lowercase())),
+            },
+        };
+        if ord.is_ne() {
+            return ord;
+        }
+    }
+}
+
+/// Match `name` against `pattern`, where `*` matches any number of characters
+/// and `?` matches exactly one
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let (pattern, name) = (pattern.chars().collect::<Vec<_>>(), name.chars().collect::<Vec<_>>());
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            },
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            },
+            _ => match backtrack {
+                // Let the last `*` consume one more character
+                Some((star, matched)) => {
+                    backtrack = Some((star, matched + 1));
+                    p = star + 1;
+                    n = matched + 1;
+                },
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_version_order() {
+        assert_eq!(compare_versions("1.10.0", "1.9.2"), Ordering::Greater);
+        assert_eq!(compare_versions("1.20.1-47.2.0", "1.20.1-47.10.0"), Ordering::Less);
+        assert_eq!(compare_versions("2.0", "2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("2.0", "2.0.1"), Ordering::Less);
+        assert_eq!(compare_versions("1.0-beta", "1.0-alpha"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0", "1.0-SNAPSHOT"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0-rc1", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0-RC1", "1.0-beta"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0-rc1", "1.0.1"), Ordering::Less);
+    }
+}
@@ -55,7 +55,7 @@ impl MiscApi<'_> {
             )
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -78,7 +78,7 @@ impl MiscApi<'_> {
             "/statistics"
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -181,7 +181,7 @@ impl NotificationsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -225,7 +225,7 @@ impl NotificationsApi<'_> {
 
         req_builder = req_builder.query(&[("ids", &params.ids)]);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -271,7 +271,7 @@ impl NotificationsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -315,7 +315,7 @@ impl NotificationsApi<'_> {
 
         req_builder = req_builder.query(&[("ids", &params.ids)]);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -361,7 +361,7 @@ impl NotificationsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -407,7 +407,7 @@ impl NotificationsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -451,7 +451,7 @@ impl NotificationsApi<'_> {
 
         req_builder = req_builder.query(&[("ids", &params.ids)]);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
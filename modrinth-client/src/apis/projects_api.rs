@@ -430,7 +430,7 @@ impl ProjectsApi<'_> {
         }
         req_builder = req_builder.json(&params.body);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -480,7 +480,7 @@ impl ProjectsApi<'_> {
         req_builder = req_builder.query(&[("ext", &params.ext)]);
         req_builder = req_builder.json(&params.body);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -506,7 +506,7 @@ impl ProjectsApi<'_> {
             )
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -555,7 +555,7 @@ impl ProjectsApi<'_> {
         }
         req_builder = req_builder.multipart(multipart_form);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -602,7 +602,7 @@ impl ProjectsApi<'_> {
 
         req_builder = req_builder.query(&[("url", &params.url)]);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -648,7 +648,7 @@ impl ProjectsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -694,7 +694,7 @@ impl ProjectsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -740,7 +740,7 @@ impl ProjectsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -767,7 +767,7 @@ impl ProjectsApi<'_> {
             )
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -793,7 +793,7 @@ impl ProjectsApi<'_> {
             )
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -817,7 +817,7 @@ impl ProjectsApi<'_> {
         );
         req_builder = req_builder.query(&[("ids", &params.ids)]);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -874,7 +874,7 @@ impl ProjectsApi<'_> {
             req_builder = req_builder.query(&[("ordering", &param_value)]);
         }
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -921,7 +921,7 @@ impl ProjectsApi<'_> {
 
         req_builder = req_builder.json(&params.editable_project);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -966,7 +966,7 @@ impl ProjectsApi<'_> {
         req_builder = req_builder.query(&[("ids", &params.ids)]);
         req_builder = req_builder.json(&params.patch_projects_body);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -991,7 +991,7 @@ impl ProjectsApi<'_> {
         );
         req_builder = req_builder.query(&[("count", &params.count)]);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -1037,7 +1037,7 @@ impl ProjectsApi<'_> {
 
         req_builder = req_builder.json(&params.schedule);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -1076,7 +1076,7 @@ impl ProjectsApi<'_> {
             req_builder = req_builder.query(&[("limit", &param_value)]);
         }
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -1121,7 +1121,7 @@ impl ProjectsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
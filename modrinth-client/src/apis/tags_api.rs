@@ -108,7 +108,7 @@ impl TagsApi<'_> {
             "/tag/category"
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -131,7 +131,7 @@ impl TagsApi<'_> {
             "/tag/donation_platform"
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -155,7 +155,7 @@ impl TagsApi<'_> {
             "/tag/license"
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -180,7 +180,7 @@ impl TagsApi<'_> {
             )
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -204,7 +204,7 @@ impl TagsApi<'_> {
             "/tag/loader"
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -227,7 +227,7 @@ impl TagsApi<'_> {
             "/tag/project_type"
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -250,7 +250,7 @@ impl TagsApi<'_> {
             "/tag/report_type"
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -273,7 +273,7 @@ impl TagsApi<'_> {
             "/tag/side_type"
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -296,7 +296,7 @@ impl TagsApi<'_> {
             "/tag/game_version"
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -201,7 +201,7 @@ impl TeamsApi<'_> {
 
         req_builder = req_builder.json(&params.user_identifier);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -248,7 +248,7 @@ impl TeamsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -275,7 +275,7 @@ impl TeamsApi<'_> {
             )
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -320,7 +320,7 @@ impl TeamsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -343,7 +343,7 @@ impl TeamsApi<'_> {
         );
         req_builder = req_builder.query(&[("ids", &params.ids)]);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -387,7 +387,7 @@ impl TeamsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -435,7 +435,7 @@ impl TeamsApi<'_> {
 
         req_builder = req_builder.json(&params.modify_team_member_body);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -482,7 +482,7 @@ impl TeamsApi<'_> {
 
         req_builder = req_builder.json(&params.user_identifier);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -220,7 +220,7 @@ impl ThreadsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -266,7 +266,7 @@ impl ThreadsApi<'_> {
             req_builder = req_builder.query(&[("count", &param_value)]);
         }
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -312,7 +312,7 @@ impl ThreadsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -356,7 +356,7 @@ impl ThreadsApi<'_> {
 
         req_builder = req_builder.query(&[("ids", &params.ids)]);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -402,7 +402,7 @@ impl ThreadsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -445,7 +445,7 @@ impl ThreadsApi<'_> {
 
         req_builder = req_builder.query(&[("ids", &params.ids)]);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -491,7 +491,7 @@ impl ThreadsApi<'_> {
 
         req_builder = req_builder.json(&params.modify_report_request);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -539,7 +539,7 @@ impl ThreadsApi<'_> {
 
         req_builder = req_builder.json(&params.thread_message_body);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -584,7 +584,7 @@ impl ThreadsApi<'_> {
 
         req_builder = req_builder.json(&params.creatable_report);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
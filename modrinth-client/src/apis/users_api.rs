@@ -228,7 +228,7 @@ impl UsersApi<'_> {
 
         req_builder = req_builder.json(&params.body);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -274,7 +274,7 @@ impl UsersApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -320,7 +320,7 @@ impl UsersApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -366,7 +366,7 @@ impl UsersApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -393,7 +393,7 @@ impl UsersApi<'_> {
             )
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -435,7 +435,7 @@ impl UsersApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -461,7 +461,7 @@ impl UsersApi<'_> {
             )
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -485,7 +485,7 @@ impl UsersApi<'_> {
         );
         req_builder = req_builder.query(&[("ids", &params.ids)]);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -530,7 +530,7 @@ impl UsersApi<'_> {
 
         req_builder = req_builder.json(&params.editable_user);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -578,7 +578,7 @@ impl UsersApi<'_> {
 
         req_builder = req_builder.query(&[("amount", &params.amount)]);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -153,7 +153,7 @@ impl VersionFilesApi<'_> {
             req_builder = req_builder.query(&[("version_id", &param_value)]);
         }
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -182,7 +182,7 @@ impl VersionFilesApi<'_> {
         req_builder = req_builder.query(&[("algorithm", &params.algorithm)]);
         req_builder = req_builder.json(&params.get_latest_version_from_hash_body);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -208,7 +208,7 @@ impl VersionFilesApi<'_> {
         );
         req_builder = req_builder.json(&params.get_latest_versions_from_hashes_body);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -238,7 +238,7 @@ impl VersionFilesApi<'_> {
             req_builder = req_builder.query(&[("multiple", &param_value)]);
         }
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -263,7 +263,7 @@ impl VersionFilesApi<'_> {
         );
         req_builder = req_builder.json(&params.hash_list);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -227,7 +227,7 @@ impl VersionsApi<'_> {
         }
         req_builder = req_builder.multipart(multipart_form);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -274,7 +274,7 @@ impl VersionsApi<'_> {
         multipart_form = multipart_form.text("data", serde_json::to_string(params.data)?);
         req_builder = req_builder.multipart(multipart_form);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -320,7 +320,7 @@ impl VersionsApi<'_> {
         }
 
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -356,7 +356,7 @@ impl VersionsApi<'_> {
             req_builder = req_builder.query(&[("featured", &param_value)]);
         }
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -382,7 +382,7 @@ impl VersionsApi<'_> {
             )
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -410,7 +410,7 @@ impl VersionsApi<'_> {
             )
         );
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -434,7 +434,7 @@ impl VersionsApi<'_> {
         );
         req_builder = req_builder.query(&[("ids", &params.ids)]);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -479,7 +479,7 @@ impl VersionsApi<'_> {
 
         req_builder = req_builder.json(&params.editable_version);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
@@ -526,7 +526,7 @@ impl VersionsApi<'_> {
 
         req_builder = req_builder.json(&params.schedule);
 
-        let resp = req_builder.send().await?;
+        let resp = self.0.execute(req_builder).await?;
 
         let status = resp.status();
         let content = resp.text().await?;
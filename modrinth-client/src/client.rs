@@ -25,11 +25,22 @@ pub struct BasicAuth {
     password: Option<String>,
 }
 
+/// Hook for customizing how every request made by an [`ApiClient`] is sent,
+/// such as adding retries or rate limiting
+pub trait Middleware: std::fmt::Debug + Send + Sync {
+    fn send<'a>(
+        &'a self,
+        client: &'a Client,
+        request: reqwest::Request,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = reqwest::Result<reqwest::Response>> + Send + 'a>>;
+}
+
 #[derive(Debug, Clone)]
 pub struct ApiClient {
     client: Client,
     server: reqwest::Url,
     pub(crate) auth: AuthData,
+    middleware: Option<std::sync::Arc<dyn Middleware>>,
 }
 
 impl ApiClient {
@@ -45,6 +56,14 @@ impl ApiClient {
         self.client.request(method, format!("{base}{path}"))
     }
 
+    /// Send `request` through the configured [`Middleware`], if any
+    pub async fn execute(&self, request: reqwest::RequestBuilder) -> reqwest::Result<reqwest::Response> {
+        match &self.middleware {
+            Some(middleware) => middleware.send(&self.client, request.build()?).await,
+            None => request.send().await,
+        }
+    }
+
     pub fn misc(&self) -> apis::MiscApi<'_> {
         apis::MiscApi(self)
     }
@@ -88,6 +107,7 @@ pub struct Builder<'a> {
     user_agent: &'a str,
     server: Option<reqwest::Url>,
     auth: Option<AuthData>,
+    middleware: Option<std::sync::Arc<dyn Middleware>>,
 }
 
 static DEFAULT_SERVER: std::sync::LazyLock<reqwest::Url> =
@@ -100,6 +120,7 @@ impl<'a> Builder<'a> {
             user_agent,
             server: None,
             auth: None,
+            middleware: None,
         }
     }
 
@@ -117,6 +138,7 @@ impl<'a> Builder<'a> {
             client,
             server: self.server.unwrap_or_else(|| DEFAULT_SERVER.clone()),
             auth: self.auth.unwrap_or_default(),
+            middleware: self.middleware,
         })
     }
 
@@ -129,4 +151,9 @@ impl<'a> Builder<'a> {
         self.auth = Some(auth);
         self
     }
+
+    pub fn middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware = Some(std::sync::Arc::new(middleware));
+        self
+    }
 }